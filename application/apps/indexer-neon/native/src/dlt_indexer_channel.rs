@@ -3,7 +3,11 @@ use crate::{
     fibex_utils::gather_fibex_data,
 };
 use crossbeam_channel as cc;
-use dlt::{fibex::FibexMetadata, filtering};
+use dlt::{
+    dlt_parse::{ParseOptions, StorageHeaderMode},
+    fibex::FibexMetadata,
+    filtering,
+};
 use indexer_base::{
     chunks::ChunkResults,
     config::{FibexConfig, IndexingConfig},
@@ -82,6 +86,7 @@ fn index_dlt_file_with_progress(
         &tx,
         shutdown_receiver,
         fibex_metadata,
+        ParseOptions::new(StorageHeaderMode::Required),
     ) {
         Err(why) => {
             error!("create_index_and_mapping_dlt: couldn't process: {}", why);
@@ -1,5 +1,6 @@
 use crate::channels::EventEmitterTask;
 use crossbeam_channel as cc;
+use dlt::dlt_parse::{ParseOptions, StorageHeaderMode};
 use failure::{err_msg, Error};
 use indexer_base::{chunks::ChunkResults, config::SectionConfig, export::export_file_line_based};
 use neon::prelude::*;
@@ -61,6 +62,7 @@ impl ExporterEventEmitter {
                     dlt_file_path,
                     destination_path,
                     sections_config,
+                    ParseOptions::new(StorageHeaderMode::Required),
                     chunk_result_sender,
                 ) {
                     Ok(_) => {}
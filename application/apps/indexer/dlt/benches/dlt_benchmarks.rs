@@ -3,7 +3,6 @@ extern crate criterion;
 extern crate dlt;
 
 // use dlt::dlt::*;
-use bytes::BytesMut;
 use criterion::Criterion;
 
 fn dlt_benchmark(c: &mut Criterion) {
@@ -66,11 +65,19 @@ fn dlt_benchmark(c: &mut Criterion) {
 }
 fn dlt_parse_benchmark(c: &mut Criterion) {
     c.bench_function("zero_termitated_string broken input", |b| {
-        let mut buf = BytesMut::with_capacity(4);
+        let mut buf = Vec::with_capacity(4);
         let broken = vec![0x41, 0, 146, 150];
         buf.extend_from_slice(&broken);
         b.iter(|| dlt::dlt_parse::dlt_zero_terminated_string(&buf, 4))
     });
+    c.bench_function("count messages", |b| {
+        let sample = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        b.iter(|| dlt::dlt_parse::count_messages(&sample))
+    });
+    c.bench_function("parse messages", |b| {
+        let sample = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        b.iter(|| dlt::dlt_parse::messages(&sample).count())
+    });
 }
 
 criterion_group!(benches, dlt_benchmark, dlt_parse_benchmark);
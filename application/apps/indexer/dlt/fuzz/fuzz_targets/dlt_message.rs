@@ -0,0 +1,53 @@
+//! parse arbitrary bytes, nothing of it may panic
+//!
+//! run with `cargo fuzz run dlt_message ../test_samples/fuzz` from the `dlt`
+//! directory, inputs that made it panic are kept in `test_samples/fuzz`
+#![no_main]
+use dlt::{
+    dlt_parse::{
+        dlt_message, dlt_message_ref, dlt_message_with_options, messages_with_resync,
+        ParseOptions, ParsedMessage, PayloadMode, StorageHeaderMode,
+    },
+    filtering::{process_filter_config, DltFilterConfig},
+};
+use libfuzzer_sys::fuzz_target;
+use std::time::{Duration, UNIX_EPOCH};
+
+fuzz_target!(|data: &[u8]| {
+    // the time range makes the filter look at the storage header timestamp
+    let time_filter = process_filter_config(DltFilterConfig {
+        time_from: Some(UNIX_EPOCH + Duration::from_secs(1)),
+        time_to: Some(UNIX_EPOCH + Duration::from_secs(u64::from(u32::MAX))),
+        ..Default::default()
+    })
+    .expect("invalid filter");
+    for mode in &[
+        StorageHeaderMode::Required,
+        StorageHeaderMode::Optional,
+        StorageHeaderMode::Absent,
+    ] {
+        if let Ok((_, ParsedMessage::Item(msg))) = dlt_message(data, None, 0, None, None, *mode) {
+            let _ = msg.to_string();
+            let _ = msg.as_bytes();
+            let _ = msg.network_trace();
+            let _ = msg.control_message();
+        }
+        let _ = dlt_message(data, Some(&time_filter), 0, None, None, *mode);
+        if let Ok((_, msg)) = dlt_message_ref(data, *mode) {
+            let _ = msg.to_owned();
+        }
+        let mut lenient = ParseOptions::new(*mode);
+        lenient.lenient = true;
+        if let Ok((_, ParsedMessage::Item(msg))) =
+            dlt_message_with_options(data, None, 0, None, None, lenient)
+        {
+            let _ = msg.to_string();
+        }
+    }
+    for resynced in messages_with_resync(data, None, None).with_payload_mode(PayloadMode::Raw) {
+        if let ParsedMessage::Item(mut msg) = resynced.message {
+            let _ = msg.decode_payload(true);
+            let _ = msg.to_string();
+        }
+    }
+});
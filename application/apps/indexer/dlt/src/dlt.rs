@@ -11,21 +11,41 @@
 // from E.S.R.Labs.
 #![allow(clippy::unit_arg)]
 
+#[cfg(any(feature = "std", test))]
 use crate::proptest_strategies::*;
-use byteorder::{BigEndian, LittleEndian};
-use bytes::{BufMut, ByteOrder, BytesMut};
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::time::Duration;
+#[cfg(feature = "std")]
 use indexer_base::error_reporter::*;
 use serde::Serialize;
-use std::{fmt, io, io::Error, rc::Rc};
+#[cfg(feature = "std")]
+use std::{fmt, io};
 
+#[cfg(any(feature = "std", test))]
 use proptest::prelude::*;
+#[cfg(any(feature = "std", test))]
 use proptest_derive::Arbitrary;
 
-use std::str;
+use core::str;
 
-use crate::fibex::FibexMetadata;
+use crate::dlt_parse::{payload_argument_ref, DltParseError};
+#[cfg(not(feature = "std"))]
+use crate::without_std::{report_warning, FibexMetadata};
+#[cfg(feature = "std")]
+use crate::{dlt_parse::dlt_non_verbose_arguments, fibex::FibexMetadata};
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Serialize, Arbitrary)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum Endianness {
     /// Little Endian
     Little,
@@ -33,13 +53,23 @@ pub enum Endianness {
     Big,
 }
 
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct DltTimeStamp {
     pub seconds: u32,
-    #[proptest(strategy = "0..=1_000_000u32")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "0..=1_000_000u32"))]
     pub microseconds: u32,
 }
 impl DltTimeStamp {
+    /// the point in time the storage header was written (seconds are since the unix epoch)
+    #[cfg(feature = "std")]
+    pub fn to_system_time(&self) -> std::time::SystemTime {
+        // the microseconds are not trusted to be below one second
+        std::time::UNIX_EPOCH
+            + std::time::Duration::from_secs(u64::from(self.seconds))
+            + std::time::Duration::from_micros(u64::from(self.microseconds))
+    }
     pub fn from_ms(ms: u64) -> Self {
         DltTimeStamp {
             seconds: (ms / 1000) as u32,
@@ -52,20 +82,56 @@ impl DltTimeStamp {
             microseconds: (us % (1000 * 1000)) as u32 * 1000 * 1000,
         }
     }
+    /// the time since the unix epoch in microseconds
+    pub fn as_us(&self) -> u64 {
+        u64::from(self.seconds) * 1000 * 1000 + u64::from(self.microseconds)
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct StorageHeader {
     pub timestamp: DltTimeStamp,
-    #[proptest(strategy = "\"[a-zA-Z 0-9]{4}\"")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "\"[a-zA-Z 0-9]{4}\""))]
     pub ecu_id: String,
 }
-trait BytesMutExt {
-    fn put_zero_terminated_string(&mut self, s: &str, max: usize);
-}
-impl BytesMutExt for BytesMut {
+/// appending of the encoded fields to the bytes of a message
+trait BufMut {
+    fn put_slice(&mut self, src: &[u8]);
+    fn put_u8(&mut self, n: u8) {
+        self.put_slice(&[n]);
+    }
+    fn put_i8(&mut self, n: i8) {
+        self.put_u8(n as u8);
+    }
+    fn put_u16<T: ByteOrder>(&mut self, n: u16) {
+        let mut buf = [0u8; 2];
+        T::write_u16(&mut buf, n);
+        self.put_slice(&buf);
+    }
+    fn put_u32<T: ByteOrder>(&mut self, n: u32) {
+        let mut buf = [0u8; 4];
+        T::write_u32(&mut buf, n);
+        self.put_slice(&buf);
+    }
+    fn put_i32<T: ByteOrder>(&mut self, n: i32) {
+        let mut buf = [0u8; 4];
+        T::write_i32(&mut buf, n);
+        self.put_slice(&buf);
+    }
+    fn put_i64<T: ByteOrder>(&mut self, n: i64) {
+        let mut buf = [0u8; 8];
+        T::write_i64(&mut buf, n);
+        self.put_slice(&buf);
+    }
+    fn put_f32<T: ByteOrder>(&mut self, n: f32) {
+        let mut buf = [0u8; 4];
+        T::write_f32(&mut buf, n);
+        self.put_slice(&buf);
+    }
     fn put_zero_terminated_string(&mut self, s: &str, max: usize) {
-        self.extend_from_slice(s.as_bytes());
+        self.put_slice(s.as_bytes());
         if max > s.len() {
             for _ in 0..(max - s.len()) {
                 self.put_u8(0x0);
@@ -73,20 +139,26 @@ impl BytesMutExt for BytesMut {
         }
     }
 }
+impl BufMut for Vec<u8> {
+    fn put_slice(&mut self, src: &[u8]) {
+        self.extend_from_slice(src);
+    }
+}
 impl StorageHeader {
     #[allow(dead_code)]
     pub fn as_bytes(self: &StorageHeader) -> Vec<u8> {
-        let mut buf = BytesMut::with_capacity(STORAGE_HEADER_LENGTH as usize);
+        let mut buf = Vec::with_capacity(STORAGE_HEADER_LENGTH as usize);
         buf.extend_from_slice(b"DLT");
         buf.put_u8(0x01);
-        buf.put_u32_le(self.timestamp.seconds);
-        buf.put_u32_le(self.timestamp.microseconds as u32);
+        buf.put_u32::<LittleEndian>(self.timestamp.seconds);
+        buf.put_u32::<LittleEndian>(self.timestamp.microseconds);
         buf.put_zero_terminated_string(&self.ecu_id[..], 4);
-        buf.to_vec()
+        buf
     }
 }
 /// The Standard Header shall be in big endian format
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct StandardHeader {
     pub version: u8,
     pub endianness: Endianness,
@@ -99,6 +171,10 @@ pub struct StandardHeader {
 }
 
 impl StandardHeader {
+    /// the protocol version (VERS bits of the header type)
+    pub fn version(&self) -> u8 {
+        self.version
+    }
     pub fn header_type_byte(&self) -> u8 {
         standard_header_type(
             self.has_extended_header,
@@ -129,6 +205,35 @@ impl StandardHeader {
         length += self.payload_length;
         length
     }
+
+    /// the ECU-ID, present if the WEID flag is set
+    pub fn ecu_id(&self) -> Option<&str> {
+        self.ecu_id.as_deref()
+    }
+
+    /// the session id, present if the WSID flag is set
+    pub fn session_id(&self) -> Option<u32> {
+        self.session_id
+    }
+
+    /// the timestamp in ticks of 0.1 ms, present if the WTMS flag is set
+    pub fn timestamp(&self) -> Option<u32> {
+        self.timestamp
+    }
+
+    /// the timestamp as time since the start of the ECU
+    pub fn timestamp_duration(&self) -> Option<Duration> {
+        self.timestamp.map(timestamp_ticks_to_duration)
+    }
+
+    pub fn message_counter(&self) -> u8 {
+        self.message_counter
+    }
+}
+
+/// convert a timestamp of the standard header (ticks of 0.1 ms) into a `Duration`
+pub fn timestamp_ticks_to_duration(ticks: u32) -> Duration {
+    Duration::from_micros(u64::from(ticks) * 100)
 }
 
 fn standard_header_type(
@@ -188,24 +293,28 @@ impl StandardHeader {
     pub fn as_bytes(&self) -> Vec<u8> {
         let header_type_byte = self.header_type_byte();
         let size = calculate_standard_header_length(header_type_byte);
-        let mut buf = BytesMut::with_capacity(size as usize);
+        let mut buf = Vec::with_capacity(size as usize);
         buf.put_u8(header_type_byte);
         buf.put_u8(self.message_counter);
-        buf.put_u16_be(self.overall_length());
+        buf.put_u16::<BigEndian>(self.overall_length());
         if let Some(id) = &self.ecu_id {
             buf.put_zero_terminated_string(&id[..], 4);
         }
         if let Some(id) = &self.session_id {
-            buf.put_u32_be(*id);
+            buf.put_u32::<BigEndian>(*id);
         }
         if let Some(time) = &self.timestamp {
-            buf.put_u32_be(*time);
+            buf.put_u32::<BigEndian>(*time);
         }
-        buf.to_vec()
+        buf
     }
 }
 
-#[derive(Debug, PartialEq, PartialOrd, Clone, Copy, Serialize, Arbitrary)]
+/// Log levels are ordered by their numeric DLT value, so the most severe
+/// level (`Fatal`) is the smallest and `Verbose` the biggest valid one
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum LogLevel {
     Fatal,
     Error,
@@ -213,22 +322,66 @@ pub enum LogLevel {
     Info,
     Debug,
     Verbose,
-    #[proptest(strategy = "(7..=15u8).prop_map(LogLevel::Invalid)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "(7..=15u8).prop_map(LogLevel::Invalid)")
+    )]
     Invalid(u8),
 }
 
-#[derive(Debug, PartialEq, Clone, Arbitrary, Serialize)]
+impl LogLevel {
+    /// the numeric value used in the message info (1 = fatal ... 6 = verbose)
+    pub fn value(self) -> u8 {
+        match self {
+            LogLevel::Fatal => LEVEL_FATAL,
+            LogLevel::Error => LEVEL_ERROR,
+            LogLevel::Warn => LEVEL_WARN,
+            LogLevel::Info => LEVEL_INFO,
+            LogLevel::Debug => LEVEL_DEBUG,
+            LogLevel::Verbose => LEVEL_VERBOSE,
+            LogLevel::Invalid(v) => v,
+        }
+    }
+
+    /// true if the level is as severe as `threshold` or more severe
+    pub fn meets_threshold(self, threshold: LogLevel) -> bool {
+        self <= threshold
+    }
+}
+
+impl Ord for LogLevel {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // an invalid level with the value of a valid one is sorted behind it
+        let key = |l: &LogLevel| (l.value(), matches!(l, LogLevel::Invalid(_)));
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for LogLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum ApplicationTraceType {
     Variable,
     FunctionIn,
     FunctionOut,
     State,
     Vfb,
-    #[proptest(strategy = "(6..15u8).prop_map(ApplicationTraceType::Invalid)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "(6..15u8).prop_map(ApplicationTraceType::Invalid)")
+    )]
     Invalid(u8),
 }
 
-#[derive(Debug, PartialEq, Clone, Arbitrary, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum NetworkTraceType {
     Ipc,
     Can,
@@ -237,17 +390,89 @@ pub enum NetworkTraceType {
     Ethernet,
     Someip,
     Invalid,
-    #[proptest(strategy = "(7..15u8).prop_map(NetworkTraceType::UserDefined)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "(7..15u8).prop_map(NetworkTraceType::UserDefined)")
+    )]
     UserDefined(u8),
 }
 
+/// a frame captured on a bus or network, as sent in a network trace message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct NetworkTrace {
+    pub trace_type: NetworkTraceType,
+    /// the network specific header of the frame (e.g. the CAN identifier), can be empty
+    pub header: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+/// A message flattened into fields that every message has, see `Message::to_record`
+///
+/// meant for loading messages into column based stores: ids and times that a
+/// message does not have are `None` and argument values keep their type
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct DltRecord {
+    /// time of the storage header in microseconds since the epoch
+    pub time_us: Option<u64>,
+    /// timestamp of the standard header in 0.1 milliseconds
+    pub timestamp: Option<u32>,
+    /// ECU-ID of the standard header, or of the storage header if there is none
+    pub ecu_id: Option<String>,
+    pub app_id: Option<String>,
+    pub context_id: Option<String>,
+    pub message_type: Option<MessageType>,
+    /// only set for log messages
+    pub log_level: Option<LogLevel>,
+    pub counter: u8,
+    pub session_id: Option<u32>,
+    /// message id of a non-verbose message
+    pub message_id: Option<u32>,
+    /// name and value of the arguments in the order of the payload
+    pub arguments: Vec<(Option<String>, Value)>,
+}
+
+/// split `bytes` into blocks that are each prefixed with their u16 length
+fn length_prefixed_blocks(
+    mut bytes: &[u8],
+    endianness: Endianness,
+) -> Result<Vec<Vec<u8>>, DltParseError> {
+    let mut blocks = vec![];
+    while !bytes.is_empty() {
+        if bytes.len() < 2 {
+            return Err(DltParseError::ParsingHickup {
+                reason: "missing length of network trace block".into(),
+            });
+        }
+        let len = if endianness == Endianness::Big {
+            BigEndian::read_u16(bytes)
+        } else {
+            LittleEndian::read_u16(bytes)
+        } as usize;
+        let block = bytes
+            .get(2..2 + len)
+            .ok_or_else(|| DltParseError::ParsingHickup {
+                reason: format!("network trace block of {} bytes is truncated", len),
+            })?;
+        blocks.push(block.to_vec());
+        bytes = &bytes[2 + len..];
+    }
+    Ok(blocks)
+}
+
 const CTRL_TYPE_REQUEST: u8 = 0x1;
 const CTRL_TYPE_RESPONSE: u8 = 0x2;
-#[derive(Debug, PartialEq, Clone, Arbitrary, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum ControlType {
     Request,  // represented by 0x1
     Response, // represented by 0x2
-    #[proptest(strategy = "(3..15u8).prop_map(ControlType::Unknown)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "(3..15u8).prop_map(ControlType::Unknown)")
+    )]
     Unknown(u8),
 }
 impl ControlType {
@@ -267,16 +492,22 @@ impl ControlType {
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Arbitrary, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum MessageType {
     Log(LogLevel),
     ApplicationTrace(ApplicationTraceType),
     NetworkTrace(NetworkTraceType),
     Control(ControlType),
-    #[proptest(strategy = "((0b100u8..0b111u8),(0..0b1111u8)).prop_map(MessageType::Unknown)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "((0b100u8..0b111u8),(0..0b1111u8)).prop_map(MessageType::Unknown)")
+    )]
     Unknown((u8, u8)),
 }
 
+#[cfg(feature = "std")]
 impl MessageType {
     pub(crate) fn try_new_from_fibex_message_info(message_info: &str) -> Option<MessageType> {
         Some(MessageType::Log(match message_info {
@@ -297,45 +528,41 @@ pub const DLT_TYPE_NW_TRACE: u8 = 0b010;
 pub const DLT_TYPE_CONTROL: u8 = 0b011;
 
 /// The Extended Header shall be in big endian format
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct ExtendedHeader {
     pub verbose: bool,
-    #[proptest(strategy = "0..=5u8")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "0..=5u8"))]
     pub argument_count: u8,
     pub message_type: MessageType,
 
-    #[proptest(strategy = "\"[a-zA-Z]{1,3}\"")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "\"[a-zA-Z]{1,3}\""))]
     pub application_id: String,
-    #[proptest(strategy = "\"[a-zA-Z]{1,3}\"")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "\"[a-zA-Z]{1,3}\""))]
     pub context_id: String,
 }
 
 impl ExtendedHeader {
     #[allow(dead_code)]
     pub fn as_bytes(self: &ExtendedHeader) -> Vec<u8> {
-        let mut buf = BytesMut::with_capacity(EXTENDED_HEADER_LENGTH as usize);
+        let mut buf = Vec::with_capacity(EXTENDED_HEADER_LENGTH as usize);
         buf.put_u8(u8::from(&self.message_type) | if self.verbose { 1 } else { 0 });
         buf.put_u8(self.argument_count);
         buf.put_zero_terminated_string(&self.application_id[..], 4);
         buf.put_zero_terminated_string(&self.context_id[..], 4);
-        buf.to_vec()
+        buf
     }
     pub fn skip_with_level(self: &ExtendedHeader, level: LogLevel) -> bool {
-        match self.message_type {
-            MessageType::Log(n) => match (n, level) {
-                (LogLevel::Invalid(a), LogLevel::Invalid(b)) => a < b,
-                (LogLevel::Invalid(_), _) => false,
-                (_, LogLevel::Invalid(_)) => true,
-                _ => level < n,
-            },
-            _ => false,
-        }
+        self.message_type.skip_with_level(level)
     }
 }
 
 /// Fixed-Point representation. only supports 32 bit and 64 bit values
 /// according to the spec 128 bit are possible but we don't support it
-#[derive(Debug, PartialEq, Clone, Arbitrary, Serialize)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum FixedPointValue {
     I32(i32),
     I64(i64),
@@ -347,6 +574,7 @@ pub fn fixed_point_value_width(v: &FixedPointValue) -> usize {
     }
 }
 #[derive(Debug, PartialEq, Clone, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum Value {
     Bool(u8),
     U8(u8),
@@ -359,32 +587,161 @@ pub enum Value {
     I32(i32),
     I64(i64),
     I128(i128),
+    /// a half-precision float, widened to `f32`
+    F16(f32),
     F32(f32),
     F64(f64),
     StringVal(String),
     Raw(Vec<u8>),
+    /// an integer argument with fixed point scaling (FIXP)
+    ///
+    /// `scaled = raw * quantization + offset`. Unsigned 64 bit values above
+    /// `i64::MAX` keep their bit pattern in `raw`.
+    FixedPoint {
+        raw: i64,
+        quantization: f64,
+        offset: i64,
+        scaled: f64,
+    },
+    /// an array argument (ARAY), the elements of all dimensions are stored
+    /// one after the other
+    Array {
+        element_type: TypeInfoKind,
+        dims: Vec<u16>,
+        values: Vec<Value>,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum StringCoding {
     ASCII,
     UTF8,
-    #[proptest(strategy = "(2..=7u8).prop_map(StringCoding::Reserved)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "(2..=7u8).prop_map(StringCoding::Reserved)")
+    )]
     Reserved(u8),
 }
-#[derive(Debug, Clone, PartialEq, Copy, Arbitrary, Serialize)]
+
+impl StringCoding {
+    /// the bytes of a string argument in this coding
+    ///
+    /// ASCII strings are written as Latin-1, characters outside of that range
+    /// are replaced by `?`
+    pub(crate) fn encode<'a>(&self, s: &'a str) -> Cow<'a, [u8]> {
+        match self {
+            StringCoding::ASCII if !s.is_ascii() => Cow::Owned(
+                s.chars()
+                    .map(|c| if (c as u32) < 0x100 { c as u8 } else { b'?' })
+                    .collect(),
+            ),
+            _ => Cow::Borrowed(s.as_bytes()),
+        }
+    }
+    /// decode the bytes of a string argument (without the null termination)
+    ///
+    /// ASCII strings are read as Latin-1, invalid UTF-8 sequences are
+    /// replaced by `U+FFFD`
+    pub(crate) fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        match self {
+            StringCoding::ASCII if !bytes.is_ascii() => {
+                Cow::Owned(bytes.iter().map(|&b| b as char).collect())
+            }
+            _ => String::from_utf8_lossy(bytes),
+        }
+    }
+}
+#[derive(Debug, Clone, PartialEq, Copy, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum FloatWidth {
+    /// only used for floats, there are no 16 bit fixed point values
+    #[cfg_attr(any(feature = "std", test), proptest(skip))]
+    Width16 = 16,
     Width32 = 32,
     Width64 = 64,
 }
 pub fn float_width_to_type_length(width: FloatWidth) -> TypeLength {
     match width {
+        FloatWidth::Width16 => TypeLength::BitLength16,
         FloatWidth::Width32 => TypeLength::BitLength32,
         FloatWidth::Width64 => TypeLength::BitLength64,
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Copy, Arbitrary, Serialize)]
+/// decode the bit pattern of an IEEE 754 half-precision float
+///
+/// every half-precision value (including subnormals, infinities and NaN)
+/// can be represented exactly as `f32`
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = u32::from(bits & 0x8000) << 16;
+    let exponent = u32::from((bits >> 10) & 0x1f);
+    let mantissa = u32::from(bits & 0x3ff);
+    let magnitude = match exponent {
+        // subnormal (or zero): mantissa * 2^-24
+        0 => (mantissa as f32 / 16_777_216.0).to_bits(),
+        // infinity or NaN (the payload of a NaN is kept)
+        0x1f => 0x7f80_0000 | (mantissa << 13),
+        _ => ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(sign | magnitude)
+}
+
+/// encode a float as IEEE 754 half-precision bit pattern
+///
+/// the value is rounded to the nearest representable value (ties to even),
+/// values too big for half-precision become infinity.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        // keep NaNs quiet so they do not turn into infinity
+        let nan = if mantissa != 0 {
+            0x200 | (mantissa >> 13) as u16
+        } else {
+            0
+        };
+        return sign | 0x7c00 | nan;
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    let (half, rest, halfway) = if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        // subnormal, the implicit leading bit becomes part of the mantissa
+        let shift = (14 - half_exponent) as u32;
+        let mantissa = mantissa | 0x80_0000;
+        (
+            mantissa >> shift,
+            mantissa & ((1 << shift) - 1),
+            1 << (shift - 1),
+        )
+    } else {
+        (
+            ((half_exponent as u32) << 10) | (mantissa >> 13),
+            mantissa & 0x1fff,
+            0x1000,
+        )
+    };
+    // a carry out of the mantissa correctly increments the exponent
+    let rounded = if rest > halfway || (rest == halfway && half & 1 == 1) {
+        half + 1
+    } else {
+        half
+    };
+    sign | rounded as u16
+}
+
+#[derive(Debug, Clone, PartialEq, Copy, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum TypeLength {
     BitLength8 = 8,
     BitLength16 = 16,
@@ -396,6 +753,7 @@ pub enum TypeLength {
 impl FloatWidth {
     pub fn width_in_bytes(self) -> usize {
         match self {
+            FloatWidth::Width16 => 2,
             FloatWidth::Width32 => 4,
             FloatWidth::Width64 => 8,
         }
@@ -414,17 +772,22 @@ impl TypeLength {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum TypeInfoKind {
     Bool,
-    #[proptest(strategy = "signed_strategy()")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "signed_strategy()"))]
     Signed(TypeLength),
     SignedFixedPoint(FloatWidth),
-    #[proptest(strategy = "unsigned_strategy()")]
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "unsigned_strategy()"))]
     Unsigned(TypeLength),
     UnsignedFixedPoint(FloatWidth),
+    #[cfg_attr(any(feature = "std", test), proptest(strategy = "float_strategy()"))]
     Float(FloatWidth),
-    // Array, NYI
+    /// ARAY: bool, integer, fixed point or float elements
+    #[cfg_attr(any(feature = "std", test), proptest(skip))]
+    Array(Box<TypeInfoKind>),
     StringType,
     Raw,
 }
@@ -450,16 +813,25 @@ pub enum TypeInfoKind {
 /// Both always contain a length information field and a field with the text (of name or unit).
 /// The length field contains the number of characters of the associated name or unit filed.
 /// The unit information is to add only in some data types.
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct TypeInfo {
     pub kind: TypeInfoKind,
     pub coding: StringCoding,
     pub has_variable_info: bool,
     pub has_trace_info: bool,
+    /// bits in which the parsed field differs from the encoding of the other
+    /// fields (e.g. reserved bits or STRU), so `as_u32` gives back the field
+    /// exactly as it was read. Always 0 for a type info that is not parsed
+    #[cfg_attr(any(feature = "std", test), proptest(value = "0"))]
+    #[serde(default)]
+    pub unmodelled_bits: u32,
 }
 impl TypeInfo {
     pub fn type_length_bits_float(len: FloatWidth) -> u32 {
         match len {
+            FloatWidth::Width16 => 0b010,
             FloatWidth::Width32 => 0b011,
             FloatWidth::Width64 => 0b100,
         }
@@ -484,35 +856,41 @@ impl TypeInfo {
         }
     }
     pub fn is_fixed_point(self: &TypeInfo) -> bool {
-        match self.kind {
+        match &self.kind {
             TypeInfoKind::SignedFixedPoint(_) => true,
             TypeInfoKind::UnsignedFixedPoint(_) => true,
+            TypeInfoKind::Array(element) => matches!(
+                **element,
+                TypeInfoKind::SignedFixedPoint(_) | TypeInfoKind::UnsignedFixedPoint(_)
+            ),
             _ => false,
         }
     }
-    pub fn as_bytes<T: ByteOrder>(self: &TypeInfo) -> Vec<u8> {
-        // println!("TypeInfo::as_bytes: {:?}", self);
-        let mut info: u32 = 0;
-        // encode length
-        match self.kind {
-            TypeInfoKind::Float(len) => info |= TypeInfo::type_length_bits_float(len),
-            TypeInfoKind::Signed(len) => info |= TypeInfo::type_length_bits(len),
-            TypeInfoKind::SignedFixedPoint(len) => info |= TypeInfo::type_length_bits_float(len),
-            TypeInfoKind::Unsigned(len) => info |= TypeInfo::type_length_bits(len),
-            TypeInfoKind::UnsignedFixedPoint(len) => info |= TypeInfo::type_length_bits_float(len),
-            _ => (),
-        }
-        match self.kind {
-            TypeInfoKind::Bool => info |= TYPE_INFO_BOOL_FLAG,
-            TypeInfoKind::Signed(_) => info |= TYPE_INFO_SINT_FLAG,
-            TypeInfoKind::SignedFixedPoint(_) => info |= TYPE_INFO_SINT_FLAG,
-            TypeInfoKind::Unsigned(_) => info |= TYPE_INFO_UINT_FLAG,
-            TypeInfoKind::UnsignedFixedPoint(_) => info |= TYPE_INFO_UINT_FLAG,
-            TypeInfoKind::Float(_) => info |= TYPE_INFO_FLOAT_FLAG,
-            // TypeInfoKind::Array => info |= TYPE_INFO_ARRAY_FLAG,
-            TypeInfoKind::StringType => info |= TYPE_INFO_STRING_FLAG,
-            TypeInfoKind::Raw => info |= TYPE_INFO_RAW_FLAG,
+    /// type length and type bits (TYLE, BOOL ... STRU) of a kind
+    fn kind_bits(kind: &TypeInfoKind) -> u32 {
+        match kind {
+            TypeInfoKind::Bool => TYPE_INFO_BOOL_FLAG,
+            TypeInfoKind::Signed(len) => TypeInfo::type_length_bits(*len) | TYPE_INFO_SINT_FLAG,
+            TypeInfoKind::SignedFixedPoint(len) => {
+                TypeInfo::type_length_bits_float(*len) | TYPE_INFO_SINT_FLAG
+            }
+            TypeInfoKind::Unsigned(len) => TypeInfo::type_length_bits(*len) | TYPE_INFO_UINT_FLAG,
+            TypeInfoKind::UnsignedFixedPoint(len) => {
+                TypeInfo::type_length_bits_float(*len) | TYPE_INFO_UINT_FLAG
+            }
+            TypeInfoKind::Float(len) => {
+                TypeInfo::type_length_bits_float(*len) | TYPE_INFO_FLOAT_FLAG
+            }
+            TypeInfoKind::Array(element) => TYPE_INFO_ARRAY_FLAG | TypeInfo::kind_bits(element),
+            TypeInfoKind::StringType => TYPE_INFO_STRING_FLAG,
+            TypeInfoKind::Raw => TYPE_INFO_RAW_FLAG,
         }
+    }
+    /// the 32 bit TypeInfo field as it is written to a message
+    ///
+    /// the reserved bits 18-31 are 0 unless they were set in the parsed field
+    pub fn as_u32(self: &TypeInfo) -> u32 {
+        let mut info: u32 = TypeInfo::kind_bits(&self.kind);
         if self.has_variable_info {
             info |= TYPE_INFO_VARIABLE_INFO
         }
@@ -527,14 +905,18 @@ impl TypeInfo {
             StringCoding::UTF8 => info |= 0b001 << 15,
             StringCoding::Reserved(v) => info |= ((0b111 & v) as u32) << 15,
         }
+        info ^ self.unmodelled_bits
+    }
+    pub fn as_bytes<T: ByteOrder>(self: &TypeInfo) -> Vec<u8> {
+        let info = self.as_u32();
         trace!("writing type info: {:#b}", info);
 
-        let mut buf = BytesMut::with_capacity(4);
+        let mut buf = Vec::with_capacity(4);
         let mut b = [0; 4];
         T::write_u32(&mut b, info);
         trace!("type info bytes: {:02X?}", b);
         buf.put_slice(&b);
-        buf.to_vec()
+        buf
     }
 }
 ///    Bit Representation               0b0011_1111_1111_1111_1111
@@ -552,34 +934,29 @@ impl TypeInfo {
 ///    type bool ...........................................^ ||||
 ///    type length ...........................................^^^^
 impl TryFrom<u32> for TypeInfo {
-    type Error = Error;
-    fn try_from(info: u32) -> Result<TypeInfo, Error> {
-        fn type_len(info: u32) -> Result<TypeLength, Error> {
+    type Error = DltParseError;
+    fn try_from(info: u32) -> Result<TypeInfo, DltParseError> {
+        fn type_len(info: u32) -> Result<TypeLength, DltParseError> {
             match info & 0b1111 {
                 0x01 => Ok(TypeLength::BitLength8),
                 0x02 => Ok(TypeLength::BitLength16),
                 0x03 => Ok(TypeLength::BitLength32),
                 0x04 => Ok(TypeLength::BitLength64),
                 0x05 => Ok(TypeLength::BitLength128),
-                v => Err(Error::new(
-                    io::ErrorKind::Other,
-                    format!("Unknown type_len in TypeInfo {:b}", v),
-                )),
+                _ => Err(DltParseError::UnknownTypeInfo(info)),
             }
         }
-        fn type_len_float(info: u32) -> Result<FloatWidth, Error> {
+        fn type_len_float(info: u32) -> Result<FloatWidth, DltParseError> {
             match info & 0b1111 {
                 0x03 => Ok(FloatWidth::Width32),
                 0x04 => Ok(FloatWidth::Width64),
-                v => Err(Error::new(
-                    io::ErrorKind::Other,
-                    format!("Unknown type_len_float in TypeInfo {:b}", v),
-                )),
+                _ => Err(DltParseError::UnknownTypeInfo(info)),
             }
         }
 
         let is_fixed_point = (info & TYPE_INFO_FIXED_POINT_FLAG) != 0;
-        let kind = match (info >> 4) & 0b111_1111 {
+        let is_array = (info & TYPE_INFO_ARRAY_FLAG) != 0;
+        let kind = match (info >> 4) & 0b110_1111 {
             0b000_0001 => Ok(TypeInfoKind::Bool),
             0b000_0010 => Ok(if is_fixed_point {
                 TypeInfoKind::SignedFixedPoint(type_len_float(info)?)
@@ -591,15 +968,19 @@ impl TryFrom<u32> for TypeInfo {
             } else {
                 TypeInfoKind::Unsigned(type_len(info)?)
             }),
-            0b000_1000 => Ok(TypeInfoKind::Float(type_len_float(info)?)),
-            // 0b001_0000 => Ok(TypeInfoKind::Array),
-            0b010_0000 => Ok(TypeInfoKind::StringType),
-            0b100_0000 => Ok(TypeInfoKind::Raw),
-            v => Err(Error::new(
-                io::ErrorKind::Other,
-                format!("Unknown TypeInfoKind in TypeInfo {:b}", v),
-            )),
+            0b000_1000 => Ok(TypeInfoKind::Float(match info & 0b1111 {
+                0x02 => FloatWidth::Width16,
+                _ => type_len_float(info)?,
+            })),
+            0b010_0000 if !is_array => Ok(TypeInfoKind::StringType),
+            0b100_0000 if !is_array => Ok(TypeInfoKind::Raw),
+            _ => Err(DltParseError::UnknownTypeInfo(info)),
         }?;
+        let kind = if is_array {
+            TypeInfoKind::Array(Box::new(kind))
+        } else {
+            kind
+        };
         let coding = match (info >> 15) & 0b111 {
             0x00 => (StringCoding::ASCII),
             0x01 => (StringCoding::UTF8),
@@ -608,12 +989,15 @@ impl TryFrom<u32> for TypeInfo {
                 StringCoding::Reserved(v as u8)
             }
         };
-        Ok(TypeInfo {
+        let mut type_info = TypeInfo {
             has_variable_info: (info & TYPE_INFO_VARIABLE_INFO) != 0,
             has_trace_info: (info & TYPE_INFO_TRACE_INFO_FLAG) != 0,
             kind,
             coding,
-        })
+            unmodelled_bits: 0,
+        };
+        type_info.unmodelled_bits = info ^ type_info.as_u32();
+        Ok(type_info)
     }
 }
 /// The following equation defines the relation between the logical value (log_v) and
@@ -631,11 +1015,54 @@ impl TryFrom<u32> for TypeInfo {
 ///     * i64 bit if Type Length (TYLE) equals 4
 ///     * i128 bit if Type Length (TYLE) equals 5 (unsupported)
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct FixedPoint {
     pub quantization: f32,
     pub offset: FixedPointValue,
 }
+impl FixedPoint {
+    /// the logical value of the transmitted integer `phy_v`
+    ///
+    /// the elements of arrays are scaled one by one, values that are no
+    /// integers are returned unchanged
+    pub fn scale(&self, phy_v: Value) -> Value {
+        let (raw, as_f64) = match phy_v {
+            Value::Array {
+                element_type,
+                dims,
+                values,
+            } => {
+                return Value::Array {
+                    element_type,
+                    dims,
+                    values: values.into_iter().map(|v| self.scale(v)).collect(),
+                }
+            }
+            Value::I8(v) => (i64::from(v), f64::from(v)),
+            Value::I16(v) => (i64::from(v), f64::from(v)),
+            Value::I32(v) => (i64::from(v), f64::from(v)),
+            Value::I64(v) => (v, v as f64),
+            Value::U8(v) => (i64::from(v), f64::from(v)),
+            Value::U16(v) => (i64::from(v), f64::from(v)),
+            Value::U32(v) => (i64::from(v), f64::from(v)),
+            Value::U64(v) => (v as i64, v as f64),
+            v => return v,
+        };
+        let offset = match self.offset {
+            FixedPointValue::I32(v) => i64::from(v),
+            FixedPointValue::I64(v) => v,
+        };
+        let quantization = f64::from(self.quantization);
+        Value::FixedPoint {
+            raw,
+            quantization,
+            offset,
+            scaled: as_f64 * quantization + offset as f64,
+        }
+    }
+}
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Argument {
     pub type_info: TypeInfo,
     pub name: Option<String>,
@@ -644,47 +1071,28 @@ pub struct Argument {
     pub value: Value,
 }
 impl Argument {
-    fn value_as_f64(&self) -> Option<f64> {
-        match self.value {
-            Value::I8(v) => Some(v as f64),
-            Value::I16(v) => Some(v as f64),
-            Value::I32(v) => Some(v as f64),
-            Value::I64(v) => Some(v as f64),
-            Value::U8(v) => Some(v as f64),
-            Value::U16(v) => Some(v as f64),
-            Value::U32(v) => Some(v as f64),
-            Value::U64(v) => Some(v as f64),
-            _ => None,
-        }
+    /// the TypeInfo bitfield of this argument (TYLE, BOOL, SINT, ... SCOD)
+    ///
+    /// for a parsed argument this is the field as it was read, including
+    /// bits that `type_info` does not model
+    pub fn type_info_raw(&self) -> u32 {
+        self.type_info.as_u32()
     }
-    fn log_v(&self) -> Option<u64> {
-        match &self.fixed_point {
-            Some(FixedPoint {
-                quantization,
-                offset,
-            }) => {
-                if let Some(value) = self.value_as_f64() {
-                    match offset {
-                        FixedPointValue::I32(v) => {
-                            Some((value * *quantization as f64) as u64 + *v as u64)
-                        }
-                        FixedPointValue::I64(v) => {
-                            Some((value * *quantization as f64) as u64 + *v as u64)
-                        }
-                    }
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        }
+    /// VARI: the argument carries a name (and for some types a unit)
+    pub fn has_variable_info(&self) -> bool {
+        self.type_info.has_variable_info
     }
-    pub(crate) fn to_real_value(&self) -> Option<u64> {
-        match (&self.type_info.kind, &self.fixed_point) {
-            (TypeInfoKind::SignedFixedPoint(_), Some(_)) => self.log_v(),
-            (TypeInfoKind::UnsignedFixedPoint(_), Some(_)) => self.log_v(),
-            _ => None,
-        }
+    /// TRAI: the argument is preceded by trace info
+    pub fn has_trace_info(&self) -> bool {
+        self.type_info.has_trace_info
+    }
+    /// FIXP: the value has to be scaled with quantization and offset
+    pub fn is_fixed_point(&self) -> bool {
+        self.type_info.is_fixed_point()
+    }
+    /// SCOD: the coding of string arguments
+    pub fn string_coding(&self) -> &StringCoding {
+        &self.type_info.coding
     }
     #[allow(dead_code)]
     pub fn valid(&self) -> bool {
@@ -694,6 +1102,10 @@ impl Argument {
                 Value::Bool(_) => (),
                 _ => valid = false,
             },
+            TypeInfoKind::Float(FloatWidth::Width16) => match self.value {
+                Value::F16(_) => (),
+                _ => valid = false,
+            },
             TypeInfoKind::Float(FloatWidth::Width32) => match self.value {
                 Value::F32(_) => (),
                 _ => valid = false,
@@ -718,14 +1130,16 @@ impl Argument {
     }
 
     pub fn len_new(self: &Argument) -> usize {
-        let name_space = match &self.name {
-            Some(n) => 2 /* length of name */ + n.len() + 1,
-            _ => 0,
-        };
-        let unit_space = match &self.unit {
-            Some(u) => 2 /* length of unit */ + u.len() + 1,
-            _ => 0,
+        // with variable info a missing name or unit is written as empty string
+        let var_info_space = |s: &Option<String>| {
+            if self.type_info.has_variable_info {
+                2 /* length */ + s.as_ref().map(|s| s.len()).unwrap_or(0) + 1
+            } else {
+                0
+            }
         };
+        let name_space = var_info_space(&self.name);
+        let unit_space = var_info_space(&self.unit);
         let without_type_info = match self.type_info.kind {
             TypeInfoKind::Bool => name_space + 1,
             TypeInfoKind::Signed(bit_width) => name_space + unit_space + bit_width.width_in_bytes(),
@@ -741,11 +1155,25 @@ impl Argument {
             TypeInfoKind::Float(float_width) => {
                 name_space + unit_space + float_width.width_in_bytes()
             }
+            TypeInfoKind::Array(ref element) => {
+                let fixed_point_space = match &self.fixed_point {
+                    Some(fp) => 4 /* quantization */ + fixed_point_value_width(&fp.offset),
+                    None => 0,
+                };
+                let (dims_space, values_space) = match &self.value {
+                    Value::Array { dims, values, .. } => {
+                        (2 * dims.len(), values.len() * array_element_width(element))
+                    }
+                    _ => (0, 0),
+                };
+                2 /* number of dimensions */ + dims_space + name_space + unit_space
+                    + fixed_point_space + values_space
+            }
             TypeInfoKind::StringType => {
                 let mut capacity = 2 /* length of string and termination char */ + name_space;
                 match &self.value {
                     Value::StringVal(sv) => {
-                        capacity += sv.len() + 1;
+                        capacity += self.type_info.coding.encode(sv).len() + 1;
                     }
                     _ => {
                         error!("Found typeinfokind StringType but no StringValue!");
@@ -778,17 +1206,19 @@ impl Argument {
         &self,
         info: &TypeInfo,
         name: &Option<String>,
-    ) -> BytesMut {
-        let mut capacity = TYPE_INFO_LENGTH + info.type_width();
-        if let Some(n) = name {
-            capacity += 2 /* length name */ + n.len() + 1;
+        value_width: usize,
+    ) -> Vec<u8> {
+        let mut capacity = TYPE_INFO_LENGTH + value_width;
+        let name = name.as_deref().unwrap_or("");
+        if info.has_variable_info {
+            capacity += 2 /* length name */ + name.len() + 1;
         }
-        let mut buf = BytesMut::with_capacity(capacity);
+        let mut buf = Vec::with_capacity(capacity);
         buf.extend_from_slice(&info.as_bytes::<T>()[..]);
-        if let Some(n) = name {
+        if info.has_variable_info {
             #[allow(deprecated)]
-            buf.put_u16::<T>(n.len() as u16 + 1);
-            buf.extend_from_slice(n.as_bytes());
+            buf.put_u16::<T>(name.len() as u16 + 1);
+            buf.extend_from_slice(name.as_bytes());
             buf.put_u8(0x0); // null termination
         }
         buf
@@ -799,7 +1229,7 @@ impl Argument {
         name: &Option<String>,
         unit: &Option<String>,
         fixed_point: &Option<FixedPoint>,
-    ) -> BytesMut {
+    ) -> Vec<u8> {
         // trace!(
         //     "mut_buf_with_typeinfo_name_unit (info: {:?}) {:?}/{:?} (fp: {:?})",
         //     info,
@@ -824,7 +1254,7 @@ impl Argument {
             capacity += 4 /* quantization */ + fixed_point_value_width(&fp.offset);
         }
         capacity += info.type_width();
-        let mut buf = BytesMut::with_capacity(capacity);
+        let mut buf = Vec::with_capacity(capacity);
         buf.extend_from_slice(&info.as_bytes::<T>()[..]);
         if info.has_variable_info {
             if let Some(n) = name {
@@ -879,7 +1309,7 @@ impl Argument {
     pub fn as_bytes<T: ByteOrder>(self: &Argument) -> Vec<u8> {
         match self.type_info.kind {
             TypeInfoKind::Bool => {
-                let mut buf = self.mut_buf_with_typeinfo_name::<T>(&self.type_info, &self.name);
+                let mut buf = self.mut_buf_with_typeinfo_name::<T>(&self.type_info, &self.name, 1);
                 let v = match self.value {
                     Value::Bool(x) => x,
                     _ => {
@@ -888,8 +1318,8 @@ impl Argument {
                     }
                 };
                 buf.put_u8(v);
-                dbg_bytes("bool argument", &buf.to_vec()[..]);
-                buf.to_vec()
+                dbg_bytes("bool argument", &buf);
+                buf
             }
             TypeInfoKind::Signed(_) => {
                 let mut buf = self.mut_buf_with_typeinfo_name_unit::<T>(
@@ -899,19 +1329,19 @@ impl Argument {
                     &self.fixed_point,
                 );
                 put_signed_value::<T>(&self.value, &mut buf);
-                dbg_bytes("signed argument", &buf.to_vec()[..]);
-                buf.to_vec()
+                dbg_bytes("signed argument", &buf);
+                buf
             }
-            TypeInfoKind::SignedFixedPoint(_) => {
+            TypeInfoKind::SignedFixedPoint(width) => {
                 let mut buf = self.mut_buf_with_typeinfo_name_unit::<T>(
                     &self.type_info,
                     &self.name,
                     &self.unit,
                     &self.fixed_point,
                 );
-                put_signed_value::<T>(&self.value, &mut buf);
-                dbg_bytes("signed fixed point argument", &buf.to_vec()[..]);
-                buf.to_vec()
+                put_signed_value::<T>(&fixed_point_phy_value(&self.value, width, true), &mut buf);
+                dbg_bytes("signed fixed point argument", &buf);
+                buf
             }
             TypeInfoKind::Unsigned(_) => {
                 let mut buf = self.mut_buf_with_typeinfo_name_unit::<T>(
@@ -921,58 +1351,72 @@ impl Argument {
                     &self.fixed_point,
                 );
                 put_unsigned_value::<T>(&self.value, &mut buf);
-                dbg_bytes("unsigned argument", &buf.to_vec()[..]);
-                buf.to_vec()
+                dbg_bytes("unsigned argument", &buf);
+                buf
             }
-            TypeInfoKind::UnsignedFixedPoint(_) => {
+            TypeInfoKind::UnsignedFixedPoint(width) => {
                 let mut buf = self.mut_buf_with_typeinfo_name_unit::<T>(
                     &self.type_info,
                     &self.name,
                     &self.unit,
                     &self.fixed_point,
                 );
-                put_unsigned_value::<T>(&self.value, &mut buf);
-                dbg_bytes("unsigned FP argument", &buf.to_vec()[..]);
-                buf.to_vec()
+                put_unsigned_value::<T>(
+                    &fixed_point_phy_value(&self.value, width, false),
+                    &mut buf,
+                );
+                dbg_bytes("unsigned FP argument", &buf);
+                buf
             }
             TypeInfoKind::Float(_) => {
-                fn write_value<T: ByteOrder>(value: &Value, buf: &mut BytesMut) {
-                    match value {
-                        Value::F32(v) => {
-                            let mut b = [0; 4];
-                            T::write_f32(&mut b, *v);
-                            buf.put_slice(&b)
-                        }
-                        Value::F64(v) => {
-                            let mut b = [0; 8];
-                            T::write_f64(&mut b, *v);
-                            buf.put_slice(&b)
-                        }
-                        _ => (),
-                    }
-                }
                 let mut buf = self.mut_buf_with_typeinfo_name_unit::<T>(
                     &self.type_info,
                     &self.name,
                     &self.unit,
                     &self.fixed_point,
                 );
-                write_value::<T>(&self.value, &mut buf);
-                dbg_bytes("float argument", &buf.to_vec()[..]);
-                buf.to_vec()
+                put_float_value::<T>(&self.value, &mut buf);
+                dbg_bytes("float argument", &buf);
+                buf
             }
-            // TypeInfoKind::Array => {
-            //     // TODO dlt array type not yet implemented NYI
-            //     eprintln!("found dlt array type...not yet supported");
-            //     BytesMut::with_capacity(STORAGE_HEADER_LENGTH).to_vec()
-            // }
+            TypeInfoKind::Array(ref element) => match &self.value {
+                Value::Array { dims, values, .. } => {
+                    // the dimensions are written between type info and variable info
+                    let head = self.mut_buf_with_typeinfo_name_unit::<T>(
+                        &self.type_info,
+                        &self.name,
+                        &self.unit,
+                        &self.fixed_point,
+                    );
+                    let mut buf = Vec::with_capacity(self.len_new());
+                    buf.extend_from_slice(&head[..TYPE_INFO_LENGTH]);
+                    #[allow(deprecated)]
+                    buf.put_u16::<T>(dims.len() as u16);
+                    for dim in dims {
+                        #[allow(deprecated)]
+                        buf.put_u16::<T>(*dim);
+                    }
+                    buf.extend_from_slice(&head[TYPE_INFO_LENGTH..]);
+                    for value in values {
+                        put_array_element::<T>(element, value, &mut buf);
+                    }
+                    dbg_bytes("array argument", &buf);
+                    buf
+                }
+                v => {
+                    error!("found invalid dlt entry for Array ({:?}", v);
+                    Vec::new()
+                }
+            },
             TypeInfoKind::StringType => {
                 match (self.type_info.has_variable_info, &self.name) {
-                    (true, Some(var_name)) => {
+                    (true, name) => {
+                        let var_name = name.as_deref().unwrap_or("");
                         match &self.value {
-                            Value::StringVal(s) => {
+                            Value::StringVal(sv) => {
+                                let s = self.type_info.coding.encode(sv);
                                 let name_len_with_termination: u16 = var_name.len() as u16 + 1;
-                                let mut buf = BytesMut::with_capacity(
+                                let mut buf = Vec::with_capacity(
                                     TYPE_INFO_LENGTH +
                                     2 /* length string */ +
                                     2 /* length name */ +
@@ -986,21 +1430,22 @@ impl Argument {
                                 buf.put_u16::<T>(name_len_with_termination);
                                 buf.extend_from_slice(var_name.as_bytes());
                                 buf.put_u8(0x0); // null termination
-                                buf.extend_from_slice(s.as_bytes());
+                                buf.extend_from_slice(&s);
                                 buf.put_u8(0x0); // null termination
-                                dbg_bytes("StringType with variable info", &buf.to_vec()[..]);
-                                buf.to_vec()
+                                dbg_bytes("StringType with variable info", &buf);
+                                buf
                             }
                             v => {
                                 error!("found invalid dlt entry for StringType ({:?}", v);
-                                BytesMut::with_capacity(0).to_vec()
+                                Vec::new()
                             }
                         }
                     }
                     (false, None) => {
                         match &self.value {
-                            Value::StringVal(s) => {
-                                let mut buf = BytesMut::with_capacity(
+                            Value::StringVal(sv) => {
+                                let s = self.type_info.coding.encode(sv);
+                                let mut buf = Vec::with_capacity(
                                     TYPE_INFO_LENGTH +
                                     2 /* length string */ +
                                     s.len() + 1,
@@ -1008,34 +1453,31 @@ impl Argument {
                                 buf.extend_from_slice(&self.type_info.as_bytes::<T>()[..]);
                                 #[allow(deprecated)]
                                 buf.put_u16::<T>(s.len() as u16 + 1);
-                                buf.extend_from_slice(s.as_bytes());
+                                buf.extend_from_slice(&s);
                                 buf.put_u8(0x0); // null termination
-                                dbg_bytes_with_info(
-                                    "StringType, no variable info",
-                                    &buf.to_vec()[..],
-                                    Some(s),
-                                );
-                                buf.to_vec()
+                                dbg_bytes_with_info("StringType, no variable info", &buf, Some(sv));
+                                buf
                             }
                             _ => {
                                 error!("found invalid dlt entry for StringType ({:?}", self);
-                                BytesMut::with_capacity(0).to_vec()
+                                Vec::new()
                             }
                         }
                     }
                     _ => {
                         error!("found invalid dlt entry ({:?}", self);
-                        BytesMut::with_capacity(0).to_vec()
+                        Vec::new()
                     }
                 }
             }
             TypeInfoKind::Raw => {
                 match (self.type_info.has_variable_info, &self.name) {
-                    (true, Some(var_name)) => {
+                    (true, name) => {
+                        let var_name = name.as_deref().unwrap_or("");
                         match &self.value {
                             Value::Raw(bytes) => {
                                 let name_len_with_termination: u16 = var_name.len() as u16 + 1;
-                                let mut buf = BytesMut::with_capacity(
+                                let mut buf = Vec::with_capacity(
                                     TYPE_INFO_LENGTH +
                                     2 /* length bytes */ +
                                     2 /* length name */ +
@@ -1050,19 +1492,19 @@ impl Argument {
                                 buf.extend_from_slice(var_name.as_bytes());
                                 buf.put_u8(0x0); // null termination
                                 buf.extend_from_slice(bytes);
-                                dbg_bytes("Raw, with variable info", &buf.to_vec()[..]);
-                                buf.to_vec()
+                                dbg_bytes("Raw, with variable info", &buf);
+                                buf
                             }
                             _ => {
                                 error!("found invalid dlt entry for Raw ({:?}", self);
-                                BytesMut::with_capacity(0).to_vec()
+                                Vec::new()
                             }
                         }
                     }
                     (false, None) => {
                         match &self.value {
                             Value::Raw(bytes) => {
-                                let mut buf = BytesMut::with_capacity(
+                                let mut buf = Vec::with_capacity(
                                     TYPE_INFO_LENGTH +
                                     2 /* length string */ +
                                     bytes.len(),
@@ -1071,25 +1513,81 @@ impl Argument {
                                 #[allow(deprecated)]
                                 buf.put_u16::<T>(bytes.len() as u16);
                                 buf.extend_from_slice(bytes);
-                                dbg_bytes("Raw, no variable info", &buf.to_vec()[..]);
-                                buf.to_vec()
+                                dbg_bytes("Raw, no variable info", &buf);
+                                buf
                             }
                             _ => {
                                 error!("found invalid dlt entry for Raw ({:?}", self);
-                                BytesMut::with_capacity(0).to_vec()
+                                Vec::new()
                             }
                         }
                     }
                     _ => {
                         error!("found invalid dlt entry ({:?}", self);
-                        BytesMut::with_capacity(0).to_vec()
+                        Vec::new()
                     }
                 }
             }
         }
     }
 }
-fn put_unsigned_value<T: ByteOrder>(value: &Value, buf: &mut BytesMut) {
+/// the number of bytes one element of an array takes
+pub fn array_element_width(element: &TypeInfoKind) -> usize {
+    match element {
+        TypeInfoKind::Bool => 1,
+        TypeInfoKind::Signed(len) | TypeInfoKind::Unsigned(len) => len.width_in_bytes(),
+        TypeInfoKind::SignedFixedPoint(width)
+        | TypeInfoKind::UnsignedFixedPoint(width)
+        | TypeInfoKind::Float(width) => width.width_in_bytes(),
+        _ => 0,
+    }
+}
+fn put_array_element<T: ByteOrder>(element: &TypeInfoKind, value: &Value, buf: &mut Vec<u8>) {
+    match (element, value) {
+        (TypeInfoKind::Bool, Value::Bool(v)) => buf.put_u8(*v),
+        (TypeInfoKind::Signed(_), v) => put_signed_value::<T>(v, buf),
+        (TypeInfoKind::SignedFixedPoint(width), v) => {
+            put_signed_value::<T>(&fixed_point_phy_value(v, *width, true), buf)
+        }
+        (TypeInfoKind::Unsigned(_), v) => put_unsigned_value::<T>(v, buf),
+        (TypeInfoKind::UnsignedFixedPoint(width), v) => {
+            put_unsigned_value::<T>(&fixed_point_phy_value(v, *width, false), buf)
+        }
+        (TypeInfoKind::Float(_), v) => put_float_value::<T>(v, buf),
+        (element, v) => error!("found invalid array element {:?} for {:?}", v, element),
+    }
+}
+fn put_float_value<T: ByteOrder>(value: &Value, buf: &mut Vec<u8>) {
+    match value {
+        Value::F16(v) => {
+            let mut b = [0; 2];
+            T::write_u16(&mut b, f32_to_f16(*v));
+            buf.put_slice(&b)
+        }
+        Value::F32(v) => {
+            let mut b = [0; 4];
+            T::write_f32(&mut b, *v);
+            buf.put_slice(&b)
+        }
+        Value::F64(v) => {
+            let mut b = [0; 8];
+            T::write_f64(&mut b, *v);
+            buf.put_slice(&b)
+        }
+        _ => (),
+    }
+}
+/// the integer that is transmitted for a fixed point value
+fn fixed_point_phy_value(value: &Value, width: FloatWidth, signed: bool) -> Value {
+    match (value, width, signed) {
+        (Value::FixedPoint { raw, .. }, FloatWidth::Width64, true) => Value::I64(*raw),
+        (Value::FixedPoint { raw, .. }, FloatWidth::Width64, false) => Value::U64(*raw as u64),
+        (Value::FixedPoint { raw, .. }, _, true) => Value::I32(*raw as i32),
+        (Value::FixedPoint { raw, .. }, _, false) => Value::U32(*raw as u32),
+        (v, _, _) => v.clone(),
+    }
+}
+fn put_unsigned_value<T: ByteOrder>(value: &Value, buf: &mut Vec<u8>) {
     match value {
         Value::U8(v) => buf.put_u8(*v),
         Value::U16(v) => {
@@ -1115,7 +1613,7 @@ fn put_unsigned_value<T: ByteOrder>(value: &Value, buf: &mut BytesMut) {
         _ => (),
     }
 }
-fn put_signed_value<T: ByteOrder>(value: &Value, buf: &mut BytesMut) {
+fn put_signed_value<T: ByteOrder>(value: &Value, buf: &mut Vec<u8>) {
     match value {
         Value::I8(v) => buf.put_i8(*v),
         Value::I16(v) => {
@@ -1161,18 +1659,37 @@ fn put_signed_value<T: ByteOrder>(value: &Value, buf: &mut BytesMut) {
 /// Control messages are normal Dlt messages with a Standard Header, an Extended Header,
 /// and payload. The payload contains of the Service ID and the contained parameters.
 ///
-#[derive(Debug, Clone, PartialEq, Arbitrary, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(any(feature = "std", test), derive(Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub enum PayloadContent {
-    #[proptest(strategy = "argument_vector_strategy().prop_map(PayloadContent::Verbose)")]
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(strategy = "argument_vector_strategy().prop_map(PayloadContent::Verbose)")
+    )]
     Verbose(Vec<Argument>),
-    #[proptest(
-        strategy = "(0..10u32, prop::collection::vec(any::<u8>(), 0..5)).prop_map(|(a, b)| PayloadContent::NonVerbose(a,b))"
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(
+            strategy = "(0..10u32, prop::collection::vec(any::<u8>(), 0..5)).prop_map(|(a, b)| PayloadContent::NonVerbose(a,b))"
+        )
     )]
     NonVerbose(u32, Vec<u8>), // (message_id, payload)
-    #[proptest(
-        strategy = "(any::<ControlType>(), prop::collection::vec(any::<u8>(), 0..5)).prop_map(|(a, b)| PayloadContent::ControlMsg(a,b))"
+    #[cfg_attr(
+        any(feature = "std", test),
+        proptest(
+            strategy = "(any::<ControlType>(), prop::collection::vec(any::<u8>(), 0..5)).prop_map(|(a, b)| PayloadContent::ControlMsg(a,b))"
+        )
     )]
     ControlMsg(ControlType, Vec<u8>),
+    /// the payload bytes as they were received, kept with `PayloadMode::Raw`
+    /// until `Message::decode_payload` is called
+    #[cfg_attr(any(feature = "std", test), proptest(skip))]
+    Raw(Vec<u8>),
+    /// the length of a payload that was dropped with `PayloadMode::Skip`,
+    /// it is encoded as zeros
+    #[cfg_attr(any(feature = "std", test), proptest(skip))]
+    Skipped(u16),
 }
 fn payload_content_len<T: ByteOrder>(content: &PayloadContent) -> usize {
     match content {
@@ -1185,17 +1702,20 @@ fn payload_content_len<T: ByteOrder>(content: &PayloadContent) -> usize {
         }),
         PayloadContent::NonVerbose(_id, payload) => 4usize + payload.len(),
         PayloadContent::ControlMsg(_id, payload) => 1usize + payload.len(),
+        PayloadContent::Raw(payload) => payload.len(),
+        PayloadContent::Skipped(length) => *length as usize,
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Payload2 {
     pub payload_content: PayloadContent,
 }
 impl Payload2 {
     pub fn arg_count(&self) -> u8 {
         match &self.payload_content {
-            PayloadContent::Verbose(args) => std::cmp::min(args.len() as u8, u8::max_value()),
+            PayloadContent::Verbose(args) => args.len().min(u8::MAX as usize) as u8,
             _ => 0,
         }
     }
@@ -1222,7 +1742,7 @@ impl Payload2 {
     }
 
     pub(crate) fn as_bytes<T: ByteOrder>(&self) -> Vec<u8> {
-        let mut buf = BytesMut::with_capacity(payload_content_len::<T>(&self.payload_content));
+        let mut buf = Vec::with_capacity(payload_content_len::<T>(&self.payload_content));
         match &self.payload_content {
             PayloadContent::Verbose(args) => {
                 for arg in args {
@@ -1248,18 +1768,31 @@ impl Payload2 {
                 buf.put_u8(ctrl_id.value());
                 buf.extend_from_slice(&payload[..]);
             }
+            PayloadContent::Raw(payload) => buf.extend_from_slice(&payload[..]),
+            PayloadContent::Skipped(length) => buf.extend_from_slice(&vec![0; *length as usize]),
         }
-        buf.to_vec()
+        buf
     }
 }
 
+/// A complete DLT message
+///
+/// Messages serialize with the serde defaults, enabling the `serde` feature
+/// also makes them deserializable. In JSON this means:
+/// * enums are externally tagged by their variant (`{"U32":42}`, `{"Log":"Warn"}`),
+///   variants without data are plain strings (`"Big"`)
+/// * binary data (raw values, non-verbose and control payloads) is an array of bytes
+/// * `fibex_metadata` is not serialized and is `None` after deserialization
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Message {
     pub storage_header: Option<StorageHeader>,
     pub header: StandardHeader,
+    /// `None` if the UEH bit of the header type is clear. Such a message has
+    /// no App-ID, Context-ID or message type and its payload is non-verbose
     pub extended_header: Option<ExtendedHeader>,
     pub payload: Payload2,
-    #[serde(skip_serializing)]
+    #[serde(skip)]
     pub fibex_metadata: Option<Rc<FibexMetadata>>,
 }
 pub const DLT_COLUMN_SENTINAL: char = '\u{0004}';
@@ -1267,12 +1800,14 @@ pub const DLT_ARGUMENT_SENTINAL: char = '\u{0005}';
 pub const DLT_NEWLINE_SENTINAL_SLICE: &[u8] = &[0x6];
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct ExtendedHeaderConfig {
     pub message_type: MessageType,
     pub app_id: String,
     pub context_id: String,
 }
 #[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct MessageConfig {
     pub version: u8,
     pub counter: u8,
@@ -1348,11 +1883,11 @@ impl Message {
         let mut capacity = self.header.overall_length() as u64;
         let mut buf = if let Some(storage_header) = &self.storage_header {
             capacity += STORAGE_HEADER_LENGTH;
-            let mut b = BytesMut::with_capacity(capacity as usize);
+            let mut b = Vec::with_capacity(capacity as usize);
             b.extend_from_slice(&storage_header.as_bytes()[..]);
             b
         } else {
-            BytesMut::with_capacity(capacity as usize)
+            Vec::with_capacity(capacity as usize)
         };
         dbg_bytes("header", &self.header.as_bytes());
         buf.extend_from_slice(&self.header.as_bytes());
@@ -1371,25 +1906,191 @@ impl Message {
             buf.extend_from_slice(&little_endian_payload);
         }
 
-        buf.to_vec()
+        buf
+    }
+
+    /// write the encoded message (including the storage header if present) to `writer`
+    ///
+    /// returns the number of bytes written
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<usize> {
+        let bytes = self.as_bytes();
+        writer.write_all(&bytes)?;
+        Ok(bytes.len())
     }
 
     pub fn byte_len(&self) -> u16 {
         self.header.overall_length()
     }
 
+    /// true if the VERB bit of the extended header is set
+    ///
+    /// messages without extended header are always non-verbose
+    pub fn is_verbose(&self) -> bool {
+        match &self.extended_header {
+            Some(h) => h.verbose,
+            None => false,
+        }
+    }
+
+    /// the message type from the extended header, `None` if there is no extended header
+    ///
+    /// the variants carry the type info of the message: the log level, the kind
+    /// of application or network trace, or whether a control message is a
+    /// request or a response
+    pub fn message_type(&self) -> Option<MessageType> {
+        self.extended_header
+            .as_ref()
+            .map(|h| h.message_type.clone())
+    }
+
+    /// the arguments of a non-verbose message, decoded with the attached FIBEX metadata
+    ///
+    /// returns `None` if the message is not non-verbose or no FIBEX metadata is available
+    pub fn non_verbose_arguments(&self) -> Option<Vec<Argument>> {
+        match (&self.payload.payload_content, &self.fibex_metadata) {
+            #[cfg(not(feature = "std"))]
+            (PayloadContent::NonVerbose(..), Some(fibex_metadata)) => fibex_metadata.unavailable(),
+            #[cfg(feature = "std")]
+            (PayloadContent::NonVerbose(id, data), Some(fibex_metadata)) => {
+                Some(dlt_non_verbose_arguments(
+                    fibex_metadata,
+                    self.extended_header.as_ref(),
+                    *id,
+                    data,
+                    self.header.endianness,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// the message as `DltRecord`
+    ///
+    /// non-verbose messages are decoded with the attached FIBEX metadata, without
+    /// it their data is a single raw argument. The payload of control messages and
+    /// payloads kept with `PayloadMode::Raw` become a raw argument as well, a
+    /// skipped payload has no arguments.
+    pub fn to_record(&self) -> DltRecord {
+        let named_values = |args: Vec<Argument>| -> Vec<(Option<String>, Value)> {
+            args.into_iter().map(|arg| (arg.name, arg.value)).collect()
+        };
+        let arguments = match &self.payload.payload_content {
+            PayloadContent::Verbose(args) => named_values(args.clone()),
+            PayloadContent::NonVerbose(_, data) => match self.non_verbose_arguments() {
+                Some(args) => named_values(args),
+                None => vec![(None, Value::Raw(data.clone()))],
+            },
+            PayloadContent::ControlMsg(_, data) | PayloadContent::Raw(data) => {
+                vec![(None, Value::Raw(data.clone()))]
+            }
+            PayloadContent::Skipped(_) => vec![],
+        };
+        let message_type = self.message_type();
+        DltRecord {
+            time_us: self.storage_header.as_ref().map(|h| h.timestamp.as_us()),
+            timestamp: self.header.timestamp,
+            ecu_id: self
+                .header
+                .ecu_id
+                .clone()
+                .or_else(|| self.storage_header.as_ref().map(|h| h.ecu_id.clone())),
+            app_id: self
+                .extended_header
+                .as_ref()
+                .map(|h| h.application_id.clone()),
+            context_id: self.extended_header.as_ref().map(|h| h.context_id.clone()),
+            log_level: match message_type {
+                Some(MessageType::Log(level)) => Some(level),
+                _ => None,
+            },
+            message_type,
+            counter: self.header.message_counter,
+            session_id: self.header.session_id,
+            message_id: match &self.payload.payload_content {
+                PayloadContent::NonVerbose(id, _) => Some(*id),
+                _ => None,
+            },
+            arguments,
+        }
+    }
+
+    /// the traced frame of a network trace message
+    ///
+    /// in verbose mode the frame is sent as one (payload only) or two (header and
+    /// payload) raw arguments, in non-verbose mode as two length prefixed blocks.
+    /// Returns `None` if this is not a network trace message
+    pub fn network_trace(&self) -> Option<Result<NetworkTrace, DltParseError>> {
+        let trace_type = match self.message_type() {
+            Some(MessageType::NetworkTrace(trace_type)) => trace_type,
+            _ => return None,
+        };
+        let parts = match &self.payload.payload_content {
+            PayloadContent::Verbose(args) => args
+                .iter()
+                .map(|arg| match &arg.value {
+                    Value::Raw(bytes) => Ok(bytes.clone()),
+                    v => Err(DltParseError::ParsingHickup {
+                        reason: format!("network trace with non raw argument {:?}", v),
+                    }),
+                })
+                .collect::<Result<Vec<Vec<u8>>, DltParseError>>(),
+            PayloadContent::NonVerbose(msg_id, data) => {
+                let mut payload = Vec::with_capacity(data.len() + 4);
+                if self.header.endianness == Endianness::Big {
+                    payload.extend_from_slice(&msg_id.to_be_bytes());
+                } else {
+                    payload.extend_from_slice(&msg_id.to_le_bytes());
+                }
+                payload.extend_from_slice(data);
+                length_prefixed_blocks(&payload, self.header.endianness)
+            }
+            PayloadContent::ControlMsg(_, _) => Err(DltParseError::ParsingHickup {
+                reason: "network trace with control message payload".into(),
+            }),
+            PayloadContent::Raw(_) | PayloadContent::Skipped(_) => {
+                Err(DltParseError::ParsingHickup {
+                    reason: "network trace payload was not decoded".into(),
+                })
+            }
+        };
+        Some(parts.and_then(|mut parts| {
+            let (header, payload) = match parts.len() {
+                1 => (vec![], parts.remove(0)),
+                2 => {
+                    let payload = parts.remove(1);
+                    (parts.remove(0), payload)
+                }
+                n => {
+                    return Err(DltParseError::ParsingHickup {
+                        reason: format!("network trace with {} parts", n),
+                    })
+                }
+            };
+            Ok(NetworkTrace {
+                trace_type,
+                header,
+                payload,
+            })
+        }))
+    }
+
     pub fn add_storage_header(mut self, time_stamp: Option<DltTimeStamp>) -> Self {
         let timestamp = match time_stamp {
             Some(ts) => ts,
+            #[cfg(feature = "std")]
             None => {
                 use std::time::{SystemTime, UNIX_EPOCH};
                 let now = SystemTime::now();
                 let since_the_epoch = now
                     .duration_since(UNIX_EPOCH)
-                    .unwrap_or(std::time::Duration::from_secs(0));
+                    .unwrap_or(Duration::from_secs(0));
                 let in_ms = since_the_epoch.as_millis();
                 DltTimeStamp::from_ms(in_ms as u64)
             }
+            // there is no clock without std
+            #[cfg(not(feature = "std"))]
+            None => DltTimeStamp::from_ms(0),
         };
         let ecu_id = self
             .header
@@ -1403,6 +2104,7 @@ impl Message {
         self
     }
 
+    #[cfg(feature = "std")]
     pub(crate) fn write_app_id_context_id_and_message_type(
         &self,
         f: &mut fmt::Formatter,
@@ -1432,6 +2134,220 @@ impl Message {
     }
 }
 
+/// Borrowed counterpart of `StorageHeader`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageHeaderRef<'a> {
+    pub timestamp: DltTimeStamp,
+    pub ecu_id: &'a str,
+}
+impl<'a> StorageHeaderRef<'a> {
+    pub fn to_owned(&self) -> StorageHeader {
+        StorageHeader {
+            timestamp: self.timestamp.clone(),
+            ecu_id: self.ecu_id.to_string(),
+        }
+    }
+}
+
+/// Borrowed counterpart of `StandardHeader`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StandardHeaderRef<'a> {
+    pub version: u8,
+    pub endianness: Endianness,
+    pub has_extended_header: bool,
+    pub message_counter: u8,
+    pub ecu_id: Option<&'a str>,
+    pub session_id: Option<u32>,
+    pub timestamp: Option<u32>,
+    pub payload_length: u16,
+}
+impl<'a> StandardHeaderRef<'a> {
+    /// the protocol version (VERS bits of the header type)
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+    pub fn to_owned(&self) -> StandardHeader {
+        StandardHeader::new(
+            self.version,
+            self.endianness,
+            self.message_counter,
+            self.has_extended_header,
+            self.payload_length,
+            self.ecu_id.map(|id| id.to_string()),
+            self.session_id,
+            self.timestamp,
+        )
+    }
+}
+
+/// Borrowed counterpart of `ExtendedHeader`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedHeaderRef<'a> {
+    pub verbose: bool,
+    pub argument_count: u8,
+    pub message_type: MessageType,
+    pub application_id: &'a str,
+    pub context_id: &'a str,
+}
+impl<'a> ExtendedHeaderRef<'a> {
+    pub fn to_owned(&self) -> ExtendedHeader {
+        ExtendedHeader {
+            verbose: self.verbose,
+            argument_count: self.argument_count,
+            message_type: self.message_type.clone(),
+            application_id: self.application_id.to_string(),
+            context_id: self.context_id.to_string(),
+        }
+    }
+}
+
+/// Borrowed counterpart of `Value`
+///
+/// strings and raw data point into the input, all other values are
+/// small enough to be stored directly (strings are only copied if they
+/// needed to be converted from Latin-1 or contained invalid UTF-8)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    StringVal(Cow<'a, str>),
+    Raw(&'a [u8]),
+    Scalar(Value),
+}
+impl<'a> ValueRef<'a> {
+    pub fn to_owned(&self) -> Value {
+        match self {
+            ValueRef::StringVal(s) => Value::StringVal(s.to_string()),
+            ValueRef::Raw(bytes) => Value::Raw(bytes.to_vec()),
+            ValueRef::Scalar(v) => v.clone(),
+        }
+    }
+}
+
+/// Borrowed counterpart of `Argument`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentRef<'a> {
+    pub type_info: TypeInfo,
+    pub name: Option<&'a str>,
+    pub unit: Option<&'a str>,
+    pub fixed_point: Option<FixedPoint>,
+    pub value: ValueRef<'a>,
+}
+impl<'a> ArgumentRef<'a> {
+    /// see `Argument::type_info_raw`
+    pub fn type_info_raw(&self) -> u32 {
+        self.type_info.as_u32()
+    }
+    pub fn to_owned(&self) -> Argument {
+        Argument {
+            type_info: self.type_info.clone(),
+            name: self.name.map(|n| n.to_string()),
+            unit: self.unit.map(|u| u.to_string()),
+            fixed_point: self.fixed_point.clone(),
+            value: self.value.to_owned(),
+        }
+    }
+}
+
+/// The arguments of a verbose message that are not yet decoded
+///
+/// arguments are only decoded while iterating so a pass over the
+/// headers does not need to look at the payload at all
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentsRef<'a> {
+    pub(crate) endianness: Endianness,
+    pub(crate) count: u8,
+    pub(crate) data: &'a [u8],
+}
+impl<'a> ArgumentsRef<'a> {
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    /// decode the arguments one by one, iteration stops after the first error
+    pub fn iter(&self) -> ArgumentsIter<'a> {
+        ArgumentsIter {
+            endianness: self.endianness,
+            remaining: self.count,
+            data: self.data,
+        }
+    }
+}
+
+pub struct ArgumentsIter<'a> {
+    endianness: Endianness,
+    remaining: u8,
+    data: &'a [u8],
+}
+impl<'a> Iterator for ArgumentsIter<'a> {
+    type Item = Result<ArgumentRef<'a>, DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let res = if self.endianness == Endianness::Big {
+            payload_argument_ref::<BigEndian>(self.data)
+        } else {
+            payload_argument_ref::<LittleEndian>(self.data)
+        };
+        match res {
+            Ok((rest, arg)) => {
+                self.data = rest;
+                Some(Ok(arg))
+            }
+            Err(e) => {
+                self.remaining = 0;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Borrowed counterpart of `PayloadContent`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadRef<'a> {
+    Verbose(ArgumentsRef<'a>),
+    NonVerbose(u32, &'a [u8]), // (message_id, payload)
+    ControlMsg(ControlType, &'a [u8]),
+}
+
+/// A DLT message that borrows ids, strings and raw data from the parsed input
+///
+/// use `to_owned` to get an owned `Message`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageRef<'a> {
+    pub storage_header: Option<StorageHeaderRef<'a>>,
+    pub header: StandardHeaderRef<'a>,
+    pub extended_header: Option<ExtendedHeaderRef<'a>>,
+    pub payload: PayloadRef<'a>,
+}
+impl<'a> MessageRef<'a> {
+    /// all arguments are decoded, fails if the payload does not contain
+    /// the arguments the header announced
+    pub fn to_owned(&self) -> Result<Message, DltParseError> {
+        let payload_content = match &self.payload {
+            PayloadRef::Verbose(arguments) => PayloadContent::Verbose(
+                arguments
+                    .iter()
+                    .map(|arg| arg.map(|a| a.to_owned()))
+                    .collect::<Result<Vec<Argument>, DltParseError>>()?,
+            ),
+            PayloadRef::NonVerbose(id, data) => PayloadContent::NonVerbose(*id, data.to_vec()),
+            PayloadRef::ControlMsg(ctrl_type, data) => {
+                PayloadContent::ControlMsg(ctrl_type.clone(), data.to_vec())
+            }
+        };
+        Ok(Message {
+            storage_header: self.storage_header.as_ref().map(|h| h.to_owned()),
+            header: self.header.to_owned(),
+            extended_header: self.extended_header.as_ref().map(|h| h.to_owned()),
+            payload: Payload2 { payload_content },
+            fibex_metadata: None,
+        })
+    }
+}
+
 impl From<&LogLevel> for u8 {
     fn from(t: &LogLevel) -> Self {
         let mut res: u8 = 0;
@@ -1482,6 +2398,8 @@ pub const WITH_ECU_ID_FLAG: u8 = 1 << 2;
 pub const WITH_SESSION_ID_FLAG: u8 = 1 << 3;
 pub const WITH_TIMESTAMP_FLAG: u8 = 1 << 4;
 pub const HEADER_MIN_LENGTH: u16 = 4;
+/// the only protocol version defined by the DLT standard
+pub const DLT_VERSION: u8 = 1;
 
 // Verbose Mode
 
@@ -1495,7 +2413,7 @@ pub const TYPE_INFO_BOOL_FLAG: u32 = 1 << 4;
 pub const TYPE_INFO_SINT_FLAG: u32 = 1 << 5;
 pub const TYPE_INFO_UINT_FLAG: u32 = 1 << 6;
 pub const TYPE_INFO_FLOAT_FLAG: u32 = 1 << 7;
-pub const _TYPE_INFO_ARRAY_FLAG: u32 = 1 << 8;
+pub const TYPE_INFO_ARRAY_FLAG: u32 = 1 << 8;
 pub const TYPE_INFO_STRING_FLAG: u32 = 1 << 9;
 pub const TYPE_INFO_RAW_FLAG: u32 = 1 << 10;
 pub const TYPE_INFO_VARIABLE_INFO: u32 = 1 << 11;
@@ -1519,6 +2437,11 @@ pub fn calculate_standard_header_length(header_type: u8) -> u16 {
     length
 }
 
+/// the protocol version is stored in the upper 3 bits of the header type
+pub fn header_type_version(header_type: u8) -> u8 {
+    header_type >> 5 & 0b111
+}
+
 // TODO use header struct not u8
 pub fn calculate_all_headers_length(header_type: u8) -> u16 {
     let mut length = calculate_standard_header_length(header_type);
@@ -1528,7 +2451,7 @@ pub fn calculate_all_headers_length(header_type: u8) -> u16 {
     length
 }
 
-pub fn zero_terminated_string(raw: &[u8]) -> Result<String, Error> {
+pub fn zero_terminated_string(raw: &[u8]) -> Result<String, DltParseError> {
     let nul_range_end = raw
         .iter()
         .position(|&c| c == b'\0')
@@ -1537,7 +2460,7 @@ pub fn zero_terminated_string(raw: &[u8]) -> Result<String, Error> {
         .map(|v| v.to_owned())
         .map_err(|e| {
             report_warning(format!("Invalid zero_terminated_string: {}", e));
-            Error::new(io::ErrorKind::Other, e)
+            DltParseError::Utf8(e)
         })
 }
 
@@ -1560,8 +2483,8 @@ pub fn u8_to_log_level(v: u8) -> Option<LogLevel> {
     }
 }
 impl TryFrom<u8> for LogLevel {
-    type Error = Error;
-    fn try_from(message_info: u8) -> Result<LogLevel, Error> {
+    type Error = DltParseError;
+    fn try_from(message_info: u8) -> Result<LogLevel, DltParseError> {
         let raw = message_info >> 4;
         let level = u8_to_log_level(raw);
         match level {
@@ -1585,8 +2508,8 @@ impl From<&ApplicationTraceType> for u8 {
 }
 
 impl TryFrom<u8> for ApplicationTraceType {
-    type Error = Error;
-    fn try_from(message_info: u8) -> Result<ApplicationTraceType, Error> {
+    type Error = DltParseError;
+    fn try_from(message_info: u8) -> Result<ApplicationTraceType, DltParseError> {
         match message_info >> 4 {
             1 => Ok(ApplicationTraceType::Variable),
             2 => Ok(ApplicationTraceType::FunctionIn),
@@ -1614,8 +2537,8 @@ impl From<&NetworkTraceType> for u8 {
 }
 
 impl TryFrom<u8> for NetworkTraceType {
-    type Error = Error;
-    fn try_from(message_info: u8) -> Result<NetworkTraceType, Error> {
+    type Error = DltParseError;
+    fn try_from(message_info: u8) -> Result<NetworkTraceType, DltParseError> {
         match message_info >> 4 {
             0 => Ok(NetworkTraceType::Invalid),
             1 => Ok(NetworkTraceType::Ipc),
@@ -1641,8 +2564,8 @@ impl From<&ControlType> for u8 {
     }
 }
 impl TryFrom<u8> for ControlType {
-    type Error = Error;
-    fn try_from(message_info: u8) -> Result<ControlType, Error> {
+    type Error = DltParseError;
+    fn try_from(message_info: u8) -> Result<ControlType, DltParseError> {
         match message_info >> 4 {
             1 => Ok(ControlType::Request),
             2 => Ok(ControlType::Response),
@@ -1666,11 +2589,26 @@ impl From<&MessageType> for u8 {
         }
     }
 }
+impl MessageType {
+    /// true for log messages that are less severe than `level`
+    /// all other message types are never skipped
+    pub fn skip_with_level(&self, level: LogLevel) -> bool {
+        match self {
+            MessageType::Log(n) => match (*n, level) {
+                (LogLevel::Invalid(a), LogLevel::Invalid(b)) => a < b,
+                (LogLevel::Invalid(_), _) => false,
+                (_, LogLevel::Invalid(_)) => true,
+                (n, level) => level < n,
+            },
+            _ => false,
+        }
+    }
+}
 /// The Message Type is encoded in bit 1-3 of the MessageInfo
 /// xxxx 321x
 impl TryFrom<u8> for MessageType {
-    type Error = Error;
-    fn try_from(message_info: u8) -> Result<MessageType, Error> {
+    type Error = DltParseError;
+    fn try_from(message_info: u8) -> Result<MessageType, DltParseError> {
         match (message_info >> 1) & 0b111 {
             DLT_TYPE_LOG => Ok(MessageType::Log(LogLevel::try_from(message_info)?)),
             DLT_TYPE_APP_TRACE => Ok(MessageType::ApplicationTrace(
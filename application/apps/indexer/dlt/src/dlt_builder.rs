@@ -0,0 +1,242 @@
+use crate::dlt::*;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use anyhow::anyhow;
+
+/// Assembles a `Message` from ids, message type and arguments
+///
+/// all header flags, the argument count and the length fields are derived
+/// from what was set, so the result can always be encoded with `as_bytes`
+/// and parsed again.
+#[derive(Debug, Clone)]
+pub struct MessageBuilder {
+    ecu_id: String,
+    app_id: String,
+    context_id: String,
+    message_type: MessageType,
+    endianness: Endianness,
+    counter: u8,
+    session_id: Option<u32>,
+    timestamp: Option<u32>,
+    storage_header: Option<DltTimeStamp>,
+    payload: PayloadContent,
+}
+
+impl MessageBuilder {
+    /// a verbose info message without arguments
+    pub fn new(ecu_id: &str, app_id: &str, context_id: &str) -> Self {
+        MessageBuilder {
+            ecu_id: ecu_id.to_string(),
+            app_id: app_id.to_string(),
+            context_id: context_id.to_string(),
+            message_type: MessageType::Log(LogLevel::Info),
+            endianness: Endianness::Big,
+            counter: 0,
+            session_id: None,
+            timestamp: None,
+            storage_header: None,
+            payload: PayloadContent::Verbose(vec![]),
+        }
+    }
+
+    pub fn log_level(self, level: LogLevel) -> Self {
+        self.message_type(MessageType::Log(level))
+    }
+
+    pub fn message_type(mut self, message_type: MessageType) -> Self {
+        self.message_type = message_type;
+        self
+    }
+
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    pub fn counter(mut self, counter: u8) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn session_id(mut self, session_id: u32) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// timestamp in 0.1 ms since the start of the ECU
+    pub fn timestamp(mut self, timestamp: u32) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// prefix the message with a storage header (with the same ECU id)
+    pub fn storage_header(mut self, timestamp: DltTimeStamp) -> Self {
+        self.storage_header = Some(timestamp);
+        self
+    }
+
+    /// append an argument, a non-verbose payload is replaced
+    ///
+    /// strings, bools, integers, floats and raw bytes (`Vec<u8>`) can be
+    /// used directly, anything else can be described with an `Argument`
+    pub fn arg<A: Into<Argument>>(mut self, argument: A) -> Self {
+        match &mut self.payload {
+            PayloadContent::Verbose(arguments) => arguments.push(argument.into()),
+            _ => self.payload = PayloadContent::Verbose(vec![argument.into()]),
+        }
+        self
+    }
+
+    pub fn args<A: Into<Argument>, I: IntoIterator<Item = A>>(self, arguments: I) -> Self {
+        arguments
+            .into_iter()
+            .fold(self, |builder, a| builder.arg(a))
+    }
+
+    /// use a non-verbose payload instead of arguments
+    pub fn non_verbose(mut self, message_id: u32, data: Vec<u8>) -> Self {
+        self.payload = PayloadContent::NonVerbose(message_id, data);
+        self
+    }
+
+    /// fails if an id is longer than 4 bytes, the message would be too big
+    /// or it has too many arguments
+    pub fn build(self) -> Result<Message, anyhow::Error> {
+        for id in &[&self.ecu_id, &self.app_id, &self.context_id] {
+            if id.len() > 4 {
+                return Err(anyhow!("id \"{}\" is longer than 4 bytes", id));
+            }
+        }
+        if let PayloadContent::Verbose(arguments) = &self.payload {
+            if arguments.len() > u8::MAX as usize {
+                return Err(anyhow!(
+                    "{} arguments, at most {} are possible",
+                    arguments.len(),
+                    u8::MAX
+                ));
+            }
+        }
+        let payload = Payload2 {
+            payload_content: self.payload,
+        };
+        let payload_length = match self.endianness {
+            Endianness::Big => payload.as_bytes::<byteorder::BigEndian>().len(),
+            Endianness::Little => payload.as_bytes::<byteorder::LittleEndian>().len(),
+        };
+        let ecu_id = self.ecu_id;
+        let storage_header = self.storage_header.map(|timestamp| StorageHeader {
+            timestamp,
+            ecu_id: ecu_id.clone(),
+        });
+        let msg = Message::new(
+            MessageConfig {
+                version: DLT_VERSION,
+                counter: self.counter,
+                endianness: self.endianness,
+                ecu_id: Some(ecu_id),
+                session_id: self.session_id,
+                timestamp: self.timestamp,
+                payload,
+                extended_header_info: Some(ExtendedHeaderConfig {
+                    message_type: self.message_type,
+                    app_id: self.app_id,
+                    context_id: self.context_id,
+                }),
+            },
+            None,
+            storage_header,
+        );
+        let headers_length = calculate_all_headers_length(msg.header.header_type_byte()) as usize;
+        if headers_length + payload_length > u16::MAX as usize {
+            return Err(anyhow!(
+                "message with {} bytes payload exceeds the maximum length of {} bytes",
+                payload_length,
+                u16::MAX
+            ));
+        }
+        Ok(msg)
+    }
+}
+
+fn argument(kind: TypeInfoKind, value: Value) -> Argument {
+    Argument {
+        type_info: TypeInfo {
+            kind,
+            coding: StringCoding::UTF8,
+            has_variable_info: false,
+            has_trace_info: false,
+            unmodelled_bits: 0,
+        },
+        name: None,
+        unit: None,
+        fixed_point: None,
+        value,
+    }
+}
+
+macro_rules! argument_from {
+    ($t:ty, $kind:expr, $value:path) => {
+        impl From<$t> for Argument {
+            fn from(v: $t) -> Self {
+                argument($kind, $value(v))
+            }
+        }
+    };
+}
+
+argument_from!(
+    u8,
+    TypeInfoKind::Unsigned(TypeLength::BitLength8),
+    Value::U8
+);
+argument_from!(
+    u16,
+    TypeInfoKind::Unsigned(TypeLength::BitLength16),
+    Value::U16
+);
+argument_from!(
+    u32,
+    TypeInfoKind::Unsigned(TypeLength::BitLength32),
+    Value::U32
+);
+argument_from!(
+    u64,
+    TypeInfoKind::Unsigned(TypeLength::BitLength64),
+    Value::U64
+);
+argument_from!(i8, TypeInfoKind::Signed(TypeLength::BitLength8), Value::I8);
+argument_from!(
+    i16,
+    TypeInfoKind::Signed(TypeLength::BitLength16),
+    Value::I16
+);
+argument_from!(
+    i32,
+    TypeInfoKind::Signed(TypeLength::BitLength32),
+    Value::I32
+);
+argument_from!(
+    i64,
+    TypeInfoKind::Signed(TypeLength::BitLength64),
+    Value::I64
+);
+argument_from!(f32, TypeInfoKind::Float(FloatWidth::Width32), Value::F32);
+argument_from!(f64, TypeInfoKind::Float(FloatWidth::Width64), Value::F64);
+argument_from!(String, TypeInfoKind::StringType, Value::StringVal);
+argument_from!(Vec<u8>, TypeInfoKind::Raw, Value::Raw);
+
+impl From<bool> for Argument {
+    fn from(v: bool) -> Self {
+        argument(TypeInfoKind::Bool, Value::Bool(v as u8))
+    }
+}
+
+impl From<&str> for Argument {
+    fn from(v: &str) -> Self {
+        v.to_string().into()
+    }
+}
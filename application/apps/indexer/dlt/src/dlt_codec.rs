@@ -0,0 +1,74 @@
+use crate::{
+    dlt::Message,
+    dlt_parse::{DltParseError, StorageHeaderMode},
+    dlt_stream::{DltStreamParser, Frame},
+    fibex::FibexMetadata,
+    filtering,
+};
+use bytes1::{Buf, BytesMut};
+use std::rc::Rc;
+use tokio_util::codec::Decoder;
+
+/// `Decoder` for DLT messages, e.g. to read them from a `TcpStream` with
+/// `tokio_util::codec::FramedRead`
+///
+/// The framing is the one of the `DltStreamParser`: invalid data is skipped
+/// and filtered out messages are dropped, so every decoded item is a message.
+/// An incomplete message stays in the buffer until the rest of it is read.
+/// Because of the FIBEX metadata the codec is not `Send`, use it on a
+/// `LocalSet` or within a single task.
+pub struct DltCodec<'a> {
+    parser: DltStreamParser<'a>,
+}
+
+impl<'a> DltCodec<'a> {
+    pub fn new(
+        storage_header_mode: StorageHeaderMode,
+        filter_config: Option<filtering::ProcessedDltFilterConfig>,
+        fibex_metadata: Option<Rc<FibexMetadata>>,
+    ) -> Self {
+        DltCodec::from_parser(DltStreamParser::new(
+            storage_header_mode,
+            filter_config,
+            fibex_metadata,
+        ))
+    }
+
+    /// decode with the settings of `parser` (e.g. `with_max_message_size` or
+    /// `with_progress`)
+    ///
+    /// bytes that are still buffered in the parser are not used
+    pub fn from_parser(parser: DltStreamParser<'a>) -> Self {
+        DltCodec { parser }
+    }
+}
+
+impl<'a> Decoder for DltCodec<'a> {
+    type Item = Message;
+    type Error = DltParseError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, DltParseError> {
+        loop {
+            match self.parser.next_frame(&src[..]) {
+                Frame::Message(msg, length) => {
+                    src.advance(length);
+                    return Ok(Some(msg));
+                }
+                Frame::Skipped { consumed, .. } => src.advance(consumed),
+                Frame::Incomplete => return Ok(None),
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Message>, DltParseError> {
+        let decoded = self.decode(src)?;
+        if decoded.is_none() && !src.is_empty() {
+            warn!("stream ended within a message, dropped {} bytes", src.len());
+            src.clear();
+        }
+        if decoded.is_none() {
+            self.parser.finish_progress();
+        }
+        Ok(decoded)
+    }
+}
@@ -0,0 +1,426 @@
+use crate::{
+    dlt::{
+        u8_to_log_level, ControlType, Endianness, LogLevel, Message, MessageType, PayloadContent,
+    },
+    dlt_parse::{count, parse_ecu_id, DltParseError, NomByteOrder},
+};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use byteorder::{BigEndian, LittleEndian};
+use nom::{
+    bytes::streaming::take,
+    number::streaming::{be_i8, be_u8},
+    IResult,
+};
+use serde::Serialize;
+
+pub const SERVICE_ID_SET_LOG_LEVEL: u32 = 0x01;
+pub const SERVICE_ID_GET_LOG_INFO: u32 = 0x03;
+pub const SERVICE_ID_GET_DEFAULT_LOG_LEVEL: u32 = 0x04;
+pub const SERVICE_ID_STORE_CONFIG: u32 = 0x05;
+pub const SERVICE_ID_SET_DEFAULT_LOG_LEVEL: u32 = 0x11;
+pub const SERVICE_ID_GET_SOFTWARE_VERSION: u32 = 0x13;
+
+/// GetLogInfo option that includes log level, trace status and descriptions
+const LOG_INFO_WITH_DESCRIPTIONS: u8 = 7;
+
+/// status byte at the start of every control response
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum ControlStatus {
+    Ok,
+    NotSupported,
+    Error,
+    /// any other status (GetLogInfo responses echo the requested options here)
+    Other(u8),
+}
+
+impl From<u8> for ControlStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            0 => ControlStatus::Ok,
+            1 => ControlStatus::NotSupported,
+            2 => ControlStatus::Error,
+            n => ControlStatus::Other(n),
+        }
+    }
+}
+
+/// a log level as used in control messages, which adds values for
+/// "use the default" and "switched off" to the regular levels
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum ControlLogLevel {
+    /// -1
+    Default,
+    /// 0
+    Off,
+    Level(LogLevel),
+}
+
+impl From<i8> for ControlLogLevel {
+    fn from(level: i8) -> Self {
+        match level {
+            -1 => ControlLogLevel::Default,
+            0 => ControlLogLevel::Off,
+            n => ControlLogLevel::Level(
+                u8_to_log_level(n as u8).unwrap_or(LogLevel::Invalid(n as u8)),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct ContextLogInfo {
+    pub context_id: String,
+    /// only present if log levels were requested
+    pub log_level: Option<ControlLogLevel>,
+    /// only present if trace states were requested
+    pub trace_status: Option<i8>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct ApplicationLogInfo {
+    pub app_id: String,
+    pub contexts: Vec<ContextLogInfo>,
+    pub description: Option<String>,
+}
+
+/// the decoded payload of a control message
+///
+/// only the most common services are decoded, everything else is
+/// kept as `Unknown` with the raw bytes after the service id
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum ControlMessage {
+    SetLogLevelRequest {
+        app_id: String,
+        context_id: String,
+        log_level: ControlLogLevel,
+        com_interface: String,
+    },
+    SetLogLevelResponse(ControlStatus),
+    GetLogInfoRequest {
+        options: u8,
+        app_id: String,
+        context_id: String,
+        com_interface: String,
+    },
+    GetLogInfoResponse {
+        status: ControlStatus,
+        /// empty if the status does not carry any log info
+        applications: Vec<ApplicationLogInfo>,
+        com_interface: Option<String>,
+    },
+    GetDefaultLogLevelRequest,
+    GetDefaultLogLevelResponse {
+        status: ControlStatus,
+        log_level: ControlLogLevel,
+    },
+    StoreConfigRequest,
+    StoreConfigResponse(ControlStatus),
+    SetDefaultLogLevelRequest {
+        log_level: ControlLogLevel,
+        com_interface: String,
+    },
+    SetDefaultLogLevelResponse(ControlStatus),
+    GetSoftwareVersionRequest,
+    GetSoftwareVersionResponse {
+        status: ControlStatus,
+        version: String,
+    },
+    Unknown {
+        service_id: u32,
+        payload: Vec<u8>,
+    },
+}
+
+impl Message {
+    /// decode the payload of a control message
+    ///
+    /// the payload is decoded on demand each time this is called, straight
+    /// from the stored control type and data without copying them
+    ///
+    /// returns `None` if this is not a control message
+    pub fn control_message(&self) -> Option<Result<ControlMessage, DltParseError>> {
+        let control_type = match self.message_type() {
+            Some(MessageType::Control(control_type)) => control_type,
+            _ => return None,
+        };
+        match &self.payload.payload_content {
+            PayloadContent::ControlMsg(first_byte, data) => Some(split_control_message(
+                &control_type,
+                first_byte.value(),
+                data,
+                self.header.endianness,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// decode a complete control message payload (starting with the service id)
+pub fn dlt_control_message(
+    control_type: &ControlType,
+    payload: &[u8],
+    endianness: Endianness,
+) -> Result<ControlMessage, DltParseError> {
+    let res = if endianness == Endianness::Big {
+        control_message::<BigEndian>(control_type, payload)
+    } else {
+        control_message::<LittleEndian>(control_type, payload)
+    };
+    let (_, msg) = res?;
+    Ok(msg)
+}
+
+/// decode a control message payload whose first byte was parsed as the
+/// control type of the payload, the rest of the service id is in `data`
+fn split_control_message(
+    control_type: &ControlType,
+    first_byte: u8,
+    data: &[u8],
+    endianness: Endianness,
+) -> Result<ControlMessage, DltParseError> {
+    let res: IResult<&[u8], &[u8]> = take(3usize)(data);
+    let (input, rest_of_id) = res?;
+    let id_bytes = [first_byte, rest_of_id[0], rest_of_id[1], rest_of_id[2]];
+    let res = if endianness == Endianness::Big {
+        service_message::<BigEndian>(control_type, u32::from_be_bytes(id_bytes), input)
+    } else {
+        service_message::<LittleEndian>(control_type, u32::from_le_bytes(id_bytes), input)
+    };
+    let (_, msg) = res?;
+    Ok(msg)
+}
+
+fn control_message<'a, T: NomByteOrder>(
+    control_type: &ControlType,
+    input: &'a [u8],
+) -> IResult<&'a [u8], ControlMessage> {
+    let (i, service_id) = T::parse_u32(input)?;
+    service_message::<T>(control_type, service_id, i)
+}
+
+fn service_message<'a, T: NomByteOrder>(
+    control_type: &ControlType,
+    service_id: u32,
+    i: &'a [u8],
+) -> IResult<&'a [u8], ControlMessage> {
+    match (service_id, control_type) {
+        (SERVICE_ID_SET_LOG_LEVEL, ControlType::Request) => {
+            let (i, app_id) = parse_ecu_id(i)?;
+            let (i, context_id) = parse_ecu_id(i)?;
+            let (i, log_level) = be_i8(i)?;
+            let (i, com_interface) = parse_ecu_id(i)?;
+            Ok((
+                i,
+                ControlMessage::SetLogLevelRequest {
+                    app_id: app_id.to_string(),
+                    context_id: context_id.to_string(),
+                    log_level: log_level.into(),
+                    com_interface: com_interface.to_string(),
+                },
+            ))
+        }
+        (SERVICE_ID_GET_LOG_INFO, ControlType::Request) => {
+            let (i, options) = be_u8(i)?;
+            let (i, app_id) = parse_ecu_id(i)?;
+            let (i, context_id) = parse_ecu_id(i)?;
+            let (i, com_interface) = parse_ecu_id(i)?;
+            Ok((
+                i,
+                ControlMessage::GetLogInfoRequest {
+                    options,
+                    app_id: app_id.to_string(),
+                    context_id: context_id.to_string(),
+                    com_interface: com_interface.to_string(),
+                },
+            ))
+        }
+        (SERVICE_ID_GET_LOG_INFO, ControlType::Response) => log_info_response::<T>(i),
+        (SERVICE_ID_GET_DEFAULT_LOG_LEVEL, ControlType::Request) => {
+            Ok((i, ControlMessage::GetDefaultLogLevelRequest))
+        }
+        (SERVICE_ID_GET_DEFAULT_LOG_LEVEL, ControlType::Response) => {
+            let (i, status) = be_u8(i)?;
+            let (i, log_level) = be_i8(i)?;
+            Ok((
+                i,
+                ControlMessage::GetDefaultLogLevelResponse {
+                    status: status.into(),
+                    log_level: log_level.into(),
+                },
+            ))
+        }
+        (SERVICE_ID_STORE_CONFIG, ControlType::Request) => {
+            Ok((i, ControlMessage::StoreConfigRequest))
+        }
+        (SERVICE_ID_SET_DEFAULT_LOG_LEVEL, ControlType::Request) => {
+            let (i, log_level) = be_i8(i)?;
+            let (i, com_interface) = parse_ecu_id(i)?;
+            Ok((
+                i,
+                ControlMessage::SetDefaultLogLevelRequest {
+                    log_level: log_level.into(),
+                    com_interface: com_interface.to_string(),
+                },
+            ))
+        }
+        (SERVICE_ID_GET_SOFTWARE_VERSION, ControlType::Request) => {
+            Ok((i, ControlMessage::GetSoftwareVersionRequest))
+        }
+        (SERVICE_ID_GET_SOFTWARE_VERSION, ControlType::Response) => {
+            let (i, status) = be_u8(i)?;
+            let (i, length) = T::parse_u32(i)?;
+            let (i, version) = take(length)(i)?;
+            Ok((
+                i,
+                ControlMessage::GetSoftwareVersionResponse {
+                    status: status.into(),
+                    version: lossy_string(version),
+                },
+            ))
+        }
+        (SERVICE_ID_SET_LOG_LEVEL, ControlType::Response)
+        | (SERVICE_ID_STORE_CONFIG, ControlType::Response)
+        | (SERVICE_ID_SET_DEFAULT_LOG_LEVEL, ControlType::Response) => {
+            let (i, status) = be_u8(i)?;
+            let status = ControlStatus::from(status);
+            let msg = match service_id {
+                SERVICE_ID_SET_LOG_LEVEL => ControlMessage::SetLogLevelResponse(status),
+                SERVICE_ID_STORE_CONFIG => ControlMessage::StoreConfigResponse(status),
+                _ => ControlMessage::SetDefaultLogLevelResponse(status),
+            };
+            Ok((i, msg))
+        }
+        _ => Ok((
+            &[],
+            ControlMessage::Unknown {
+                service_id,
+                payload: i.to_vec(),
+            },
+        )),
+    }
+}
+
+/// the GetLogInfo response lists all applications with their contexts.
+/// Depending on the status (which echoes the requested options) the
+/// entries contain log level, trace status and descriptions:
+/// * 3: only ids
+/// * 4: with log level
+/// * 5: with trace status
+/// * 6: with log level and trace status
+/// * 7: with log level, trace status and descriptions
+fn log_info_response<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], ControlMessage> {
+    let (i, status) = be_u8(input)?;
+    if !(3..=LOG_INFO_WITH_DESCRIPTIONS).contains(&status) {
+        return Ok((
+            i,
+            ControlMessage::GetLogInfoResponse {
+                status: status.into(),
+                applications: vec![],
+                com_interface: None,
+            },
+        ));
+    }
+    let with_log_level = status == 4 || status >= 6;
+    let with_trace_status = status >= 5;
+    let with_description = status == LOG_INFO_WITH_DESCRIPTIONS;
+    let (i, app_count) = T::parse_u16(i)?;
+    let (i, applications) = count(
+        |input| {
+            application_log_info::<T>(input, with_log_level, with_trace_status, with_description)
+        },
+        app_count as usize,
+    )(i)?;
+    let (i, com_interface) = parse_ecu_id(i)?;
+    Ok((
+        i,
+        ControlMessage::GetLogInfoResponse {
+            status: status.into(),
+            applications,
+            com_interface: Some(com_interface.to_string()),
+        },
+    ))
+}
+
+fn application_log_info<T: NomByteOrder>(
+    input: &[u8],
+    with_log_level: bool,
+    with_trace_status: bool,
+    with_description: bool,
+) -> IResult<&[u8], ApplicationLogInfo> {
+    let (i, app_id) = parse_ecu_id(input)?;
+    let (i, context_count) = T::parse_u16(i)?;
+    let (i, contexts) = count(
+        |input| context_log_info::<T>(input, with_log_level, with_trace_status, with_description),
+        context_count as usize,
+    )(i)?;
+    let (i, description) = optional_description::<T>(i, with_description)?;
+    Ok((
+        i,
+        ApplicationLogInfo {
+            app_id: app_id.to_string(),
+            contexts,
+            description,
+        },
+    ))
+}
+
+fn context_log_info<T: NomByteOrder>(
+    input: &[u8],
+    with_log_level: bool,
+    with_trace_status: bool,
+    with_description: bool,
+) -> IResult<&[u8], ContextLogInfo> {
+    let (i, context_id) = parse_ecu_id(input)?;
+    let (i, log_level) = if with_log_level {
+        let (i, level) = be_i8(i)?;
+        (i, Some(level.into()))
+    } else {
+        (i, None)
+    };
+    let (i, trace_status) = if with_trace_status {
+        let (i, status) = be_i8(i)?;
+        (i, Some(status))
+    } else {
+        (i, None)
+    };
+    let (i, description) = optional_description::<T>(i, with_description)?;
+    Ok((
+        i,
+        ContextLogInfo {
+            context_id: context_id.to_string(),
+            log_level,
+            trace_status,
+            description,
+        },
+    ))
+}
+
+fn optional_description<T: NomByteOrder>(
+    input: &[u8],
+    present: bool,
+) -> IResult<&[u8], Option<String>> {
+    if !present {
+        return Ok((input, None));
+    }
+    let (i, length) = T::parse_u16(input)?;
+    let (i, description) = take(length)(i)?;
+    Ok((i, Some(lossy_string(description))))
+}
+
+fn lossy_string(bytes: &[u8]) -> String {
+    let without_terminator = match bytes.iter().position(|b| *b == 0) {
+        Some(end) => &bytes[..end],
+        None => bytes,
+    };
+    String::from_utf8_lossy(without_terminator).into_owned()
+}
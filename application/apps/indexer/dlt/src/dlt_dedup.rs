@@ -0,0 +1,95 @@
+use crate::{
+    dlt::{Message, MessageRef},
+    dlt_parse::DltParseError,
+};
+
+/// What has to be equal for a message to count as repetition of the one before
+///
+/// the message counter is never compared, it changes with every message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatKey {
+    /// ECU-ID, session id, App-ID, Context-ID, message type and the payload
+    Content,
+    /// the content and the timestamps of the standard and the storage header
+    ContentAndTime,
+}
+
+impl RepeatKey {
+    /// whether `msg` repeats `previous`
+    ///
+    /// only the borrowed headers and the undecoded payload bytes are compared
+    pub fn is_repetition(self, previous: &MessageRef<'_>, msg: &MessageRef<'_>) -> bool {
+        let (a, b) = (&previous.header, &msg.header);
+        let same_content = a.version == b.version
+            && a.endianness == b.endianness
+            && a.ecu_id == b.ecu_id
+            && a.session_id == b.session_id
+            && a.payload_length == b.payload_length
+            && previous.storage_header.as_ref().map(|h| h.ecu_id)
+                == msg.storage_header.as_ref().map(|h| h.ecu_id)
+            && previous.extended_header == msg.extended_header
+            && previous.payload == msg.payload;
+        match self {
+            RepeatKey::Content => same_content,
+            RepeatKey::ContentAndTime => {
+                same_content
+                    && a.timestamp == b.timestamp
+                    && previous.storage_header.as_ref().map(|h| &h.timestamp)
+                        == msg.storage_header.as_ref().map(|h| &h.timestamp)
+            }
+        }
+    }
+}
+
+/// Iterator over messages where runs of the same message are collapsed
+///
+/// yields each message together with the number of times it occurred in a row
+/// (1 if it was not repeated). Only consecutive messages are collapsed, a message
+/// that shows up again after a different one starts a new run.
+pub struct CollapsedRepeats<'a, I> {
+    messages: I,
+    key: RepeatKey,
+    /// the first message after the last run, or the error that ended it
+    next: Option<Result<MessageRef<'a>, DltParseError>>,
+}
+
+/// collapse repeated messages of e.g. `dlt_parse::message_refs`
+///
+/// the messages are compared as `MessageRef`s, only the first message of every
+/// run is converted to an owned `Message`
+pub fn collapse_repeats<'a, I>(messages: I, key: RepeatKey) -> CollapsedRepeats<'a, I>
+where
+    I: Iterator<Item = Result<MessageRef<'a>, DltParseError>>,
+{
+    CollapsedRepeats {
+        messages,
+        key,
+        next: None,
+    }
+}
+
+impl<'a, I> Iterator for CollapsedRepeats<'a, I>
+where
+    I: Iterator<Item = Result<MessageRef<'a>, DltParseError>>,
+{
+    type Item = Result<(Message, usize), DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.next.take().or_else(|| self.messages.next())? {
+            Ok(msg) => msg,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut count = 1;
+        loop {
+            match self.messages.next() {
+                Some(Ok(msg)) if self.key.is_repetition(&first, &msg) => count += 1,
+                // an error is returned after the run that it ended
+                Some(next) => {
+                    self.next = Some(next);
+                    break;
+                }
+                None => break,
+            }
+        }
+        Some(first.to_owned().map(|msg| (msg, count)))
+    }
+}
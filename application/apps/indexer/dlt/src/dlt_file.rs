@@ -12,8 +12,9 @@
 use crate::{
     dlt::Message,
     dlt_parse::{
-        dlt_message, forward_to_next_storage_header, skip_storage_header, DltParseError,
-        ParsedMessage, DLT_MIN_BUFFER_SPACE, DLT_PATTERN_SIZE, DLT_READER_CAPACITY,
+        dlt_message_with_options, forward_to_next_storage_header, skip_storage_header,
+        DltParseError, FrameStep, FrameWalker, ParseOptions, ParsedMessage, Progress,
+        StorageHeaderMode, DLT_MIN_BUFFER_SPACE, DLT_READER_CAPACITY,
     },
     filtering,
 };
@@ -21,6 +22,7 @@ use anyhow::anyhow;
 use buf_redux::{policy::MinBuffered, BufReader as ReduxReader};
 use crossbeam_channel as cc;
 use crossbeam_channel::unbounded;
+use flate2::bufread::MultiGzDecoder;
 use indexer_base::{
     chunks::{ChunkFactory, ChunkResults},
     config::*,
@@ -29,17 +31,51 @@ use indexer_base::{
 };
 use std::{
     fs,
-    io::{BufRead, BufWriter, Write},
-    path::PathBuf,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
     rc::Rc,
 };
 
 use crate::fibex::FibexMetadata;
 
+/// the first bytes of every gzip file
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// open a dlt file for reading, gzip compressed files are decompressed on the fly
+///
+/// compressed files are recognized by their content, not by their name.
+/// The result can be used with `dlt_parse::messages_from_reader` without ever
+/// holding the whole decompressed content in memory.
+pub fn open_dlt_reader<P: AsRef<Path>>(path: P) -> io::Result<Box<dyn BufRead>> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
 pub async fn parse_dlt_file(
     in_file: PathBuf,
     filter_config: Option<filtering::ProcessedDltFilterConfig>,
     fibex_metadata: Option<Rc<FibexMetadata>>,
+) -> Result<Vec<Message>, DltParseError> {
+    parse_dlt_file_with_options(
+        in_file,
+        filter_config,
+        fibex_metadata,
+        ParseOptions::new(StorageHeaderMode::Required),
+    )
+    .await
+}
+
+/// like `parse_dlt_file` but the messages are parsed as `options` say
+/// (e.g. for storage headers with another magic)
+pub async fn parse_dlt_file_with_options(
+    in_file: PathBuf,
+    filter_config: Option<filtering::ProcessedDltFilterConfig>,
+    fibex_metadata: Option<Rc<FibexMetadata>>,
+    options: ParseOptions,
 ) -> Result<Vec<Message>, DltParseError> {
     trace!("parse_dlt_file");
     let source_file_size = fs::metadata(&in_file)?.len();
@@ -50,7 +86,7 @@ pub async fn parse_dlt_file(
         &in_file,
         filter_config,
         update_channel.clone(),
-        true,
+        options,
         fibex_metadata,
     )?;
     // type Item = Result<Option<Message>, DltParseError>;
@@ -80,15 +116,17 @@ pub fn create_index_and_mapping_dlt(
     update_channel: &cc::Sender<ChunkResults>,
     shutdown_receiver: Option<cc::Receiver<()>>,
     fibex_metadata: Option<FibexMetadata>,
+    options: ParseOptions,
 ) -> Result<(), anyhow::Error> {
     trace!("create_index_and_mapping_dlt");
-    let filter_config: Option<filtering::ProcessedDltFilterConfig> =
-        dlt_filter.map(filtering::process_filter_config);
+    let filter_config: Option<filtering::ProcessedDltFilterConfig> = dlt_filter
+        .map(filtering::process_filter_config)
+        .transpose()?;
     let mut message_producer = FileMessageProducer::new(
         &config.in_file,
         filter_config,
         update_channel.clone(),
-        true,
+        options,
         fibex_metadata.map(Rc::new),
     )?;
     // TODO do not clone metadata...if we use it in FileMessageProducer, we should not need it in index_dlt_content
@@ -106,24 +144,32 @@ pub struct MessageStats {
     parsed: usize,
     no_parse: usize,
 }
-pub struct FileMessageProducer {
-    reader: ReduxReader<fs::File, MinBuffered>,
+pub struct FileMessageProducer<'a> {
+    reader: ReduxReader<Box<dyn BufRead>, MinBuffered>,
     filter_config: Option<filtering::ProcessedDltFilterConfig>,
     stats: MessageStats,
     update_channel: cc::Sender<ChunkResults>,
-    with_storage_header: bool,
+    walker: FrameWalker,
+    /// nothing more can be read into the buffer
+    eof: bool,
     fibex_metadata: Option<Rc<FibexMetadata>>,
+    /// bytes used up by messages or skipped so far
+    processed: u64,
+    progress: Option<Progress<'a>>,
 }
 
-impl FileMessageProducer {
-    fn new(
+impl<'a> FileMessageProducer<'a> {
+    /// read the messages of the (possibly gzip compressed) file at `in_path`
+    ///
+    /// problems are reported as notifications on `update_channel`
+    pub fn new(
         in_path: &PathBuf,
         filter_config: Option<filtering::ProcessedDltFilterConfig>,
         update_channel: cc::Sender<ChunkResults>,
-        with_storage_header: bool,
+        options: ParseOptions,
         fibex_metadata: Option<Rc<FibexMetadata>>,
-    ) -> Result<FileMessageProducer, DltParseError> {
-        let f = match fs::File::open(&in_path) {
+    ) -> Result<FileMessageProducer<'a>, DltParseError> {
+        let f = match open_dlt_reader(in_path) {
             Ok(file) => file,
             Err(e) => {
                 warn!("could not open {:?}", in_path);
@@ -147,13 +193,26 @@ impl FileMessageProducer {
                 no_parse: 0,
             },
             update_channel,
-            with_storage_header,
+            walker: FrameWalker::new(options),
+            eof: false,
             fibex_metadata,
+            processed: 0,
+            progress: None,
         })
     }
+
+    /// call `callback` with the number of processed bytes and `total` while
+    /// reading, see `dlt_parse::Messages::with_progress`
+    ///
+    /// for compressed files the processed bytes are decompressed bytes, so
+    /// `total` should be the decompressed size, it is only used for reporting
+    pub fn with_progress<F: FnMut(u64, u64) + 'a>(mut self, total: u64, callback: F) -> Self {
+        self.progress = Some(Progress::new(total, callback));
+        self
+    }
 }
 
-impl Iterator for FileMessageProducer {
+impl<'a> Iterator for FileMessageProducer<'a> {
     type Item = ParsedMessage;
     fn next(&mut self) -> Option<ParsedMessage> {
         match self.produce_next_message() {
@@ -162,96 +221,151 @@ impl Iterator for FileMessageProducer {
         }
     }
 }
-impl FileMessageProducer {
+impl<'a> FileMessageProducer<'a> {
     fn produce_next_message(&mut self) -> (usize, Result<ParsedMessage, DltParseError>) {
-        #[allow(clippy::never_loop)]
-        let consume_and_parse_result = loop {
-            match self.reader.fill_buf() {
-                Ok(content) => {
-                    if content.is_empty() {
-                        trace!("0, Ok(ParsedMessage::Invalid)");
-                        return (0, Ok(ParsedMessage::Invalid));
-                    }
-                    let available = content.len();
+        let (consumed, result) = self.read_next_message();
+        if let Some(progress) = &mut self.progress {
+            if consumed == 0 {
+                // nothing left to read
+                progress.finish();
+            } else {
+                self.processed += consumed as u64;
+                progress.update(self.processed);
+            }
+        }
+        (consumed, result)
+    }
 
-                    let parse_result: Result<(&[u8], ParsedMessage), DltParseError> = dlt_message(
-                        content,
-                        self.filter_config.as_ref(),
-                        self.stats.parsed + self.stats.no_parse,
-                        Some(&self.update_channel),
-                        self.fibex_metadata.clone(),
-                        self.with_storage_header,
+    fn read_next_message(&mut self) -> (usize, Result<ParsedMessage, DltParseError>) {
+        // bytes in front of the next message are consumed right away
+        let mut skipped = 0usize;
+        loop {
+            let content = match self.reader.fill_buf() {
+                Ok(content) => content,
+                Err(e) => {
+                    trace!("no more content");
+                    return (
+                        skipped,
+                        Err(DltParseError::Unrecoverable {
+                            cause: format!("error for filling buffer with dlt messages: {:?}", e),
+                        }),
                     );
-
-                    match parse_result {
-                        Ok((rest, maybe_msg)) => {
-                            let consumed = available - rest.len();
-                            self.stats.parsed += 1;
-                            break (consumed, Ok(maybe_msg));
-                        }
-                        Err(DltParseError::IncompleteParse { needed }) => {
-                            debug!("parse incomplete");
-                            self.stats.no_parse += 1;
-                            let needed_s = match needed {
-                                Some(s) => format!("{}", s),
-                                None => "unknown".to_string(),
-                            };
-                            break (0, Err(DltParseError::Unrecoverable {
-                                cause: format!(
-                                    "read_one_dlt_message: imcomplete parsing error for dlt messages: (bytes left: {}, but needed: {})",
-                                    content.len(),
-                                    needed_s
-                                ),
-                            }));
-                        }
-                        Err(DltParseError::ParsingHickup { reason }) => {
-                            warn!("parse error");
-                            self.stats.no_parse += 1;
-                            break (
-                                DLT_PATTERN_SIZE,
-                                Err(DltParseError::ParsingHickup {
-                                    reason: format!(
-                                        "read_one_dlt_message: parsing error for dlt messages: {}",
-                                        reason
-                                    ),
-                                }),
-                            );
-                        }
-                        Err(DltParseError::Unrecoverable { cause }) => {
-                            warn!("parse failure");
-                            self.stats.no_parse += 1;
-                            break (
-                                0,
+                }
+            };
+            if content.is_empty() {
+                trace!("0, Ok(ParsedMessage::Invalid)");
+                return (skipped, Ok(ParsedMessage::Invalid));
+            }
+            let (consumed, result) = match self.walker.step(content, self.eof) {
+                FrameStep::Frame(length) => match dlt_message_with_options(
+                    &content[..length],
+                    self.filter_config.as_ref(),
+                    self.stats.parsed + self.stats.no_parse,
+                    Some(&self.update_channel),
+                    self.fibex_metadata.clone(),
+                    self.walker.options,
+                ) {
+                    Ok((_, maybe_msg)) => {
+                        self.stats.parsed += 1;
+                        (length, Ok(maybe_msg))
+                    }
+                    Err(e) => {
+                        self.stats.no_parse += 1;
+                        (
+                            self.walker.skip_unusable(content, Some(length)),
+                            Err(read_error(e)),
+                        )
+                    }
+                },
+                FrameStep::Skip(dropped) => {
+                    let _ = self.update_channel.send(Err(Notification {
+                        severity: Severity::WARNING,
+                        content: format!("dropped {} to get to next message", dropped),
+                        line: Some(self.stats.parsed + self.stats.no_parse),
+                    }));
+                    self.reader.consume(dropped);
+                    skipped += dropped;
+                    continue;
+                }
+                FrameStep::Incomplete { .. } if !self.eof => {
+                    // the buffer policy does not read more while enough is
+                    // buffered, but the message needs more
+                    self.reader.make_room();
+                    match self.reader.read_into_buf() {
+                        Ok(0) => self.eof = true,
+                        Ok(_) => (),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                        Err(e) => {
+                            return (
+                                skipped,
                                 Err(DltParseError::Unrecoverable {
-                                    cause: format!(
-                                    "read_one_dlt_message: parsing failure for dlt messages: {}",
-                                    cause
-                                ),
+                                    cause: format!("error for reading dlt messages: {:?}", e),
                                 }),
-                            );
+                            )
                         }
                     }
+                    continue;
                 }
-                Err(e) => {
-                    trace!("no more content");
-                    break (
-                        0,
+                FrameStep::Incomplete { needed } => {
+                    debug!("parse incomplete");
+                    self.stats.no_parse += 1;
+                    (
+                        self.walker.skip_unusable(content, None),
                         Err(DltParseError::Unrecoverable {
-                            cause: format!("error for filling buffer with dlt messages: {:?}", e),
+                            cause: format!(
+                                "read_one_dlt_message: imcomplete parsing error for dlt messages: (bytes left: {}, but needed: {})",
+                                content.len(),
+                                needed
+                            ),
                         }),
-                    );
+                    )
+                }
+                FrameStep::Invalid(e) => {
+                    warn!("parse error: {}", e);
+                    self.stats.no_parse += 1;
+                    (
+                        self.walker.skip_unusable(content, None),
+                        Err(DltParseError::ParsingHickup {
+                            reason: format!("read_one_dlt_message: {}", e),
+                        }),
+                    )
                 }
+            };
+            self.reader.consume(consumed);
+            trace!("produce_next_message (consumed {})", skipped + consumed);
+            return (skipped + consumed, result);
+        }
+    }
+}
+
+/// the error of a message that could not be parsed, as reported by `FileMessageProducer`
+fn read_error(e: DltParseError) -> DltParseError {
+    match e {
+        DltParseError::ParsingHickup { reason } => {
+            warn!("parse error");
+            DltParseError::ParsingHickup {
+                reason: format!(
+                    "read_one_dlt_message: parsing error for dlt messages: {}",
+                    reason
+                ),
             }
-        };
-        self.reader.consume(consume_and_parse_result.0);
-        trace!(
-            "produce_next_message (consumed {})",
-            consume_and_parse_result.0
-        );
-        consume_and_parse_result
+        }
+        DltParseError::Unrecoverable { cause } => {
+            warn!("parse failure");
+            DltParseError::Unrecoverable {
+                cause: format!(
+                    "read_one_dlt_message: parsing failure for dlt messages: {}",
+                    cause
+                ),
+            }
+        }
+        e => {
+            warn!("parse error: {}", e);
+            e
+        }
     }
 }
-impl futures::Stream for FileMessageProducer {
+impl<'a> futures::Stream for FileMessageProducer<'a> {
     type Item = Result<(usize, Option<Message>), DltParseError>;
     fn poll_next(
         mut self: std::pin::Pin<&mut Self>,
@@ -406,6 +520,7 @@ pub fn export_session_file(
         session_file_path,
         destination_path,
         sections,
+        ParseOptions::new(StorageHeaderMode::Required),
         update_channel,
     )
 }
@@ -414,6 +529,7 @@ pub fn export_as_dlt_file(
     dlt_file_path: PathBuf,
     destination_path: PathBuf,
     sections: SectionConfig,
+    options: ParseOptions,
     update_channel: cc::Sender<ChunkResults>,
 ) -> Result<(), DltParseError> {
     use std::io::{Read, Seek};
@@ -429,7 +545,7 @@ pub fn export_as_dlt_file(
         let mut reader = &mut std::io::BufReader::new(f);
         let out_file = std::fs::File::create(destination_path)?;
         trace!("created out_file: {:?}", &out_file);
-        let partitioner = FilePartitioner::new(&dlt_file_path, sections)?;
+        let partitioner = FilePartitioner::new(&dlt_file_path, sections, options)?;
         let mut out_writer = BufWriter::new(out_file);
 
         for part in partitioner.get_parts() {
@@ -472,9 +588,14 @@ struct FilePartitioner {
     offset: u64,
     section_config: SectionConfig,
     file_size: u64,
+    storage_magic: [u8; 4],
 }
 impl FilePartitioner {
-    fn new(in_path: &PathBuf, c: SectionConfig) -> Result<Self, anyhow::Error> {
+    fn new(
+        in_path: &PathBuf,
+        c: SectionConfig,
+        options: ParseOptions,
+    ) -> Result<Self, anyhow::Error> {
         let f = fs::File::open(&in_path)?;
         Ok(FilePartitioner {
             reader: ReduxReader::with_capacity(DLT_READER_CAPACITY, f)
@@ -482,6 +603,7 @@ impl FilePartitioner {
             offset: 0,
             section_config: c,
             file_size: fs::metadata(in_path)?.len(),
+            storage_magic: options.storage_magic,
         })
     }
     fn get_parts(mut self) -> Vec<FilePart> {
@@ -521,10 +643,10 @@ impl FilePartitioner {
                             trace!("0, Ok(ParsedMessage::Invalid)");
                             break;
                         }
-                        match skip_storage_header(content) {
+                        match skip_storage_header(content, self.storage_magic) {
                             Ok((rest, skipped_bytes)) => {
                                 let (len_without_storage_header, _was_last) =
-                                    match forward_to_next_storage_header(rest) {
+                                    match forward_to_next_storage_header(rest, self.storage_magic) {
                                         Some((dropped, _)) => (dropped, false),
                                         None => (rest.len() as u64, true),
                                     };
@@ -1,18 +1,12 @@
-use crate::{
-    dlt::*,
-    dlt_parse::{dlt_fint, dlt_fixed_point, dlt_sint, dlt_uint},
-    fibex::*,
-    service_id::*,
-};
+use crate::{dlt::*, dlt_parse::dlt_fibex_arguments, fibex::*, service_id::*};
 use byteorder::{BigEndian, LittleEndian};
-use bytes::ByteOrder;
 use chrono::{
     prelude::{DateTime, Utc},
     NaiveDateTime,
 };
 use std::{
     fmt::{self, Formatter},
-    str,
+    io, str,
 };
 
 lazy_static! {
@@ -64,6 +58,14 @@ impl fmt::Display for Message {
                     None => write!(f, "[Unknown CtrlCommand]"),
                 }
             }
+            PayloadContent::Raw(data) => {
+                self.write_app_id_context_id_and_message_type(f)?;
+                write!(f, "[raw] {:02X?}", data)
+            }
+            PayloadContent::Skipped(length) => {
+                self.write_app_id_context_id_and_message_type(f)?;
+                write!(f, "{}", skipped_text(*length))
+            }
         }
     }
 }
@@ -129,10 +131,15 @@ impl fmt::Display for ControlType {
     }
 }
 
+/// The common rendering of a value
+///
+/// booleans are shown as `true`/`false`, integers in decimal, floats with
+/// 6 significant digits and raw data as uppercase hex. The alternate flag
+/// (`{:#}`) separates the bytes of raw data by a space.
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         match self {
-            Value::Bool(value) => value.fmt(f),
+            Value::Bool(value) => f.write_str(if *value == 0 { "false" } else { "true" }),
             Value::U8(value) => value.fmt(f),
             Value::U16(value) => value.fmt(f),
             Value::U32(value) => value.fmt(f),
@@ -143,8 +150,10 @@ impl fmt::Display for Value {
             Value::I32(value) => value.fmt(f),
             Value::I64(value) => value.fmt(f),
             Value::I128(value) => value.fmt(f),
-            Value::F32(value) => value.fmt(f),
-            Value::F64(value) => value.fmt(f),
+            Value::F16(value) | Value::F32(value) => {
+                f.write_str(&dltviewer_float(f64::from(*value)))
+            }
+            Value::F64(value) => f.write_str(&dltviewer_float(*value)),
             Value::StringVal(s) => write!(
                 f,
                 "{}",
@@ -152,23 +161,39 @@ impl fmt::Display for Value {
                     .collect::<Vec<&str>>()
                     .join(&DLT_NEWLINE_SENTINAL_STR)
             ),
-            Value::Raw(value) => write!(f, "{:02X?}", value),
+            Value::Raw(bytes) => {
+                for (i, b) in bytes.iter().enumerate() {
+                    if i > 0 && f.alternate() {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{:02X}", b)?;
+                }
+                Ok(())
+            }
+            Value::FixedPoint { scaled, .. } => f.write_str(&dltviewer_float(*scaled)),
+            Value::Array { values, .. } => {
+                f.write_str("[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    v.fmt(f)?;
+                }
+                f.write_str("]")
+            }
         }
     }
 }
 
+/// `name: value unit`, a `{:#}` is passed on to the value
 impl fmt::Display for Argument {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         if let Some(n) = &self.name {
             write!(f, "{}: ", n)?;
         }
+        self.value.fmt(f)?;
         if let Some(u) = &self.unit {
-            u.fmt(f)?;
-        }
-        if let Some(v) = self.to_real_value() {
-            write!(f, "{}", v)?;
-        } else {
-            self.value.fmt(f)?;
+            write!(f, " {}", u)?;
         }
 
         Ok(())
@@ -177,8 +202,10 @@ impl fmt::Display for Argument {
 
 impl fmt::Display for DltTimeStamp {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        let naive: Option<NaiveDateTime> =
-            NaiveDateTime::from_timestamp_opt(i64::from(self.seconds), self.microseconds * 1000);
+        let naive: Option<NaiveDateTime> = self
+            .microseconds
+            .checked_mul(1000)
+            .and_then(|nanos| NaiveDateTime::from_timestamp_opt(i64::from(self.seconds), nanos));
         match naive {
             Some(n) => {
                 let datetime: DateTime<Utc> = DateTime::from_utc(n, Utc);
@@ -233,6 +260,103 @@ impl fmt::Display for LogLevel {
 }
 
 impl Message {
+    /// the payload as human readable text, arguments are separated by a space
+    ///
+    /// non-verbose messages are decoded with the fibex metadata if available,
+    /// otherwise the message id and the raw data are shown
+    pub fn payload_text(&self) -> String {
+        match &self.payload.payload_content {
+            PayloadContent::Verbose(arguments) => join_arguments(arguments, |a| a.to_string()),
+            PayloadContent::NonVerbose(id, data) => match self.fibex_arguments(*id, data) {
+                Some(arguments) => join_arguments(&arguments, |a| a.to_string()),
+                None => format!("[{}] {:02X?}", id, data),
+            },
+            PayloadContent::ControlMsg(ctrl_id, _data) => control_text(ctrl_id),
+            PayloadContent::Raw(data) => format!("[raw] {:02X?}", data),
+            PayloadContent::Skipped(length) => skipped_text(*length),
+        }
+    }
+
+    /// the message as line of the ASCII export of DLT-Viewer
+    ///
+    /// the columns are index, time, timestamp, count, ECU, App, Context,
+    /// type, subtype, mode, number of arguments and payload, each separated
+    /// by a space. The time of the storage header is shown in UTC.
+    pub fn format_as_dltviewer_line(&self, index: usize) -> String {
+        let (seconds, microseconds) = match &self.storage_header {
+            Some(h) => (h.timestamp.seconds, h.timestamp.microseconds),
+            None => (0, 0),
+        };
+        let time = NaiveDateTime::from_timestamp_opt(i64::from(seconds), 0)
+            .map(|t| t.format("%Y/%m/%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let timestamp = self.header.timestamp.unwrap_or(0);
+        let ecu_id = self
+            .header
+            .ecu_id
+            .as_ref()
+            .or_else(|| self.storage_header.as_ref().map(|h| &h.ecu_id))
+            .map(String::as_str)
+            .unwrap_or("");
+        let (app_id, context_id, (message_type, subtype), argument_count) =
+            match &self.extended_header {
+                Some(h) => (
+                    h.application_id.as_str(),
+                    h.context_id.as_str(),
+                    dltviewer_type_and_subtype(&h.message_type),
+                    h.argument_count,
+                ),
+                None => ("", "", ("", ""), 0),
+            };
+        let payload = match &self.payload.payload_content {
+            PayloadContent::Verbose(arguments) => join_arguments(arguments, dltviewer_argument),
+            PayloadContent::NonVerbose(id, data) => match self.fibex_arguments(*id, data) {
+                Some(arguments) => join_arguments(&arguments, dltviewer_argument),
+                None => format!("[{}] {}", id, dltviewer_hex(data)),
+            },
+            PayloadContent::ControlMsg(ctrl_id, _data) => control_text(ctrl_id),
+            PayloadContent::Raw(data) => format!("[raw] {}", dltviewer_hex(data)),
+            PayloadContent::Skipped(length) => skipped_text(*length),
+        };
+        format!(
+            "{} {}.{:06} {}.{:04} {} {} {} {} {} {} {} {} {}",
+            index,
+            time,
+            microseconds,
+            timestamp / 10_000,
+            timestamp % 10_000,
+            self.header.message_counter,
+            ecu_id,
+            app_id,
+            context_id,
+            message_type,
+            subtype,
+            if self.is_verbose() {
+                "verbose"
+            } else {
+                "non-verbose"
+            },
+            argument_count,
+            payload
+        )
+    }
+
+    /// the arguments of a non-verbose message as described in the fibex metadata
+    fn fibex_arguments(&self, id: u32, data: &[u8]) -> Option<Vec<Argument>> {
+        let frame_metadata = self.fibex_metadata.as_ref()?.frame_for(
+            self.extended_header
+                .as_ref()
+                .map(|h| h.application_id.as_ref()),
+            self.extended_header.as_ref().map(|h| h.context_id.as_ref()),
+            id,
+        )?;
+        Some(if self.header.endianness == Endianness::Big {
+            dlt_fibex_arguments::<BigEndian>(frame_metadata, data)
+        } else {
+            dlt_fibex_arguments::<LittleEndian>(frame_metadata, data)
+        })
+    }
+
     pub(crate) fn format_nonverbose_data(
         &self,
         id: u32,
@@ -241,23 +365,20 @@ impl Message {
     ) -> fmt::Result {
         let mut is_written = false;
         if let Some(fibex_metadata) = &self.fibex_metadata {
-            let id_text = format!("ID_{}", id);
-            let frame_metadata = if let Some(extended_header) = &self.extended_header {
-                fibex_metadata.frame_map_with_key.get(&(
-                    ContextId(extended_header.context_id.clone()),
-                    ApplicationId(extended_header.application_id.clone()),
-                    FrameId(id_text),
-                )) // TODO: avoid cloning here (Cow or Borrow)
-            } else {
-                fibex_metadata.frame_map.get(&FrameId(id_text))
-            };
+            let frame_metadata = fibex_metadata.frame_for(
+                self.extended_header
+                    .as_ref()
+                    .map(|h| h.application_id.as_ref()),
+                self.extended_header.as_ref().map(|h| h.context_id.as_ref()),
+                id,
+            );
             if let Some(frame_metadata) = frame_metadata {
                 let FrameMetadata {
                     application_id,
                     context_id,
                     message_info,
                     ..
-                } = &**frame_metadata;
+                } = frame_metadata;
                 write!(
                     f,
                     "{}{}{}{}",
@@ -290,196 +411,15 @@ impl Message {
                     write!(f, "-")?;
                 }
                 write!(f, "{}", DLT_COLUMN_SENTINAL)?;
-                let mut offset = 0;
-                for pdu in &frame_metadata.pdus {
-                    if let Some(description) = &pdu.description {
-                        let arg = Argument {
-                            type_info: TypeInfo {
-                                kind: TypeInfoKind::StringType,
-                                coding: StringCoding::UTF8,
-                                has_trace_info: false,
-                                has_variable_info: false,
-                            },
-                            name: None,
-                            unit: None,
-                            fixed_point: None,
-                            value: Value::StringVal(description.to_string()),
-                        };
-                        write!(f, "{}{} ", DLT_ARGUMENT_SENTINAL, arg)?;
-                    } else {
-                        for signal_type in &pdu.signal_types {
-                            let mut fixed_point = None;
-                            let value = match signal_type.kind {
-                                TypeInfoKind::StringType | TypeInfoKind::Raw => {
-                                    if data.len() < offset + 2 {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let length = if self.header.endianness == Endianness::Big {
-                                        BigEndian::read_u16(&data[offset..offset + 2]) as usize
-                                    } else {
-                                        LittleEndian::read_u16(&data[offset..offset + 2]) as usize
-                                    };
-                                    offset += 2;
-                                    if data.len() < offset + length {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let v = if signal_type.kind == TypeInfoKind::StringType {
-                                        Value::StringVal(
-                                            String::from_utf8(
-                                                data[offset..offset + length].to_vec(),
-                                            )
-                                            .map_err(|_| fmt::Error)?,
-                                        )
-                                    } else {
-                                        Value::Raw(Vec::from(&data[offset..offset + length]))
-                                    };
-                                    offset += length;
-                                    v
-                                }
-                                TypeInfoKind::Bool => {
-                                    offset += 1;
-                                    if data.len() < offset {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    Value::Bool(data[offset - 1])
-                                }
-                                TypeInfoKind::Float(width) => {
-                                    let length = width as usize / 8;
-                                    if data.len() < offset + length {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let v = if self.header.endianness == Endianness::Big {
-                                        dlt_fint::<BigEndian>(width)(&data[offset..offset + length])
-                                    } else {
-                                        dlt_fint::<LittleEndian>(width)(
-                                            &data[offset..offset + length],
-                                        )
-                                    }
-                                    .map_err(|_| fmt::Error)?
-                                    .1;
-                                    offset += length;
-                                    v
-                                }
-                                TypeInfoKind::Signed(length) => {
-                                    let byte_length = length as usize / 8;
-                                    if data.len() < offset + byte_length {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let value_offset = &data[offset..];
-                                    let (_, v) = if self.header.endianness == Endianness::Big {
-                                        dlt_sint::<BigEndian>(length)(value_offset)
-                                    } else {
-                                        dlt_sint::<LittleEndian>(length)(value_offset)
-                                    }
-                                    .map_err(|_| fmt::Error)?;
-                                    offset += byte_length;
-                                    v
-                                }
-                                TypeInfoKind::SignedFixedPoint(length) => {
-                                    let byte_length = length as usize / 8;
-                                    if data.len() < offset + byte_length {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let (value_offset, fp) =
-                                        if self.header.endianness == Endianness::Big {
-                                            dlt_fixed_point::<BigEndian>(
-                                                &data[offset..offset + byte_length],
-                                                length,
-                                            )
-                                        } else {
-                                            dlt_fixed_point::<LittleEndian>(
-                                                &data[offset..offset + byte_length],
-                                                length,
-                                            )
-                                        }
-                                        .map_err(|_| fmt::Error)?;
-                                    fixed_point = Some(fp);
-                                    let (_, v) =
-                                        if self.header.endianness == Endianness::Big {
-                                            dlt_sint::<BigEndian>(float_width_to_type_length(
-                                                length,
-                                            ))(
-                                                value_offset
-                                            )
-                                        } else {
-                                            dlt_sint::<LittleEndian>(float_width_to_type_length(
-                                                length,
-                                            ))(
-                                                value_offset
-                                            )
-                                        }
-                                        .map_err(|_| fmt::Error)?;
-                                    offset += byte_length;
-                                    v
-                                }
-                                TypeInfoKind::Unsigned(length) => {
-                                    let byte_length = length as usize / 8;
-                                    if data.len() < offset + byte_length {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let value_offset = &data[offset..];
-                                    let (_, v) = if self.header.endianness == Endianness::Big {
-                                        dlt_uint::<BigEndian>(length)(value_offset)
-                                    } else {
-                                        dlt_uint::<LittleEndian>(length)(value_offset)
-                                    }
-                                    .map_err(|_| fmt::Error)?;
-                                    offset += byte_length;
-                                    v
-                                }
-                                TypeInfoKind::UnsignedFixedPoint(length) => {
-                                    let byte_length = length as usize / 8;
-                                    if data.len() < offset + byte_length {
-                                        return fmt::Result::Err(fmt::Error);
-                                    }
-                                    let value_offset = {
-                                        let (r, fp) =
-                                            if self.header.endianness == Endianness::Big {
-                                                dlt_fixed_point::<BigEndian>(
-                                                    &data[offset..offset + byte_length],
-                                                    length,
-                                                )
-                                            } else {
-                                                dlt_fixed_point::<LittleEndian>(
-                                                    &data[offset..offset + byte_length],
-                                                    length,
-                                                )
-                                            }
-                                            .map_err(|_| fmt::Error)?;
-                                        fixed_point = Some(fp);
-                                        r
-                                    };
-                                    let (_, v) =
-                                        if self.header.endianness == Endianness::Big {
-                                            dlt_uint::<BigEndian>(float_width_to_type_length(
-                                                length,
-                                            ))(
-                                                value_offset
-                                            )
-                                        } else {
-                                            dlt_uint::<LittleEndian>(float_width_to_type_length(
-                                                length,
-                                            ))(
-                                                value_offset
-                                            )
-                                        }
-                                        .map_err(|_| fmt::Error)?;
-                                    offset += byte_length;
-                                    v
-                                }
-                            };
-                            let arg = Argument {
-                                type_info: signal_type.clone(),
-                                name: None,
-                                unit: None,
-                                fixed_point,
-                                value,
-                            };
-                            write!(f, "{}{} ", DLT_ARGUMENT_SENTINAL, arg)?;
-                        }
-                    };
-                    is_written = true;
+                let arguments = if self.header.endianness == Endianness::Big {
+                    dlt_fibex_arguments::<BigEndian>(frame_metadata, data)
+                } else {
+                    dlt_fibex_arguments::<LittleEndian>(frame_metadata, data)
+                };
+                for arg in arguments {
+                    write!(f, "{}{} ", DLT_ARGUMENT_SENTINAL, arg)?;
                 }
+                is_written = true;
             } else {
                 self.write_app_id_context_id_and_message_type(f)?;
             }
@@ -500,10 +440,258 @@ impl Message {
         Ok(())
     }
 }
+fn join_arguments<F: Fn(&Argument) -> String>(arguments: &[Argument], format: F) -> String {
+    arguments
+        .iter()
+        .map(format)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn control_text(ctrl_id: &ControlType) -> String {
+    match SERVICE_ID_MAPPING.get(&ctrl_id.value()) {
+        Some((name, _desc)) => format!("[{}]", name),
+        None => "[Unknown CtrlCommand]".to_string(),
+    }
+}
+
+/// a payload that was dropped while parsing with `PayloadMode::Skip`
+fn skipped_text(length: u16) -> String {
+    format!("[{} bytes skipped]", length)
+}
+
+/// type and subtype of a message the way DLT-Viewer names them
+fn dltviewer_type_and_subtype(message_type: &MessageType) -> (&'static str, &'static str) {
+    match message_type {
+        MessageType::Log(level) => (
+            "log",
+            match level {
+                LogLevel::Fatal => "fatal",
+                LogLevel::Error => "error",
+                LogLevel::Warn => "warn",
+                LogLevel::Info => "info",
+                LogLevel::Debug => "debug",
+                LogLevel::Verbose => "verbose",
+                LogLevel::Invalid(_) => "",
+            },
+        ),
+        MessageType::ApplicationTrace(trace_type) => (
+            "app_trace",
+            match trace_type {
+                ApplicationTraceType::Variable => "variable",
+                ApplicationTraceType::FunctionIn => "func_in",
+                ApplicationTraceType::FunctionOut => "func_out",
+                ApplicationTraceType::State => "state",
+                ApplicationTraceType::Vfb => "vfb",
+                ApplicationTraceType::Invalid(_) => "",
+            },
+        ),
+        MessageType::NetworkTrace(trace_type) => (
+            "nw_trace",
+            match trace_type {
+                NetworkTraceType::Ipc => "ipc",
+                NetworkTraceType::Can => "can",
+                NetworkTraceType::Flexray => "flexray",
+                NetworkTraceType::Most => "most",
+                NetworkTraceType::Ethernet => "ethernet",
+                NetworkTraceType::Someip => "someip",
+                NetworkTraceType::Invalid | NetworkTraceType::UserDefined(_) => "",
+            },
+        ),
+        MessageType::Control(control_type) => (
+            "control",
+            match control_type {
+                ControlType::Request => "request",
+                ControlType::Response => "response",
+                ControlType::Unknown(_) => "",
+            },
+        ),
+        MessageType::Unknown(_) => ("", ""),
+    }
+}
+
+/// the header row written by `write_csv`
+pub const CSV_COLUMNS: [&str; 8] = [
+    "index",
+    "timestamp",
+    "ecu",
+    "app",
+    "context",
+    "log_level",
+    "message_type",
+    "payload",
+];
+
+/// write the messages as CSV with `,` as delimiter, see `write_csv_with_delimiter`
+pub fn write_csv<'a, I, W>(messages: I, writer: &mut W) -> io::Result<usize>
+where
+    I: IntoIterator<Item = &'a Message>,
+    W: io::Write,
+{
+    write_csv_with_delimiter(messages, writer, ',')
+}
+
+/// write a header row and one row per message
+///
+/// the columns are `CSV_COLUMNS`: the index of the message, the time of the
+/// storage header, ECU, App and Context id, the log level (empty for other
+/// message types), the message type and the payload text. The payload is
+/// always quoted, other fields only if they contain the delimiter, a quote or
+/// a line break. Quotes are escaped by doubling them.
+/// Rows end with `\r\n`. Use `;` as delimiter for spreadsheets in locales that
+/// use `,` as decimal separator.
+///
+/// returns the number of messages written
+pub fn write_csv_with_delimiter<'a, I, W>(
+    messages: I,
+    writer: &mut W,
+    delimiter: char,
+) -> io::Result<usize>
+where
+    I: IntoIterator<Item = &'a Message>,
+    W: io::Write,
+{
+    let header: Vec<String> = CSV_COLUMNS.iter().map(|c| c.to_string()).collect();
+    write_csv_row(writer, &header, delimiter)?;
+    let mut count = 0;
+    for (index, msg) in messages.into_iter().enumerate() {
+        let timestamp = msg
+            .storage_header
+            .as_ref()
+            .map(|h| h.timestamp.to_string())
+            .unwrap_or_default();
+        let ecu_id = msg
+            .header
+            .ecu_id
+            .as_ref()
+            .or_else(|| msg.storage_header.as_ref().map(|h| &h.ecu_id))
+            .map(String::as_str)
+            .unwrap_or("");
+        let (app_id, context_id, (message_type, subtype)) = match &msg.extended_header {
+            Some(h) => (
+                h.application_id.as_str(),
+                h.context_id.as_str(),
+                dltviewer_type_and_subtype(&h.message_type),
+            ),
+            None => ("", "", ("", "")),
+        };
+        let log_level = match msg.message_type() {
+            Some(MessageType::Log(_)) => subtype,
+            _ => "",
+        };
+        // the display of string arguments replaces line breaks, CSV can keep them
+        let payload = msg.payload_text().replace(*DLT_NEWLINE_SENTINAL_STR, "\n");
+        let row = [
+            index.to_string(),
+            csv_field(&timestamp, delimiter, false),
+            csv_field(ecu_id, delimiter, false),
+            csv_field(app_id, delimiter, false),
+            csv_field(context_id, delimiter, false),
+            log_level.to_string(),
+            message_type.to_string(),
+            csv_field(&payload, delimiter, true),
+        ];
+        write_csv_row(writer, &row, delimiter)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn write_csv_row<W: io::Write>(
+    writer: &mut W,
+    fields: &[String],
+    delimiter: char,
+) -> io::Result<()> {
+    let mut separator = [0u8; 4];
+    let separator = delimiter.encode_utf8(&mut separator).as_bytes();
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(separator)?;
+        }
+        writer.write_all(field.as_bytes())?;
+    }
+    writer.write_all(b"\r\n")
+}
+
+fn csv_field(value: &str, delimiter: char, always_quote: bool) -> String {
+    if always_quote || value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// an argument value the way DLT-Viewer shows it in the payload column
+fn dltviewer_argument(argument: &Argument) -> String {
+    dltviewer_value(&argument.value)
+}
+
+/// like the `Display` of the value except for what DLT-Viewer shows differently
+fn dltviewer_value(value: &Value) -> String {
+    match value {
+        // every message stays on one line, DLT-Viewer shows line breaks as spaces
+        Value::StringVal(s) => s.replace(&['\n', '\r'][..], " "),
+        Value::Raw(bytes) => dltviewer_hex(bytes),
+        // DLT-Viewer shows the transmitted value of fixed point arguments
+        Value::FixedPoint { raw, .. } => raw.to_string(),
+        Value::Array { values, .. } => values
+            .iter()
+            .map(dltviewer_value)
+            .collect::<Vec<String>>()
+            .join(" "),
+        v => v.to_string(),
+    }
+}
+
+/// bytes as lowercase hex, separated by spaces
+fn dltviewer_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// a float with 6 significant digits like `printf("%g")`, which is what
+/// DLT-Viewer uses
+pub(crate) fn dltviewer_float(value: f64) -> String {
+    fn trim_zeros(s: &str) -> &str {
+        if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            s
+        }
+    }
+    if value.is_nan() {
+        return "nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    if value == 0.0 {
+        return if value.is_sign_negative() { "-0" } else { "0" }.to_string();
+    }
+    // the exponent is only known after rounding to 6 digits
+    let scientific = format!("{:.5e}", value);
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap_or(0));
+    let exponent: i32 = exponent[1..].parse().unwrap_or(0);
+    if !(-4..6).contains(&exponent) {
+        format!(
+            "{}e{}{:02}",
+            trim_zeros(mantissa),
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        )
+    } else {
+        trim_zeros(&format!("{:.*}", (5 - exponent) as usize, value)).to_string()
+    }
+}
+
 impl fmt::Display for TypeInfo {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        let kind = match self.kind {
+        let kind = match &self.kind {
             TypeInfoKind::Bool => "Bool".into(),
+            TypeInfoKind::Array(element) => format!("Array ({:?})", element),
             TypeInfoKind::Signed(type_length) => format!("Signed ({:?})", type_length),
             TypeInfoKind::SignedFixedPoint(float_width) => format!("Signed FP ({:?})", float_width),
             TypeInfoKind::Unsigned(type_length) => format!("Unsigned ({:?})", type_length),
@@ -0,0 +1,84 @@
+use crate::dlt_parse::{
+    count_messages_with_options, inventory_with_options, message_refs, messages, DltInventory,
+    DltParseError, MessageRefs, Messages, ParseOptions, StorageHeaderMode,
+};
+use memmap2::Mmap;
+use std::{fs::File, io, path::Path};
+
+/// A dlt file (with storage headers) that is mapped into memory
+///
+/// The slice based parsing functions work directly on the mapped region, so
+/// even huge files are never read completely. Messages returned by
+/// `message_refs` borrow from the mapping; it is released when the `DltFile`
+/// is dropped.
+pub struct DltFile {
+    // empty files cannot be mapped
+    mmap: Option<Mmap>,
+    options: ParseOptions,
+}
+
+impl DltFile {
+    /// map the file at `path`
+    ///
+    /// the file must not be truncated by someone else while it is mapped,
+    /// accessing the missing part of the mapping would crash the process
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(DltFile {
+                mmap: None,
+                options: ParseOptions::new(StorageHeaderMode::Required),
+            });
+        }
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(DltFile {
+            mmap: Some(mmap),
+            options: ParseOptions::new(StorageHeaderMode::Required),
+        })
+    }
+
+    /// parse the file as `options` say (e.g. for storage headers with another
+    /// magic), the storage header mode of `options` is not used
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// the complete content of the file
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.mmap {
+            Some(mmap) => &mmap[..],
+            None => &[],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_bytes().is_empty()
+    }
+
+    /// all messages of the file, decoded into owned `Message`s
+    pub fn messages(&self) -> Messages<'_> {
+        messages(self.as_bytes()).with_options(self.options)
+    }
+
+    /// the number of complete messages, see `dlt_parse::count_messages`
+    pub fn count_messages(&self) -> Result<usize, DltParseError> {
+        count_messages_with_options(self.as_bytes(), self.options)
+    }
+
+    /// the ids used in the file, see `dlt_parse::inventory`
+    pub fn inventory(&self) -> DltInventory {
+        inventory_with_options(self.as_bytes(), self.options)
+    }
+
+    /// all messages of the file, borrowing their content from the mapping
+    ///
+    /// a message that is cut off at the end of the file is reported as error
+    pub fn message_refs(&self) -> MessageRefs<'_> {
+        message_refs(self.as_bytes()).with_options(self.options)
+    }
+}
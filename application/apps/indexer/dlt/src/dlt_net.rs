@@ -185,21 +185,26 @@ pub async fn create_index_and_mapping_dlt_from_socket(
     trace!("create_index_and_mapping_dlt_from_socket");
     let res = match utils::next_line_nr(out_path) {
         Ok(initial_line_nr) => {
-            let filter_config: Option<filtering::ProcessedDltFilterConfig> =
-                dlt_filter.map(filtering::process_filter_config);
-            match index_from_socket(
-                session_id,
-                socket_config,
-                filter_config,
-                update_channel.clone(),
-                fibex_metadata,
-                tag,
-                out_path,
-                initial_line_nr,
-                shutdown_receiver,
-            )
-            .await
-            {
+            let indexed = match dlt_filter.map(filtering::process_filter_config).transpose() {
+                Ok(filter_config) => {
+                    index_from_socket(
+                        session_id,
+                        socket_config,
+                        filter_config,
+                        update_channel.clone(),
+                        fibex_metadata,
+                        tag,
+                        out_path,
+                        initial_line_nr,
+                        shutdown_receiver,
+                    )
+                    .await
+                }
+                Err(e) => Err(ConnectionError::WrongConfiguration {
+                    cause: format!("invalid filter: {}", e),
+                }),
+            };
+            match indexed {
                 Err(ConnectionError::WrongConfiguration { cause }) => {
                     let _ = update_channel.send(Err(Notification {
                         severity: Severity::ERROR,
@@ -307,7 +312,7 @@ impl futures::Stream for UdpMessageProducer {
                     0,
                     Some(&self.update_channel),
                     self.fibex_metadata.clone(),
-                    false,
+                    StorageHeaderMode::Absent,
                 ) {
                     Ok((_, ParsedMessage::Invalid)) => {
                         warn!("invalid message received");
@@ -331,7 +336,7 @@ impl futures::Stream for UdpMessageProducer {
                             return futures::task::Poll::Ready(Some(Ok(Some(messages))));
                         }
                     }
-                    Err(DltParseError::IncompleteParse { .. }) => {
+                    Err(DltParseError::IncompleteData { .. }) => {
                         // TODO handle situation: multiple messages but last message is split
                         return futures::task::Poll::Pending;
                     }
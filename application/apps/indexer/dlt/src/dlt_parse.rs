@@ -9,51 +9,170 @@
 // Dissemination of this information or reproduction of this material
 // is strictly forbidden unless prior written permission is obtained
 // from E.S.R.Labs.
-use crate::{dlt::*, filtering};
+use crate::dlt::*;
+#[cfg(not(feature = "std"))]
+use crate::without_std::{
+    cc, report_error, ChunkResults, FibexMetadata, IndexingResults, Notification,
+    ProcessedDltFilterConfig, Severity,
+};
+#[cfg(feature = "std")]
+use crate::{
+    fibex::{FibexMetadata, FrameMetadata},
+    filtering::ProcessedDltFilterConfig,
+};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt, str};
+#[cfg(feature = "std")]
 use crossbeam_channel as cc;
+#[cfg(feature = "std")]
 use indexer_base::{chunks::ChunkResults, error_reporter::*, progress::*, utils};
 use serde::Serialize;
 
+#[cfg(feature = "std")]
 use buf_redux::{policy::MinBuffered, BufReader as ReduxReader};
 use byteorder::{BigEndian, LittleEndian};
 use nom::{
     bytes::streaming::{tag, take, take_while_m_n},
     combinator::map,
-    multi::count,
+    error::{ErrorKind, ParseError},
     number::streaming,
     sequence::tuple,
     IResult,
 };
+#[cfg(feature = "std")]
 use rustc_hash::FxHashMap;
+#[cfg(feature = "std")]
 use std::{
+    collections::HashMap,
     fs,
     io::{BufRead, Read},
-    rc::Rc,
 };
-use thiserror::Error;
-
-use crate::fibex::FibexMetadata;
-use std::str;
 
+#[cfg(feature = "std")]
 const STOP_CHECK_LINE_THRESHOLD: usize = 250_000;
+#[cfg(feature = "std")]
 pub(crate) const DLT_READER_CAPACITY: usize = 10 * 1024 * 1024;
+#[cfg(feature = "std")]
 pub(crate) const DLT_MIN_BUFFER_SPACE: usize = 10 * 1024;
 pub(crate) const DLT_PATTERN_SIZE: usize = 4;
-pub(crate) const DLT_PATTERN: &[u8] = &[0x44, 0x4C, 0x54, 0x01];
+/// the first bytes of a standard storage header: `DLT` and `0x01`
+///
+/// some tools write a different pattern, iterators and the stream parser can be
+/// configured with `with_storage_magic` for these files, the other functions
+/// take it as `ParseOptions::storage_magic` (see the `_with_options` variants)
+pub const DLT_STORAGE_MAGIC: [u8; 4] = [0x44, 0x4C, 0x54, 0x01];
+#[cfg(test)]
+pub(crate) const DLT_PATTERN: &[u8] = &DLT_STORAGE_MAGIC;
+/// the biggest message (including its storage header) that is accepted by default
+///
+/// dlt-daemon limits messages to 1390 bytes unless configured otherwise, a
+/// bigger length is most likely a corrupt header. Traces with bigger messages
+/// can be parsed with a higher `ParseOptions::max_message_size`, up to
+/// `MAX_MESSAGE_SIZE`.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024;
+/// the biggest message the length field of the standard header allows (plus storage header)
+pub const MAX_MESSAGE_SIZE: usize = STORAGE_HEADER_LENGTH as usize + u16::MAX as usize;
+
+/// how storage headers are expected in the parsed input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageHeaderMode {
+    /// every message starts with a storage header (dlt files),
+    /// bytes in front of the next storage header are skipped
+    Required,
+    /// a message might start with a storage header or directly with the standard header
+    Optional,
+    /// messages start with the standard header (e.g. when received from an ECU
+    /// over the network), no storage header is expected
+    Absent,
+}
+
+/// how much of the payload is decoded when a message is parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadMode {
+    /// decode the arguments, the payload of non-verbose and control messages
+    /// is split off as usual
+    Decode,
+    /// keep the payload bytes as they are (`PayloadContent::Raw`), they can be
+    /// decoded later with `Message::decode_payload`
+    Raw,
+    /// drop the payload and only keep its length (`PayloadContent::Skipped`)
+    Skip,
+}
+
+/// how a message is parsed by `dlt_message_with_options`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub storage_header_mode: StorageHeaderMode,
+    /// the first bytes of a storage header
+    pub storage_magic: [u8; 4],
+    pub payload_mode: PayloadMode,
+    /// keep arguments of verbose messages that cannot be decoded (e.g. with an
+    /// unknown type info) as raw data instead of failing on the whole message
+    pub lenient: bool,
+    /// messages (including their storage header) that claim to be bigger are
+    /// rejected with `ImplausibleLength`
+    pub max_message_size: usize,
+}
+
+impl ParseOptions {
+    /// standard storage headers and decoded payloads
+    pub fn new(storage_header_mode: StorageHeaderMode) -> Self {
+        ParseOptions {
+            storage_header_mode,
+            storage_magic: DLT_STORAGE_MAGIC,
+            payload_mode: PayloadMode::Decode,
+            lenient: false,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// the same options for input that always has storage headers
+    pub(crate) fn with_storage_header(self) -> Self {
+        ParseOptions {
+            storage_header_mode: StorageHeaderMode::Required,
+            ..self
+        }
+    }
+}
+
+impl StorageHeaderMode {
+    /// whether the message at the start of `input` is parsed with a storage header
+    pub(crate) fn applies_to(self, input: &[u8], storage_magic: [u8; 4]) -> bool {
+        match self {
+            StorageHeaderMode::Required => true,
+            StorageHeaderMode::Optional => input.starts_with(&storage_magic),
+            StorageHeaderMode::Absent => false,
+        }
+    }
+}
 
 pub(crate) fn parse_ecu_id(input: &[u8]) -> IResult<&[u8], &str> {
     dlt_zero_terminated_string(input, 4)
 }
-/// skip ahead in input array till we reach a storage header
+
+/// skip ahead in input array till we reach a storage header starting with `storage_magic`
 /// return Some(dropped, rest_slice) that starts with the next storage header (if any)
 /// or None if no more storage header could be found.
 /// note: we won't skip anything if the input already begins
 /// with a storage header
-pub(crate) fn forward_to_next_storage_header(input: &[u8]) -> Option<(u64, &[u8])> {
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub(crate) fn forward_to_next_storage_header(
+    input: &[u8],
+    storage_magic: [u8; 4],
+) -> Option<(u64, &[u8])> {
     let mut found = false;
     let mut to_drop = 0usize;
     for v in input.windows(DLT_PATTERN_SIZE) {
-        if v == DLT_PATTERN {
+        if v == storage_magic {
             found = true;
             break;
         }
@@ -73,14 +192,100 @@ pub(crate) fn forward_to_next_storage_header(input: &[u8]) -> Option<(u64, &[u8]
     Some((to_drop as u64, &input[to_drop..]))
 }
 
+/// find the offset of the next storage header that plausibly starts a message
+///
+/// the `DLT\x01` pattern can also show up inside of a payload, so a candidate
+/// is only accepted if the standard header that follows it makes sense
+/// (supported version and a length that covers all announced headers).
+/// A candidate too close to the end of the input to be checked is accepted.
+pub fn find_next_storage_header(input: &[u8]) -> Option<usize> {
+    find_next_storage_header_with_magic(input, DLT_STORAGE_MAGIC)
+}
+
+/// like `find_next_storage_header` for storage headers that start with `storage_magic`
+pub fn find_next_storage_header_with_magic(input: &[u8], storage_magic: [u8; 4]) -> Option<usize> {
+    let mut offset = 0usize;
+    while let Some(pos) = input[offset..]
+        .windows(DLT_PATTERN_SIZE)
+        .position(|w| w == storage_magic)
+    {
+        let candidate = offset + pos;
+        if is_plausible_message_start(&input[candidate..]) {
+            return Some(candidate);
+        }
+        trace!("ignoring storage header pattern at {}", candidate);
+        offset = candidate + 1;
+    }
+    None
+}
+
+fn is_plausible_message_start(input: &[u8]) -> bool {
+    check_message_start(input).is_ok()
+}
+
+/// check the standard header after the storage header at the start of `input`
+fn check_message_start(input: &[u8]) -> Result<(), DltParseError> {
+    let header_start = STORAGE_HEADER_LENGTH as usize;
+    let (header_type_byte, overall_length) = match input.get(header_start..header_start + 4) {
+        Some(&[header_type_byte, _, len_high, len_low]) => {
+            (header_type_byte, u16::from_be_bytes([len_high, len_low]))
+        }
+        _ => return Ok(()),
+    };
+    check_version(header_type_byte)?;
+    if overall_length < calculate_all_headers_length(header_type_byte) {
+        return Err(DltParseError::ParsingHickup {
+            reason: format!("invalid message length {}", overall_length),
+        });
+    }
+    Ok(())
+}
+
+/// number of bytes till the next storage header
+///
+/// nothing is skipped when the input already starts with a storage header,
+/// otherwise we resync using `find_next_storage_header`
+pub(crate) fn bytes_to_next_storage_header(input: &[u8], storage_magic: [u8; 4]) -> Option<usize> {
+    if input.starts_with(&storage_magic) {
+        Some(0)
+    } else {
+        find_next_storage_header_with_magic(input, storage_magic)
+    }
+}
+
+/// parse a storage header that starts right at the beginning of the input
+pub(crate) fn dlt_storage_header_ref(
+    input: &[u8],
+    storage_magic: [u8; 4],
+) -> IResult<&[u8], StorageHeaderRef<'_>> {
+    let (i, (_, seconds, microseconds)) = tuple((
+        tag(&storage_magic[..]),
+        streaming::le_u32,
+        streaming::le_u32,
+    ))(input)?;
+    let (after_string, ecu_id) = dlt_zero_terminated_string(i, 4)?;
+    Ok((
+        after_string,
+        StorageHeaderRef {
+            timestamp: DltTimeStamp {
+                seconds,
+                microseconds,
+            },
+            ecu_id,
+        },
+    ))
+}
+
 pub(crate) fn dlt_storage_header<'a, T>(
     input: &'a [u8],
     index: Option<usize>,
     update_channel: Option<&cc::Sender<IndexingResults<T>>>,
+    storage_magic: [u8; 4],
 ) -> IResult<&'a [u8], Option<StorageHeader>> {
     // println!("dlt_storage_header (left: {} bytes)", input.len());
-    match forward_to_next_storage_header(input) {
-        Some((consumed, rest)) => {
+    match bytes_to_next_storage_header(input, storage_magic) {
+        Some(consumed) => {
+            let rest = &input[consumed..];
             if consumed > 0 {
                 if let Some(tx) = update_channel {
                     let _ = tx.send(Err(Notification {
@@ -90,24 +295,9 @@ pub(crate) fn dlt_storage_header<'a, T>(
                     }));
                 }
             }
-            let (i, (_, _, seconds, microseconds)) = tuple((
-                tag("DLT"),
-                tag(&[0x01]),
-                streaming::le_u32,
-                streaming::le_u32,
-            ))(rest)?;
-            let (after_string, ecu_id) = dlt_zero_terminated_string(i, 4)?;
-            // println!("after_stringA (left: {} bytes)", after_string.len());
-            Ok((
-                after_string,
-                Some(StorageHeader {
-                    timestamp: DltTimeStamp {
-                        seconds,
-                        microseconds,
-                    },
-                    ecu_id: ecu_id.to_string(),
-                }),
-            ))
+            let (after_storage_header, storage_header) =
+                dlt_storage_header_ref(rest, storage_magic)?;
+            Ok((after_storage_header, Some(storage_header.to_owned())))
         }
         None => {
             if let Some(tx) = update_channel {
@@ -149,9 +339,52 @@ fn maybe_parse_u32(a: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Option<u32>> {
     }
 }
 
-/// The standard header is part of every DLT message
-/// all big endian format [PRS_Dlt_00091]
-pub(crate) fn dlt_standard_header(input: &[u8]) -> IResult<&[u8], StandardHeader> {
+/// The fields of a standard header with the ECU-ID still borrowed from the input
+/// this allows checking a message against a filter before allocating anything
+pub(crate) struct StandardHeaderFields<'a> {
+    header_type_byte: u8,
+    message_counter: u8,
+    overall_length: u16,
+    ecu_id: Option<&'a str>,
+    session_id: Option<u32>,
+    timestamp: Option<u32>,
+}
+
+impl<'a> StandardHeaderFields<'a> {
+    fn to_standard_header_ref(&self) -> StandardHeaderRef<'a> {
+        let header_type_byte = self.header_type_byte;
+        StandardHeaderRef {
+            version: header_type_version(header_type_byte),
+            endianness: if (header_type_byte & BIG_ENDIAN_FLAG) != 0 {
+                Endianness::Big
+            } else {
+                Endianness::Little
+            },
+            has_extended_header: (header_type_byte & WITH_EXTENDED_HEADER_FLAG) != 0,
+            message_counter: self.message_counter,
+            ecu_id: self.ecu_id,
+            session_id: self.session_id,
+            timestamp: self.timestamp,
+            // the overall length is only validated later, don't underflow if it is too short
+            payload_length: self
+                .overall_length
+                .saturating_sub(calculate_all_headers_length(header_type_byte)),
+        }
+    }
+    fn to_standard_header(&self) -> StandardHeader {
+        self.to_standard_header_ref().to_owned()
+    }
+}
+
+/// we only know how to parse messages of `DLT_VERSION`
+fn check_version(header_type_byte: u8) -> Result<(), DltParseError> {
+    match header_type_version(header_type_byte) {
+        DLT_VERSION => Ok(()),
+        version => Err(DltParseError::UnsupportedVersion(version)),
+    }
+}
+
+pub(crate) fn dlt_standard_header_fields(input: &[u8]) -> IResult<&[u8], StandardHeaderFields<'_>> {
     let (rest, header_type_byte) = streaming::be_u8(input)?;
     let has_ecu_id = (header_type_byte & WITH_ECU_ID_FLAG) != 0;
     let has_session_id = (header_type_byte & WITH_SESSION_ID_FLAG) != 0;
@@ -163,39 +396,75 @@ pub(crate) fn dlt_standard_header(input: &[u8]) -> IResult<&[u8], StandardHeader
         maybe_parse_u32(has_session_id),
         maybe_parse_u32(has_timestamp),
     ))(rest)?;
-    let has_extended_header = (header_type_byte & WITH_EXTENDED_HEADER_FLAG) != 0;
-    let payload_length = overall_length - calculate_all_headers_length(header_type_byte);
-
     Ok((
         i,
-        StandardHeader::new(
-            header_type_byte >> 5 & 0b111,
-            if (header_type_byte & BIG_ENDIAN_FLAG) != 0 {
-                Endianness::Big
-            } else {
-                Endianness::Little
-            },
+        StandardHeaderFields {
+            header_type_byte,
             message_counter,
-            has_extended_header,
-            payload_length,
-            ecu_id.map(|r| r.to_string()),
+            overall_length,
+            ecu_id,
             session_id,
             timestamp,
-        ),
+        },
     ))
 }
 
-pub(crate) fn dlt_extended_header<'a, T>(
-    input: &'a [u8],
-    index: Option<usize>,
-    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
-) -> IResult<&'a [u8], ExtendedHeader> {
+/// The standard header is part of every DLT message
+/// all big endian format [PRS_Dlt_00091]
+pub(crate) fn dlt_standard_header(input: &[u8]) -> IResult<&[u8], StandardHeader> {
+    let (i, fields) = dlt_standard_header_fields(input)?;
+    Ok((i, fields.to_standard_header()))
+}
+
+/// The fields of an extended header with App-ID and Context-ID still borrowed from the input
+pub(crate) struct ExtendedHeaderFields<'a> {
+    message_info: u8,
+    argument_count: u8,
+    app_id: &'a str,
+    context_id: &'a str,
+}
+
+pub(crate) fn dlt_extended_header_fields(input: &[u8]) -> IResult<&[u8], ExtendedHeaderFields<'_>> {
     let (i, (message_info, argument_count, app_id, context_id)) = tuple((
         streaming::be_u8,
         streaming::be_u8,
         parse_ecu_id,
         parse_ecu_id,
     ))(input)?;
+    Ok((
+        i,
+        ExtendedHeaderFields {
+            message_info,
+            argument_count,
+            app_id,
+            context_id,
+        },
+    ))
+}
+
+pub(crate) fn dlt_extended_header<'a, T>(
+    input: &'a [u8],
+    index: Option<usize>,
+    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
+) -> IResult<&'a [u8], ExtendedHeader> {
+    let (i, fields) = dlt_extended_header_fields(input)?;
+    Ok((i, to_extended_header(&fields, index, update_channel)?))
+}
+
+fn to_extended_header<'a, T>(
+    fields: &ExtendedHeaderFields,
+    index: Option<usize>,
+    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
+) -> Result<ExtendedHeader, nom::Err<(&'a [u8], nom::error::ErrorKind)>> {
+    to_extended_header_ref(fields, index, update_channel).map(|h| h.to_owned())
+}
+
+fn to_extended_header_ref<'a, 'b, T>(
+    fields: &ExtendedHeaderFields<'b>,
+    index: Option<usize>,
+    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
+) -> Result<ExtendedHeaderRef<'b>, nom::Err<(&'a [u8], nom::error::ErrorKind)>> {
+    let message_info = fields.message_info;
     let verbose = (message_info & VERBOSE_FLAG) != 0;
     match MessageType::try_from(message_info) {
         Ok(message_type) => {
@@ -239,16 +508,13 @@ pub(crate) fn dlt_extended_header<'a, T>(
                     _ => (),
                 };
             };
-            Ok((
-                i,
-                ExtendedHeader {
-                    verbose,
-                    argument_count,
-                    message_type,
-                    application_id: app_id.to_string(),
-                    context_id: context_id.to_string(),
-                },
-            ))
+            Ok(ExtendedHeaderRef {
+                verbose,
+                argument_count: fields.argument_count,
+                message_type,
+                application_id: fields.app_id,
+                context_id: fields.context_id,
+            })
         }
         Err(e) => {
             if let Some(tx) = update_channel {
@@ -283,33 +549,72 @@ pub fn dlt_zero_terminated_string(s: &[u8], size: usize) -> IResult<&[u8], &str>
     Ok((rest, res_str))
 }
 
+/// the value of a string argument, decoded according to the string coding
+///
+/// the content ends at the first null byte, anything after it is skipped
+pub(crate) fn dlt_string_value<'a>(
+    input: &'a [u8],
+    size: usize,
+    coding: &StringCoding,
+) -> IResult<&'a [u8], Cow<'a, str>> {
+    let (rest, content) = take(size)(input)?;
+    let without_null = match content.iter().position(|b| *b == 0) {
+        Some(end) => &content[..end],
+        None => content,
+    };
+    Ok((rest, coding.decode(without_null)))
+}
+
+/// names and units without content are treated as missing
+fn non_empty(s: &str) -> Option<&str> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+/// a name or unit of the variable info, it ends at the first null byte
+///
+/// unlike string values these have no string coding, so they have to be UTF-8
+fn dlt_variable_string(input: &[u8], size: usize) -> Result<(&[u8], &str), DltParseError> {
+    let (rest, content) = take(size)(input)?;
+    let without_null = match content.iter().position(|b| *b == 0) {
+        Some(end) => &content[..end],
+        None => content,
+    };
+    Ok((rest, str::from_utf8(without_null)?))
+}
+
+/// the variable info (VARI) of numeric arguments
+///
+/// both lengths come first, then the zero terminated name and unit. For fixed
+/// point arguments quantization and offset follow the variable info.
 #[allow(clippy::type_complexity)]
-fn dlt_variable_name_and_unit<T: NomByteOrder>(
+fn dlt_variable_name_and_unit<'a, T: NomByteOrder>(
     type_info: &TypeInfo,
-) -> fn(&[u8]) -> IResult<&[u8], (Option<String>, Option<String>)> {
-    // println!("dlt_variable_name_and_unit");
-    if type_info.has_variable_info {
-        |input| {
-            let (i2, name_size_unit_size) = tuple((T::parse_u16, T::parse_u16))(input)?;
-            dbg_parsed("namesize, unitsize", input, i2, &name_size_unit_size);
-            // println!("(name_size, unit_size): {:?}", (name_size, unit_size));
-            let (i3, name) = dlt_zero_terminated_string(i2, name_size_unit_size.0 as usize)?;
-            dbg_parsed("name", i2, i3, &name);
-            // println!("name: {}", name);
-            let (rest, unit) = dlt_zero_terminated_string(i3, name_size_unit_size.1 as usize)?;
-            dbg_parsed("unit", i3, rest, &unit);
-            // println!("unit: {}", unit);
-            Ok((rest, (Some(name.to_string()), Some(unit.to_string()))))
-        }
-    } else {
-        |input| Ok((input, (None, None)))
+    input: &'a [u8],
+) -> Result<(&'a [u8], (Option<&'a str>, Option<&'a str>)), DltParseError> {
+    if !type_info.has_variable_info {
+        return Ok((input, (None, None)));
     }
+    let (i2, name_size_unit_size) = tuple((T::parse_u16, T::parse_u16))(input)?;
+    dbg_parsed("namesize, unitsize", input, i2, &name_size_unit_size);
+    let (i3, name) = dlt_variable_string(i2, name_size_unit_size.0 as usize)?;
+    dbg_parsed("name", i2, i3, &name);
+    let (rest, unit) = dlt_variable_string(i3, name_size_unit_size.1 as usize)?;
+    dbg_parsed("unit", i3, rest, &unit);
+    Ok((rest, (non_empty(name), non_empty(unit))))
 }
-fn dlt_variable_name<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], String> {
+/// the variable info (VARI) of bool, string and raw arguments: only a name
+fn dlt_variable_name<T: NomByteOrder>(
+    input: &[u8],
+) -> Result<(&[u8], Option<&str>), DltParseError> {
     let (i, size) = T::parse_u16(input)?;
-    let (i2, name) = dlt_zero_terminated_string(i, size as usize)?;
-    Ok((i2, name.to_string()))
+    let (rest, name) = dlt_variable_string(i, size as usize)?;
+    Ok((rest, non_empty(name)))
 }
+
 pub trait NomByteOrder: Clone + Copy + Eq + Ord + PartialEq + PartialOrd {
     fn parse_u16(i: &[u8]) -> IResult<&[u8], u16>;
     fn parse_i16(i: &[u8]) -> IResult<&[u8], i16>;
@@ -430,11 +735,17 @@ pub(crate) fn dlt_sint<T: NomByteOrder>(width: TypeLength) -> fn(&[u8]) -> IResu
 }
 pub(crate) fn dlt_fint<T: NomByteOrder>(width: FloatWidth) -> fn(&[u8]) -> IResult<&[u8], Value> {
     match width {
+        FloatWidth::Width16 => |i| map(T::parse_u16, |bits| Value::F16(f16_to_f32(bits)))(i),
         FloatWidth::Width32 => |i| map(T::parse_f32, Value::F32)(i),
         FloatWidth::Width64 => |i| map(T::parse_f64, Value::F64)(i),
     }
 }
+#[cfg(test)]
 pub(crate) fn dlt_type_info<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], TypeInfo> {
+    type_info::<T>(input).map_err(|e| to_nom_error(input, e))
+}
+
+fn type_info<T: NomByteOrder>(input: &[u8]) -> Result<(&[u8], TypeInfo), DltParseError> {
     let (i, info) = T::parse_u32(input)?;
     match TypeInfo::try_from(info) {
         Ok(type_info) => {
@@ -444,10 +755,22 @@ pub(crate) fn dlt_type_info<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], Typ
         }
         Err(_) => {
             report_error(format!("dlt_type_info no type_info for 0x{:02X?}", info));
-            Err(nom::Err::Error((&[], nom::error::ErrorKind::Verify)))
+            Err(DltParseError::UnknownTypeInfo(info))
         }
     }
 }
+
+/// the nom style results of `dlt_type_info` and `dlt_argument` (used by the
+/// parser tests)
+///
+/// only missing data is `Incomplete`, data that cannot be decoded is a `Failure`
+#[cfg(test)]
+fn to_nom_error(input: &[u8], err: DltParseError) -> nom::Err<(&[u8], nom::error::ErrorKind)> {
+    match err {
+        DltParseError::IncompleteData { needed } => nom::Err::Incomplete(nom::Needed::Size(needed)),
+        _ => nom::Err::Failure((input, nom::error::ErrorKind::Verify)),
+    }
+}
 pub(crate) fn dlt_fixed_point<T: NomByteOrder>(
     input: &[u8],
     width: FloatWidth,
@@ -479,97 +802,217 @@ pub(crate) fn dlt_fixed_point<T: NomByteOrder>(
         Err(nom::Err::Error((&[], nom::error::ErrorKind::Verify)))
     }
 }
+/// parser for one element of an array with the given element type
+fn dlt_array_element<T: NomByteOrder>(
+    element: &TypeInfoKind,
+) -> fn(&[u8]) -> IResult<&[u8], Value> {
+    match element {
+        TypeInfoKind::Bool => |i| map(streaming::be_u8, Value::Bool)(i),
+        TypeInfoKind::Signed(length) => dlt_sint::<T>(*length),
+        TypeInfoKind::SignedFixedPoint(width) => dlt_sint::<T>(float_width_to_type_length(*width)),
+        TypeInfoKind::Unsigned(length) => dlt_uint::<T>(*length),
+        TypeInfoKind::UnsignedFixedPoint(width) => {
+            dlt_uint::<T>(float_width_to_type_length(*width))
+        }
+        TypeInfoKind::Float(width) => dlt_fint::<T>(*width),
+        _ => |i| Err(nom::Err::Error((i, nom::error::ErrorKind::Verify))),
+    }
+}
+
+/// apply `f` `n` times, like `nom::multi::count`
+pub(crate) fn count<I, O, E, F>(f: F, n: usize) -> impl Fn(I) -> IResult<I, Vec<O>, E>
+where
+    I: Clone,
+    F: Fn(I) -> IResult<I, O, E>,
+    E: ParseError<I>,
+{
+    move |i: I| {
+        let mut input = i.clone();
+        let mut res = Vec::new();
+        for _ in 0..n {
+            match f(input.clone()) {
+                Ok((rest, o)) => {
+                    res.push(o);
+                    input = rest;
+                }
+                Err(nom::Err::Error(e)) => {
+                    return Err(nom::Err::Error(E::append(i, ErrorKind::Count, e)));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok((input, res))
+    }
+}
+
+/// an array argument after its type info
+///
+/// the number of dimensions and the size of each dimension come first,
+/// followed by the variable info, the fixed point fields and the elements
+fn dlt_array<'a, T: NomByteOrder>(
+    input: &'a [u8],
+    type_info: &TypeInfo,
+    element: &TypeInfoKind,
+) -> Result<(&'a [u8], ArgumentRef<'a>), DltParseError> {
+    let (i, dim_count) = T::parse_u16(input)?;
+    let (i, dims) = count(T::parse_u16, dim_count as usize)(i)?;
+    dbg_parsed("array dimensions", input, i, &dims);
+    let (i, (name, unit)) = dlt_variable_name_and_unit::<T>(type_info, i)?;
+    let (i, fixed_point) = match element {
+        TypeInfoKind::SignedFixedPoint(width) | TypeInfoKind::UnsignedFixedPoint(width) => {
+            map(|i| dlt_fixed_point::<T>(i, *width), Some)(i)?
+        }
+        _ => (i, None),
+    };
+    let element_count = if dims.is_empty() {
+        0
+    } else {
+        dims.iter()
+            .try_fold(1usize, |n, dim| n.checked_mul(*dim as usize))
+            .unwrap_or(usize::MAX)
+    };
+    // do not allocate for elements that are not there, arrays are always
+    // inside of a payload so more data would not help
+    let needed = element_count.saturating_mul(array_element_width(element));
+    if needed > i.len() {
+        return Err(DltParseError::ParsingHickup {
+            reason: format!(
+                "array of {} elements does not fit into the remaining {} bytes",
+                element_count,
+                i.len()
+            ),
+        });
+    }
+    let (rest, values) = count(dlt_array_element::<T>(element), element_count)(i)?;
+    let value = Value::Array {
+        element_type: element.clone(),
+        dims,
+        values,
+    };
+    Ok((
+        rest,
+        ArgumentRef {
+            type_info: type_info.clone(),
+            name,
+            unit,
+            value: ValueRef::Scalar(match &fixed_point {
+                Some(fp) => fp.scale(value),
+                None => value,
+            }),
+            fixed_point,
+        },
+    ))
+}
+
+#[cfg(test)]
 pub(crate) fn dlt_argument<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], Argument> {
-    let (i, type_info) = dlt_type_info::<T>(input)?;
+    map(dlt_argument_ref::<T>, |arg| arg.to_owned())(input)
+}
+
+/// parse an argument without copying strings or raw data
+#[cfg(test)]
+pub(crate) fn dlt_argument_ref<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], ArgumentRef<'_>> {
+    argument_ref::<T>(input).map_err(|e| to_nom_error(input, e))
+}
+
+/// like `argument_ref` for an argument inside of a payload
+///
+/// the payload is complete, so an argument that is cut off does not fit into
+/// it and more data would not help
+pub(crate) fn payload_argument_ref<T: NomByteOrder>(
+    input: &[u8],
+) -> Result<(&[u8], ArgumentRef<'_>), DltParseError> {
+    argument_ref::<T>(input).map_err(|e| match e {
+        DltParseError::IncompleteData { .. } => DltParseError::ParsingHickup {
+            reason: format!("argument exceeds the payload ({} bytes left)", input.len()),
+        },
+        e => e,
+    })
+}
+
+/// `IncompleteData` if the argument is cut off at the end of `input`
+fn argument_ref<T: NomByteOrder>(input: &[u8]) -> Result<(&[u8], ArgumentRef<'_>), DltParseError> {
+    let (i, type_info) = type_info::<T>(input)?;
     dbg_parsed("type info", input, i, &type_info);
-    // println!("type info: {:?}", type_info);
     match type_info.kind {
+        TypeInfoKind::Array(ref element) => dlt_array::<T>(i, &type_info, element),
         TypeInfoKind::Signed(width) => {
-            let (before_val, name_unit) = dlt_variable_name_and_unit::<T>(&type_info)(i)?;
+            let (before_val, name_unit) = dlt_variable_name_and_unit::<T>(&type_info, i)?;
             dbg_parsed("name and unit", i, before_val, &name_unit);
             let (rest, value) = dlt_sint::<T>(width)(before_val)?;
             dbg_parsed("sint", before_val, rest, &value);
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name: name_unit.0,
                     unit: name_unit.1,
-                    value,
+                    value: ValueRef::Scalar(value),
                     fixed_point: None,
                     type_info,
                 },
             ))
         }
         TypeInfoKind::SignedFixedPoint(width) => {
-            // println!("parsing TypeInfoKind::Signed");
-            let (before_val, name_unit) = dlt_variable_name_and_unit::<T>(&type_info)(i)?;
+            let (before_val, name_unit) = dlt_variable_name_and_unit::<T>(&type_info, i)?;
             dbg_parsed("name and unit", i, before_val, &name_unit);
-            let (r, fp) = dlt_fixed_point::<T>(before_val, width)?;
-            let (after_fixed_point, fixed_point) = (r, Some(fp));
+            let (after_fixed_point, fp) = dlt_fixed_point::<T>(before_val, width)?;
+            let fixed_point = Some(fp.clone());
             dbg_parsed("fixed_point", before_val, after_fixed_point, &fixed_point);
             let (rest, value) =
                 dlt_sint::<T>(float_width_to_type_length(width))(after_fixed_point)?;
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name: name_unit.0,
                     unit: name_unit.1,
-                    value,
+                    value: ValueRef::Scalar(fp.scale(value)),
                     fixed_point,
                     type_info,
                 },
             ))
         }
         TypeInfoKind::Unsigned(width) => {
-            let (before_val, (name, unit)) = dlt_variable_name_and_unit::<T>(&type_info)(i)?;
-            // println!("Unsigned: calling dlt_uint for {:02X?}", before_val);
+            let (before_val, (name, unit)) = dlt_variable_name_and_unit::<T>(&type_info, i)?;
             let (rest, value) = dlt_uint::<T>(width)(before_val)?;
             dbg_parsed("unsigned", before_val, rest, &value);
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name,
                     unit,
-                    value,
+                    value: ValueRef::Scalar(value),
                     fixed_point: None,
                     type_info,
                 },
             ))
         }
         TypeInfoKind::UnsignedFixedPoint(width) => {
-            let (before_val, (name, unit)) = dlt_variable_name_and_unit::<T>(&type_info)(i)?;
-            let (after_fixed_point, fixed_point) = {
-                let (r, fp) = dlt_fixed_point::<T>(before_val, width)?;
-                (r, Some(fp))
-            };
-            // println!(
-            //     "UnsignedFixedPoint: calling dlt_uint for {:02X?}",
-            //     before_val
-            // );
+            let (before_val, (name, unit)) = dlt_variable_name_and_unit::<T>(&type_info, i)?;
+            let (after_fixed_point, fp) = dlt_fixed_point::<T>(before_val, width)?;
             let (rest, value) =
                 dlt_uint::<T>(float_width_to_type_length(width))(after_fixed_point)?;
+            let value = fp.scale(value);
+            let fixed_point = Some(fp);
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name,
                     unit,
-                    value,
+                    value: ValueRef::Scalar(value),
                     fixed_point,
                     type_info,
                 },
             ))
         }
         TypeInfoKind::Float(width) => {
-            let (rest, ((name, unit), value)) = tuple((
-                dlt_variable_name_and_unit::<T>(&type_info),
-                dlt_fint::<T>(width),
-            ))(i)?;
+            let (before_val, (name, unit)) = dlt_variable_name_and_unit::<T>(&type_info, i)?;
+            let (rest, value) = dlt_fint::<T>(width)(before_val)?;
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name,
                     unit,
-                    value,
+                    value: ValueRef::Scalar(value),
                     fixed_point: None,
                     type_info,
                 },
@@ -578,14 +1021,14 @@ pub(crate) fn dlt_argument<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], Argu
         TypeInfoKind::Raw => {
             let (i2, raw_byte_cnt) = T::parse_u16(i)?;
             let (i3, name) = if type_info.has_variable_info {
-                map(dlt_variable_name::<T>, Some)(i2)?
+                dlt_variable_name::<T>(i2)?
             } else {
                 (i2, None)
             };
-            let (rest, value) = map(take(raw_byte_cnt), |s: &[u8]| Value::Raw(s.to_vec()))(i3)?;
+            let (rest, value) = map(take(raw_byte_cnt), ValueRef::Raw)(i3)?;
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name,
                     unit: None,
                     value,
@@ -596,7 +1039,7 @@ pub(crate) fn dlt_argument<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], Argu
         }
         TypeInfoKind::Bool => {
             let (after_var_name, name) = if type_info.has_variable_info {
-                map(dlt_variable_name::<T>, Some)(i)?
+                dlt_variable_name::<T>(i)?
             } else {
                 (i, None)
             };
@@ -605,35 +1048,31 @@ pub(crate) fn dlt_argument<T: NomByteOrder>(input: &[u8]) -> IResult<&[u8], Argu
             dbg_parsed("bool value", after_var_name, rest, &bool_value);
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     type_info,
                     name,
                     unit: None,
                     fixed_point: None,
-                    value: Value::Bool(bool_value),
+                    value: ValueRef::Scalar(Value::Bool(bool_value)),
                 },
             ))
         }
         TypeInfoKind::StringType => {
             let (i2, size) = T::parse_u16(i)?;
             let (i3, name) = if type_info.has_variable_info {
-                map(dlt_variable_name::<T>, Some)(i2)?
+                dlt_variable_name::<T>(i2)?
             } else {
                 (i2, None)
             };
-            let (rest, value) = dlt_zero_terminated_string(i3, size as usize)?;
+            let (rest, value) = dlt_string_value(i3, size as usize, &type_info.coding)?;
             dbg_parsed("StringType", i3, rest, &value);
-            // println!(
-            //     "was stringtype: \"{}\", size should have been {}",
-            //     value, size
-            // );
             Ok((
                 rest,
-                Argument {
+                ArgumentRef {
                     name,
                     unit: None,
                     fixed_point: None,
-                    value: Value::StringVal(value.to_string()),
+                    value: ValueRef::StringVal(value),
                     type_info,
                 },
             ))
@@ -646,108 +1085,330 @@ struct DltArgumentParser {
     current_index: Option<usize>,
 }
 
+/// arguments of a verbose payload, every argument that cannot be decoded is kept
+/// as a raw argument
+///
+/// the data of the raw argument is what follows the type info. If the length
+/// bits of the type info tell the size of the value, parsing goes on after it,
+/// otherwise the rest of the payload becomes the raw argument.
+fn dlt_lenient_arguments<T: NomByteOrder>(payload: &[u8], arg_cnt: u8) -> Vec<Argument> {
+    let mut i = payload;
+    let mut arguments = Vec::with_capacity(arg_cnt as usize);
+    while arguments.len() < arg_cnt as usize && !i.is_empty() {
+        if let Ok((after_argument, argument)) = payload_argument_ref::<T>(i) {
+            arguments.push(argument.to_owned());
+            i = after_argument;
+            continue;
+        }
+        debug!(
+            "argument {} could not be decoded, kept as raw data",
+            arguments.len()
+        );
+        let (data, value_length) = match T::parse_u32(i) {
+            Ok((data, info)) if info & TYPE_INFO_VARIABLE_INFO == 0 => {
+                (data, type_length_bytes(info))
+            }
+            Ok((data, _)) => (data, None),
+            Err(_) => (i, None),
+        };
+        match value_length.and_then(|len| data.get(..len)) {
+            Some(value) => {
+                arguments.push(raw_argument(value.to_vec()));
+                i = &data[value.len()..];
+            }
+            None => {
+                arguments.push(raw_argument(data.to_vec()));
+                break;
+            }
+        }
+    }
+    arguments
+}
+
+/// size of a value as given by the length bits (TYLE) of a type info
+fn type_length_bytes(info: u32) -> Option<usize> {
+    match info & 0b1111 {
+        tyle @ 0x01..=0x05 => Some(1 << (tyle - 1)),
+        _ => None,
+    }
+}
+
+/// the payload of `payload_length` bytes at the start of `input`
+///
+/// only a payload that is cut off is `IncompleteData`, anything within the
+/// payload that cannot be decoded is reported with the error of the argument
+/// that failed
 fn dlt_payload<T: NomByteOrder>(
     input: &[u8],
     verbose: bool,
     payload_length: u16,
     arg_cnt: u8,
     is_controll_msg: bool,
-) -> IResult<&[u8], Payload2> {
-    // println!("try to parse dlt_payload for {:02X?}", input,);
-    if verbose {
-        // println!("verbose, arg_cnt = {}", arg_cnt);
-        let (rest, arguments) = count(dlt_argument::<T>, arg_cnt as usize)(input)?;
-        Ok((
-            rest,
-            Payload2 {
-                payload_content: PayloadContent::Verbose(arguments),
-            },
-        ))
-    } else if is_controll_msg {
-        // println!("is_controll_msg");
-        if payload_length < 1 {
-            // println!("error, payload too short {}", payload_length);
-            return Err(nom::Err::Failure((&[], nom::error::ErrorKind::Verify)));
+    lenient: bool,
+) -> Result<(&[u8], Payload2), DltParseError> {
+    let (rest, payload) = take(payload_length)(input)?;
+    let payload_content = if verbose && lenient {
+        PayloadContent::Verbose(dlt_lenient_arguments::<T>(payload, arg_cnt))
+    } else if verbose {
+        let mut i = payload;
+        let mut arguments = Vec::with_capacity(arg_cnt as usize);
+        for _ in 0..arg_cnt {
+            let (after_argument, argument) = payload_argument_ref::<T>(i)?;
+            arguments.push(argument.to_owned());
+            i = after_argument;
         }
-        match tuple((nom::number::complete::be_u8, take(payload_length - 1)))(input) {
-            Ok((rest, (control_msg_id, payload))) => Ok((
-                rest,
-                Payload2 {
-                    payload_content: PayloadContent::ControlMsg(
-                        ControlType::from_value(control_msg_id),
-                        payload.to_vec(),
-                    ),
-                },
-            )),
-            Err(e) => {
-                // println!("error e {:?}", e);
-                Err(e)
+        PayloadContent::Verbose(arguments)
+    } else if is_controll_msg {
+        match payload.split_first() {
+            Some((control_msg_id, data)) => {
+                PayloadContent::ControlMsg(ControlType::from_value(*control_msg_id), data.to_vec())
+            }
+            None => {
+                return Err(DltParseError::ParsingHickup {
+                    reason: "control message without payload".into(),
+                })
             }
         }
     } else {
-        // println!("non verbose (input.len = {})", input.len());
-        // println!(
-        //     "not is_controll_msg, payload_length: {}, input left: {}",
-        //     payload_length,
-        //     input.len()
-        // );
-        if input.len() < 4 {
-            // println!("error, payload too short {}", input.len());
-            return Err(nom::Err::Failure((&[], nom::error::ErrorKind::Verify)));
-        }
-        match tuple((T::parse_u32, take(payload_length - 4)))(input) {
-            Ok((rest, (message_id, payload))) => Ok((
-                rest,
-                Payload2 {
-                    payload_content: PayloadContent::NonVerbose(message_id, payload.to_vec()),
-                },
-            )),
-            Err(e) => {
-                // println!("error e {:?}", e);
-                Err(e)
+        match T::parse_u32(payload) {
+            Ok((data, message_id)) => PayloadContent::NonVerbose(message_id, data.to_vec()),
+            Err(_) => {
+                return Err(DltParseError::ParsingHickup {
+                    reason: "non-verbose payload without message id".into(),
+                })
             }
         }
-    }
+    };
+    Ok((rest, Payload2 { payload_content }))
 }
 
-#[inline]
-fn dbg_parsed<T: std::fmt::Debug>(_name: &str, _before: &[u8], _after: &[u8], _value: &T) {
-    #[cfg(feature = "debug_parser")]
-    {
-        let input_len = _before.len();
-        let now_len = _after.len();
-        let parsed_len = input_len - now_len;
-        if parsed_len == 0 {
-            trace!("{}: not parsed", _name);
+impl Message {
+    /// decode a payload that was kept with `PayloadMode::Raw`
+    ///
+    /// the bytes are decoded with the endianness of the standard header like in
+    /// `dlt_message`, `lenient` has the meaning of `ParseOptions::lenient`.
+    /// Payloads that are already decoded are left as they are,
+    /// a payload dropped with `PayloadMode::Skip` cannot be decoded anymore.
+    pub fn decode_payload(&mut self, lenient: bool) -> Result<(), DltParseError> {
+        let data = match &self.payload.payload_content {
+            PayloadContent::Raw(data) => data,
+            PayloadContent::Skipped(_) => {
+                return Err(DltParseError::ParsingHickup {
+                    reason: "the payload was skipped while parsing".into(),
+                })
+            }
+            _ => return Ok(()),
+        };
+        let (verbose, arg_count, is_control_msg) = match &self.extended_header {
+            Some(h) => (
+                h.verbose,
+                h.argument_count,
+                matches!(h.message_type, MessageType::Control(_)),
+            ),
+            None => (false, 0, false),
+        };
+        let payload_length = data.len() as u16;
+        let (_, payload) = if self.header.endianness == Endianness::Big {
+            dlt_payload::<BigEndian>(
+                data,
+                verbose,
+                payload_length,
+                arg_count,
+                is_control_msg,
+                lenient,
+            )?
         } else {
-            trace!(
-                "parsed {} ({} bytes: {:02X?}) => {:?}",
-                _name,
-                parsed_len,
-                &_before[0..parsed_len],
-                _value
-            );
-        }
+            dlt_payload::<LittleEndian>(
+                data,
+                verbose,
+                payload_length,
+                arg_count,
+                is_control_msg,
+                lenient,
+            )?
+        };
+        self.payload = payload;
+        Ok(())
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum ParsedMessage {
-    /// Regular message, could be parsed
-    Item(Message),
-    /// message was filtered out due to filter conditions (Log-Level etc.)
-    FilteredOut,
-    /// Parsed message was invalid, no parse possible
-    Invalid,
-}
-
-/// a DLT message looks like this: [STANDARD-HEADER][EXTENDED-HEADER][PAYLOAD]
-/// if stored, an additional header is placed BEFORE all of this [storage-header][...]
-/// example: 444C5401 262CC94D D8A20C00 45435500 3500001F 45435500 3F88623A 16014150 5000434F 4E001100 00000472 656D6F
-/// --------------------------------------------
-/// [STORAGE-HEADER]: 444C5401 262CC94D D8A20C00 45435500
-///     444C5401 = DLT + 0x01 (DLT Pattern)
-///  timestamp_sec: 262CC94D = 0x4DC92C26
+#[cfg(feature = "std")]
+/// decode one signal of a non-verbose message as described in the FIBEX
+/// strings and raw data are prefixed by their length (u16)
+fn dlt_fibex_signal<'a, T: NomByteOrder>(
+    input: &'a [u8],
+    signal_type: &TypeInfo,
+) -> IResult<&'a [u8], Argument> {
+    let (rest, value) = match signal_type.kind {
+        TypeInfoKind::StringType => {
+            let (i, length) = T::parse_u16(input)?;
+            let (rest, content) = take(length)(i)?;
+            (
+                rest,
+                Value::StringVal(String::from_utf8_lossy(content).into_owned()),
+            )
+        }
+        TypeInfoKind::Raw => {
+            let (i, length) = T::parse_u16(input)?;
+            let (rest, content) = take(length)(i)?;
+            (rest, Value::Raw(content.to_vec()))
+        }
+        TypeInfoKind::Bool => map(streaming::be_u8, Value::Bool)(input)?,
+        TypeInfoKind::Float(width) => dlt_fint::<T>(width)(input)?,
+        TypeInfoKind::Signed(length) => dlt_sint::<T>(length)(input)?,
+        TypeInfoKind::Unsigned(length) => dlt_uint::<T>(length)(input)?,
+        // quantization and offset are not part of a non-verbose payload
+        TypeInfoKind::SignedFixedPoint(width) => {
+            dlt_sint::<T>(float_width_to_type_length(width))(input)?
+        }
+        TypeInfoKind::UnsignedFixedPoint(width) => {
+            dlt_uint::<T>(float_width_to_type_length(width))(input)?
+        }
+        TypeInfoKind::Array(_) => {
+            report_error("arrays are not supported in non-verbose messages");
+            return Err(nom::Err::Error((input, nom::error::ErrorKind::Verify)));
+        }
+    };
+    Ok((
+        rest,
+        Argument {
+            type_info: signal_type.clone(),
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value,
+        },
+    ))
+}
+
+/// data that is not decoded any further, e.g. the unknown parts of a payload
+pub(crate) fn raw_argument(data: Vec<u8>) -> Argument {
+    Argument {
+        type_info: TypeInfo {
+            kind: TypeInfoKind::Raw,
+            coding: StringCoding::ASCII,
+            has_variable_info: false,
+            has_trace_info: false,
+            unmodelled_bits: 0,
+        },
+        name: None,
+        unit: None,
+        fixed_point: None,
+        value: Value::Raw(data),
+    }
+}
+
+#[cfg(feature = "std")]
+/// decode the payload of a non-verbose message using the layout of a FIBEX frame
+///
+/// PDUs that only carry a description become string arguments. If the data does not
+/// fit the frame description, whatever could not be decoded is kept as a raw argument.
+pub fn dlt_fibex_arguments<T: NomByteOrder>(frame: &FrameMetadata, data: &[u8]) -> Vec<Argument> {
+    let mut arguments = vec![];
+    let mut rest = data;
+    for pdu in &frame.pdus {
+        if let Some(description) = &pdu.description {
+            arguments.push(Argument {
+                type_info: TypeInfo {
+                    kind: TypeInfoKind::StringType,
+                    coding: StringCoding::UTF8,
+                    has_variable_info: false,
+                    has_trace_info: false,
+                    unmodelled_bits: 0,
+                },
+                name: None,
+                unit: None,
+                fixed_point: None,
+                value: Value::StringVal(description.to_string()),
+            });
+            continue;
+        }
+        for signal_type in &pdu.signal_types {
+            match dlt_fibex_signal::<T>(rest, signal_type) {
+                Ok((after_signal, argument)) => {
+                    arguments.push(argument);
+                    rest = after_signal;
+                }
+                Err(e) => {
+                    warn!(
+                        "non-verbose data does not match frame {}: {:?}",
+                        frame.short_name, e
+                    );
+                    arguments.push(raw_argument(rest.to_vec()));
+                    return arguments;
+                }
+            }
+        }
+    }
+    arguments
+}
+
+#[cfg(feature = "std")]
+/// decode the payload of a non-verbose message with the given FIBEX metadata
+///
+/// the frame is looked up by message id, app-id and context-id. Unknown message ids
+/// produce a single raw argument that contains the complete data.
+pub fn dlt_non_verbose_arguments(
+    fibex_metadata: &FibexMetadata,
+    extended_header: Option<&ExtendedHeader>,
+    message_id: u32,
+    data: &[u8],
+    endianness: Endianness,
+) -> Vec<Argument> {
+    match fibex_metadata.frame_for(
+        extended_header.map(|h| &h.application_id[..]),
+        extended_header.map(|h| &h.context_id[..]),
+        message_id,
+    ) {
+        Some(frame) => {
+            if endianness == Endianness::Big {
+                dlt_fibex_arguments::<BigEndian>(frame, data)
+            } else {
+                dlt_fibex_arguments::<LittleEndian>(frame, data)
+            }
+        }
+        None => vec![raw_argument(data.to_vec())],
+    }
+}
+
+#[inline]
+fn dbg_parsed<T: fmt::Debug>(_name: &str, _before: &[u8], _after: &[u8], _value: &T) {
+    #[cfg(feature = "debug_parser")]
+    {
+        let input_len = _before.len();
+        let now_len = _after.len();
+        let parsed_len = input_len - now_len;
+        if parsed_len == 0 {
+            trace!("{}: not parsed", _name);
+        } else {
+            trace!(
+                "parsed {} ({} bytes: {:02X?}) => {:?}",
+                _name,
+                parsed_len,
+                &_before[0..parsed_len],
+                _value
+            );
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParsedMessage {
+    /// Regular message, could be parsed
+    Item(Message),
+    /// message was filtered out due to filter conditions (Log-Level etc.)
+    FilteredOut,
+    /// Parsed message was invalid, no parse possible
+    Invalid,
+}
+
+/// a DLT message looks like this: [STANDARD-HEADER][EXTENDED-HEADER][PAYLOAD]
+/// if stored, an additional header is placed BEFORE all of this [storage-header][...]
+/// example: 444C5401 262CC94D D8A20C00 45435500 3500001F 45435500 3F88623A 16014150 5000434F 4E001100 00000472 656D6F
+/// --------------------------------------------
+/// [STORAGE-HEADER]: 444C5401 262CC94D D8A20C00 45435500
+///     444C5401 = DLT + 0x01 (DLT Pattern)
+///  timestamp_sec: 262CC94D = 0x4DC92C26
 ///  timestamp_us: D8A20C00 = 0x000CA2D8
 ///  ecu-id: 45435500 = b"ECU\0"
 ///
@@ -780,206 +1441,1555 @@ pub enum ParsedMessage {
 ///
 pub fn dlt_message<'a>(
     input: &'a [u8],
-    filter_config_opt: Option<&filtering::ProcessedDltFilterConfig>,
+    filter_config_opt: Option<&ProcessedDltFilterConfig>,
     index: usize,
     update_channel: Option<&cc::Sender<ChunkResults>>,
     fibex_metadata: Option<Rc<FibexMetadata>>,
-    with_storage_header: bool,
+    storage_header_mode: StorageHeaderMode,
 ) -> Result<(&'a [u8], ParsedMessage), DltParseError> {
+    dlt_message_with_options(
+        input,
+        filter_config_opt,
+        index,
+        update_channel,
+        fibex_metadata,
+        ParseOptions::new(storage_header_mode),
+    )
+}
+
+/// like `dlt_message` but storage headers may start with a different magic and
+/// the payload can be kept undecoded, see `ParseOptions`
+///
+/// payload criteria of the filter need the decoded payload, so with such a
+/// filter messages are always decoded
+pub fn dlt_message_with_options<'a>(
+    input: &'a [u8],
+    filter_config_opt: Option<&ProcessedDltFilterConfig>,
+    index: usize,
+    update_channel: Option<&cc::Sender<ChunkResults>>,
+    fibex_metadata: Option<Rc<FibexMetadata>>,
+    options: ParseOptions,
+) -> Result<(&'a [u8], ParsedMessage), DltParseError> {
+    let ParseOptions {
+        storage_header_mode,
+        storage_magic,
+        payload_mode,
+        lenient,
+        max_message_size,
+    } = options;
     // trace!("starting to parse dlt_message==================");
-    let (after_storage_header, storage_header) = if with_storage_header {
-        dlt_storage_header(input, Some(index), update_channel)?
-    } else {
-        (input, None)
-    };
+    let (after_storage_header, storage_header) =
+        if storage_header_mode.applies_to(input, storage_magic) {
+            match dlt_storage_header(input, Some(index), update_channel, storage_magic) {
+                Ok(parsed) => parsed,
+                // no storage header anywhere in the input
+                Err(nom::Err::Failure(_)) => return Err(DltParseError::InvalidStorageHeader),
+                Err(e) => return Err(e.into()),
+            }
+        } else {
+            (input, None)
+        };
     dbg_parsed(
         "storage header",
         &input,
         &after_storage_header,
         &storage_header,
     );
-    let (after_storage_and_normal_header, header) = dlt_standard_header(after_storage_header)?;
+    let (after_storage_and_normal_header, header_fields) =
+        dlt_standard_header_fields(after_storage_header)?;
+    check_version(header_fields.header_type_byte)?;
+    check_message_size(
+        if storage_header.is_some() {
+            STORAGE_HEADER_LENGTH as usize
+        } else {
+            0
+        },
+        header_fields.overall_length,
+        max_message_size,
+    )?;
+
+    let payload_length = match validated_payload_length(
+        header_fields.overall_length,
+        header_fields.header_type_byte,
+        Some(index),
+        update_channel,
+    ) {
+        Some(length) => length,
+        None => {
+            return Ok((after_storage_and_normal_header, ParsedMessage::Invalid));
+        }
+    };
+    let (after_headers, extended_header_fields) =
+        if (header_fields.header_type_byte & WITH_EXTENDED_HEADER_FLAG) != 0 {
+            let (rest, fields) = dlt_extended_header_fields(after_storage_and_normal_header)?;
+            (rest, Some(fields))
+        } else {
+            (after_storage_and_normal_header, None)
+        };
+    if let Some(filter_config) = filter_config_opt {
+        // the ECU-ID of the standard header is optional, the storage header
+        // might still tell us where the message came from
+        let ecu_id = header_fields
+            .ecu_id
+            .or_else(|| storage_header.as_ref().map(|h| &h.ecu_id[..]));
+        let matches = match &extended_header_fields {
+            Some(fields) => filter_config.matches(
+                ecu_id,
+                Some(fields.app_id),
+                Some(fields.context_id),
+                MessageType::try_from(fields.message_info).ok().as_ref(),
+            ),
+            None => filter_config.matches(ecu_id, None, None, None),
+        } && filter_config
+            .matches_time(storage_header.as_ref().map(|h| &h.timestamp));
+        if !matches {
+            // no need to parse further, skip payload
+            let (after_message, _) = take(payload_length)(after_headers)?;
+            return Ok((after_message, ParsedMessage::FilteredOut));
+        }
+    }
+    let header = header_fields.to_standard_header();
+    dbg_parsed(
+        "normal header",
+        after_storage_header,
+        after_storage_and_normal_header,
+        &header,
+    );
+
+    let mut verbose: bool = false;
+    let mut is_controll_msg = false;
+    let mut arg_count = 0;
+    let extended_header = match extended_header_fields {
+        Some(fields) => {
+            let ext_header = to_extended_header(&fields, Some(index), update_channel)?;
+            verbose = ext_header.verbose;
+            arg_count = ext_header.argument_count;
+            is_controll_msg = matches!(ext_header.message_type, MessageType::Control(_));
+            dbg_parsed(
+                "extended header",
+                after_storage_and_normal_header,
+                after_headers,
+                &ext_header,
+            );
+            Some(ext_header)
+        }
+        None => None,
+    };
+    // trace!("about to parse payload, left: {}", after_headers.len());
+    // trace!("after_headers: {} bytes left", after_headers.len());
     // trace!(
-    //     "parsed header is {}",
+    //     "parsing payload (header is {})",
     //     if header.endianness == Endianness::Big {
     //         "big endian"
     //     } else {
     //         "little endian"
     //     }
     // );
-    dbg_parsed(
-        "normal header",
-        &after_storage_header,
-        &after_storage_and_normal_header,
-        &header,
-    );
-    // trace!("dlt_msg 3, header: {:?}", serde_json::to_string(&header));
+    let decode = payload_mode == PayloadMode::Decode
+        || matches!(filter_config_opt, Some(filter_config) if filter_config.has_payload_criteria());
+    let (i, payload) = if !decode {
+        let (rest, data) = take(payload_length)(after_headers)?;
+        let payload_content = if payload_mode == PayloadMode::Raw {
+            PayloadContent::Raw(data.to_vec())
+        } else {
+            PayloadContent::Skipped(payload_length)
+        };
+        (rest, Payload2 { payload_content })
+    } else if header.endianness == Endianness::Big {
+        // trace!("parsing payload big endian");
+        dlt_payload::<BigEndian>(
+            after_headers,
+            verbose,
+            payload_length,
+            arg_count,
+            is_controll_msg,
+            lenient,
+        )?
+    } else {
+        // trace!("parsing payload little endian");
+        dlt_payload::<LittleEndian>(
+            after_headers,
+            verbose,
+            payload_length,
+            arg_count,
+            is_controll_msg,
+            lenient,
+        )?
+    };
+    dbg_parsed("payload", &after_headers, &i, &payload);
+    // trace!("after payload: {} bytes left", i.len());
+    let message = Message {
+        storage_header,
+        header,
+        extended_header,
+        payload,
+        fibex_metadata,
+    };
+    // without std there are no filters
+    #[cfg(feature = "std")]
+    if let Some(filter_config) = filter_config_opt {
+        // only messages that passed the header filter get here
+        if filter_config.has_payload_criteria() {
+            let payload = message.payload_text();
+            let ecu_id = message
+                .header
+                .ecu_id
+                .as_deref()
+                .or_else(|| message.storage_header.as_ref().map(|h| h.ecu_id.as_str()));
+            let ext = message.extended_header.as_ref();
+            if !filter_config.matches_payload(&payload)
+                || filter_config.excludes_payload(
+                    ecu_id,
+                    ext.map(|h| h.application_id.as_str()),
+                    ext.map(|h| h.context_id.as_str()),
+                    &payload,
+                )
+            {
+                return Ok((i, ParsedMessage::FilteredOut));
+            }
+        }
+    }
+    Ok((i, ParsedMessage::Item(message)))
+}
+
+/// parse a message without copying any of its content
+///
+/// ids, strings and raw data of the returned `MessageRef` point into `input`,
+/// the arguments of verbose messages are only decoded when iterating over them.
+/// This makes a pass over all messages (e.g. for counting or statistics) free of
+/// allocations. Use `MessageRef::to_owned` if a `Message` is needed.
+pub fn dlt_message_ref(
+    input: &[u8],
+    storage_header_mode: StorageHeaderMode,
+) -> Result<(&[u8], MessageRef<'_>), DltParseError> {
+    dlt_message_ref_with_options(input, ParseOptions::new(storage_header_mode))
+}
+
+/// like `dlt_message_ref` for storage headers that start with `storage_magic`
+pub fn dlt_message_ref_with_storage_magic(
+    input: &[u8],
+    storage_header_mode: StorageHeaderMode,
+    storage_magic: [u8; 4],
+) -> Result<(&[u8], MessageRef<'_>), DltParseError> {
+    dlt_message_ref_with_options(
+        input,
+        ParseOptions {
+            storage_magic,
+            ..ParseOptions::new(storage_header_mode)
+        },
+    )
+}
+
+/// like `dlt_message_ref` with the storage header mode, magic and size limit of
+/// `options`, the payload settings are not used
+pub fn dlt_message_ref_with_options(
+    input: &[u8],
+    options: ParseOptions,
+) -> Result<(&[u8], MessageRef<'_>), DltParseError> {
+    let ParseOptions {
+        storage_header_mode,
+        storage_magic,
+        max_message_size,
+        ..
+    } = options;
+    let (after_storage_header, storage_header) =
+        if storage_header_mode.applies_to(input, storage_magic) {
+            match bytes_to_next_storage_header(input, storage_magic) {
+                Some(skipped) => {
+                    let (after_storage_header, storage_header) =
+                        dlt_storage_header_ref(&input[skipped..], storage_magic)?;
+                    (after_storage_header, Some(storage_header))
+                }
+                None => return Err(DltParseError::InvalidStorageHeader),
+            }
+        } else {
+            (input, None)
+        };
+    let (after_standard_header, header_fields) = dlt_standard_header_fields(after_storage_header)?;
+    check_version(header_fields.header_type_byte)?;
+    check_message_size(
+        if storage_header.is_some() {
+            STORAGE_HEADER_LENGTH as usize
+        } else {
+            0
+        },
+        header_fields.overall_length,
+        max_message_size,
+    )?;
+    let payload_length = match validated_payload_length::<()>(
+        header_fields.overall_length,
+        header_fields.header_type_byte,
+        None,
+        None,
+    ) {
+        Some(length) => length,
+        None => {
+            return Err(DltParseError::ParsingHickup {
+                reason: format!("invalid message length {}", header_fields.overall_length),
+            })
+        }
+    };
+    let header = header_fields.to_standard_header_ref();
+    let (after_headers, extended_header) = if header.has_extended_header {
+        let (rest, fields) = dlt_extended_header_fields(after_standard_header)?;
+        (
+            rest,
+            Some(to_extended_header_ref::<()>(&fields, None, None)?),
+        )
+    } else {
+        (after_standard_header, None)
+    };
+    let (rest, payload_data) = take(payload_length)(after_headers)?;
+    let payload = match &extended_header {
+        Some(ext_header) if ext_header.verbose => PayloadRef::Verbose(ArgumentsRef {
+            endianness: header.endianness,
+            count: ext_header.argument_count,
+            data: payload_data,
+        }),
+        Some(ExtendedHeaderRef {
+            message_type: MessageType::Control(_),
+            ..
+        }) => match payload_data.split_first() {
+            Some((control_msg_id, data)) => {
+                PayloadRef::ControlMsg(ControlType::from_value(*control_msg_id), data)
+            }
+            None => {
+                return Err(DltParseError::ParsingHickup {
+                    reason: "control message without payload".into(),
+                })
+            }
+        },
+        _ => {
+            let (data, message_id) = if header.endianness == Endianness::Big {
+                BigEndian::parse_u32(payload_data)
+            } else {
+                LittleEndian::parse_u32(payload_data)
+            }
+            .map_err(|_: nom::Err<(&[u8], nom::error::ErrorKind)>| {
+                DltParseError::ParsingHickup {
+                    reason: "non-verbose payload without message id".into(),
+                }
+            })?;
+            PayloadRef::NonVerbose(message_id, data)
+        }
+    };
+    Ok((
+        rest,
+        MessageRef {
+            storage_header,
+            header,
+            extended_header,
+            payload,
+        },
+    ))
+}
+
+fn validated_payload_length<T>(
+    message_length: u16,
+    header_type_byte: u8,
+    index: Option<usize>,
+    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
+) -> Option<u16> {
+    let headers_length = calculate_all_headers_length(header_type_byte);
+    if message_length < headers_length {
+        if let Some(tx) = update_channel {
+            let _ = tx.send(Err(Notification {
+                severity: Severity::ERROR,
+                content: format!(
+                    "Invalid header length {} (message only has {} bytes)",
+                    headers_length, message_length
+                ),
+                line: index,
+            }));
+        }
+        return None;
+    }
+    Some(message_length - headers_length)
+}
+
+fn skip_till_after_next_storage_header(
+    input: &[u8],
+    storage_magic: [u8; 4],
+) -> Result<(&[u8], u64), DltParseError> {
+    match bytes_to_next_storage_header(input, storage_magic) {
+        Some(consumed) => {
+            let (after_storage_header, skipped_bytes) =
+                skip_storage_header(&input[consumed..], storage_magic)?;
+            Ok((after_storage_header, consumed as u64 + skipped_bytes))
+        }
+        None => Err(DltParseError::InvalidStorageHeader),
+    }
+}
+
+/// determine the number of bytes of the message at the beginning of the input
+///
+/// only the length field of the standard header is evaluated, so this works
+/// with just the first few bytes of a message.
+/// Returns `IncompleteData` if not even those bytes are available.
+pub fn dlt_frame_length(
+    input: &[u8],
+    storage_header_mode: StorageHeaderMode,
+) -> Result<usize, DltParseError> {
+    dlt_frame_length_with_limit(input, storage_header_mode, DEFAULT_MAX_MESSAGE_SIZE)
+}
+
+/// like `dlt_frame_length` but returns `ImplausibleLength` for frames
+/// that are bigger than `max_message_size`
+pub fn dlt_frame_length_with_limit(
+    input: &[u8],
+    storage_header_mode: StorageHeaderMode,
+    max_message_size: usize,
+) -> Result<usize, DltParseError> {
+    frame_length(
+        input,
+        storage_header_mode,
+        max_message_size,
+        DLT_STORAGE_MAGIC,
+    )
+}
+
+pub(crate) fn frame_length(
+    input: &[u8],
+    storage_header_mode: StorageHeaderMode,
+    max_message_size: usize,
+    storage_magic: [u8; 4],
+) -> Result<usize, DltParseError> {
+    if storage_header_mode == StorageHeaderMode::Optional && input.len() < DLT_PATTERN_SIZE {
+        // we cannot tell yet if this is a storage header
+        return Err(DltParseError::IncompleteData {
+            needed: DLT_PATTERN_SIZE - input.len(),
+        });
+    }
+    let with_storage_header = storage_header_mode.applies_to(input, storage_magic);
+    let storage_header_length = if with_storage_header {
+        STORAGE_HEADER_LENGTH as usize
+    } else {
+        0
+    };
+    // header-type and message counter are followed by the length (big endian)
+    let needed = storage_header_length + 4;
+    if input.len() < needed {
+        return Err(DltParseError::IncompleteData {
+            needed: needed - input.len(),
+        });
+    }
+    if with_storage_header && input[..DLT_PATTERN_SIZE] != storage_magic {
+        return Err(DltParseError::InvalidStorageHeader);
+    }
+    let overall_length = u16::from_be_bytes([
+        input[storage_header_length + 2],
+        input[storage_header_length + 3],
+    ]);
+    if overall_length < HEADER_MIN_LENGTH {
+        return Err(DltParseError::ParsingHickup {
+            reason: format!("invalid message length {}", overall_length),
+        });
+    }
+    check_message_size(storage_header_length, overall_length, max_message_size)?;
+    Ok(storage_header_length + overall_length as usize)
+}
+
+/// `ImplausibleLength` if a message is bigger than `max_message_size`
+fn check_message_size(
+    storage_header_length: usize,
+    overall_length: u16,
+    max_message_size: usize,
+) -> Result<(), DltParseError> {
+    let length = storage_header_length + overall_length as usize;
+    if length > max_message_size {
+        return Err(DltParseError::ImplausibleLength {
+            length,
+            max: max_message_size,
+        });
+    }
+    Ok(())
+}
+
+/// what `FrameWalker::step` found at the start of the input
+#[derive(Debug, PartialEq)]
+pub(crate) enum FrameStep {
+    /// a message of this many bytes (including its storage header) starts right
+    /// at the beginning of the input
+    Frame(usize),
+    /// this many bytes in front of the next storage header are no message
+    Skip(usize),
+    /// the message at the beginning of the input is cut off, at least `needed`
+    /// more bytes are missing
+    Incomplete { needed: usize },
+    /// no message starts at the beginning of the input
+    Invalid(DltParseError),
+}
+
+/// the framing shared by all message iterators and scans
+///
+/// only storage headers and the length fields of the standard headers are
+/// evaluated. With `StorageHeaderMode::Required` anything that is not a
+/// plausible storage header is skipped (see `find_next_storage_header`),
+/// without storage headers there is nothing to resync to and the input has to
+/// start with a message.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameWalker {
+    pub(crate) options: ParseOptions,
+    resync: bool,
+}
+
+impl FrameWalker {
+    pub(crate) fn new(options: ParseOptions) -> Self {
+        FrameWalker {
+            options,
+            resync: options.storage_header_mode == StorageHeaderMode::Required,
+        }
+    }
+
+    /// report anything but a storage header as `InvalidStorageHeader` instead of skipping it
+    pub(crate) fn without_resync(mut self) -> Self {
+        self.resync = false;
+        self
+    }
+
+    /// look at the start of `input` for the next message
+    ///
+    /// `at_end` tells that no more data follows the input, so its last bytes
+    /// are not kept as the possible start of a storage header
+    pub(crate) fn step(&self, input: &[u8], at_end: bool) -> FrameStep {
+        let ParseOptions {
+            storage_header_mode,
+            storage_magic,
+            max_message_size,
+            ..
+        } = self.options;
+        if self.resync {
+            match bytes_to_next_storage_header(input, storage_magic) {
+                Some(0) => {
+                    // unlike a storage header found further ahead, this one
+                    // might have a header that makes no sense
+                    if let Err(e) = check_message_start(input) {
+                        return FrameStep::Invalid(e);
+                    }
+                }
+                Some(skip) => return FrameStep::Skip(skip),
+                None => {
+                    let keep = if at_end {
+                        0
+                    } else {
+                        partial_storage_magic(input, storage_magic)
+                    };
+                    if keep < input.len() {
+                        return FrameStep::Skip(input.len() - keep);
+                    }
+                }
+            }
+        } else if storage_header_mode == StorageHeaderMode::Required
+            && !storage_magic.starts_with(&input[..input.len().min(DLT_PATTERN_SIZE)])
+        {
+            return FrameStep::Invalid(DltParseError::InvalidStorageHeader);
+        }
+        match frame_length(input, storage_header_mode, max_message_size, storage_magic) {
+            Ok(length) if length <= input.len() => FrameStep::Frame(length),
+            Ok(length) => FrameStep::Incomplete {
+                needed: length - input.len(),
+            },
+            Err(DltParseError::IncompleteData { needed }) => FrameStep::Incomplete { needed },
+            Err(e) => FrameStep::Invalid(e),
+        }
+    }
+
+    /// number of bytes to drop when the message at the start of `input` cannot be used
+    ///
+    /// the length field of a message that could not be parsed (`frame_length`) is
+    /// only trusted if the next message starts right after it, without storage
+    /// headers there is no way to check that. Otherwise we move on by one byte
+    /// and resync from there.
+    pub(crate) fn skip_unusable(&self, input: &[u8], frame_length: Option<usize>) -> usize {
+        match frame_length {
+            Some(length) if !self.resync || self.is_message_start(&input[length..]) => length,
+            _ => 1,
+        }
+    }
+
+    fn is_message_start(&self, input: &[u8]) -> bool {
+        input.is_empty()
+            || (input.starts_with(&self.options.storage_magic) && is_plausible_message_start(input))
+    }
+}
+
+/// length of the longest end of `input` that might be the start of a storage header
+fn partial_storage_magic(input: &[u8], storage_magic: [u8; 4]) -> usize {
+    let len = input.len();
+    (1..DLT_PATTERN_SIZE.min(len + 1))
+        .rev()
+        .find(|n| storage_magic.starts_with(&input[len - n..]))
+        .unwrap_or(0)
+}
+
+/// the frames of an input that is completely in memory, see `FrameWalker`
+///
+/// yields the position of every message together with its bytes, or with the
+/// error of a part of the input where no message could be found
+pub(crate) struct Frames<'a> {
+    input: &'a [u8],
+    offset: usize,
+    walker: FrameWalker,
+    pending_discarded: usize,
+    discarded: usize,
+}
+
+impl<'a> Frames<'a> {
+    pub(crate) fn new(input: &'a [u8], walker: FrameWalker) -> Self {
+        Frames {
+            input,
+            offset: 0,
+            walker,
+            pending_discarded: 0,
+            discarded: 0,
+        }
+    }
+
+    /// start looking for messages at `offset`
+    #[cfg(feature = "std")]
+    pub(crate) fn starting_at(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub(crate) fn options(&self) -> ParseOptions {
+        self.walker.options
+    }
+
+    pub(crate) fn options_mut(&mut self) -> &mut ParseOptions {
+        &mut self.walker.options
+    }
+
+    /// position in the input up to which messages were looked for
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// total number of bytes that were no message
+    pub(crate) fn discarded(&self) -> usize {
+        self.discarded
+    }
+
+    /// number of bytes that were no message since the last call
+    pub(crate) fn take_discarded(&mut self) -> usize {
+        core::mem::take(&mut self.pending_discarded)
+    }
+
+    /// the frame at `offset` could not be parsed, continue as `FrameWalker::skip_unusable` says
+    pub(crate) fn reject(&mut self, offset: usize, frame: &[u8]) {
+        self.offset = offset;
+        let skip = self
+            .walker
+            .skip_unusable(&self.input[offset..], Some(frame.len()));
+        self.skip(skip);
+    }
+
+    fn skip(&mut self, n: usize) {
+        self.offset += n;
+        self.pending_discarded += n;
+        self.discarded += n;
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = (usize, Result<&'a [u8], DltParseError>);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let offset = self.offset;
+            let input = &self.input[offset..];
+            if input.is_empty() {
+                return None;
+            }
+            let error = match self.walker.step(input, true) {
+                FrameStep::Frame(length) => {
+                    self.offset += length;
+                    return Some((offset, Ok(&input[..length])));
+                }
+                FrameStep::Skip(skip) => {
+                    self.skip(skip);
+                    continue;
+                }
+                FrameStep::Incomplete { needed } => DltParseError::IncompleteData { needed },
+                FrameStep::Invalid(e) => e,
+            };
+            let skip = self.walker.skip_unusable(input, None);
+            self.skip(skip);
+            return Some((offset, Err(error)));
+        }
+    }
+}
+
+/// result of `count_messages_and_rest`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MessageCount {
+    /// number of complete messages
+    pub messages: usize,
+    /// bytes of a message at the end of the input that is cut off
+    pub trailing_bytes: usize,
+}
+
+/// count the messages (with storage headers) in `input`
+///
+/// only the length fields are evaluated, nothing is decoded. A message that
+/// is cut off at the end of the input is not counted.
+pub fn count_messages(input: &[u8]) -> Result<usize, DltParseError> {
+    count_messages_with_options(input, ParseOptions::new(StorageHeaderMode::Required))
+}
+
+/// like `count_messages` with the storage magic and size limit of `options`
+///
+/// messages are always expected to have storage headers, the storage header
+/// mode of `options` is not used
+pub fn count_messages_with_options(
+    input: &[u8],
+    options: ParseOptions,
+) -> Result<usize, DltParseError> {
+    count_messages_and_rest_with_options(input, options).map(|count| count.messages)
+}
+
+/// like `count_messages` but also reports the size of a cut off last message
+pub fn count_messages_and_rest(input: &[u8]) -> Result<MessageCount, DltParseError> {
+    count_messages_and_rest_with_options(input, ParseOptions::new(StorageHeaderMode::Required))
+}
+
+/// like `count_messages_and_rest` with the options of `count_messages_with_options`
+pub fn count_messages_and_rest_with_options(
+    input: &[u8],
+    options: ParseOptions,
+) -> Result<MessageCount, DltParseError> {
+    let mut count = MessageCount {
+        messages: 0,
+        trailing_bytes: 0,
+    };
+    let walker = FrameWalker::new(options.with_storage_header()).without_resync();
+    for (offset, frame) in Frames::new(input, walker) {
+        match frame {
+            Ok(_) => count.messages += 1,
+            Err(DltParseError::IncompleteData { .. }) => {
+                count.trailing_bytes = input.len() - offset;
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(count)
+}
+
+/// a place where the time of the storage headers is not continuous,
+/// see `scan_time_anomalies`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeAnomaly {
+    /// index of the message with the unexpected time
+    pub index: usize,
+    pub previous_ts: DltTimeStamp,
+    pub current_ts: DltTimeStamp,
+    /// microseconds from the previous to the current time, negative if the time
+    /// goes backwards
+    pub delta: i64,
+}
+
+/// find the messages (with storage headers) whose time is before the time of the
+/// previous message or more than `max_jump` after it
+///
+/// only the storage headers and the length fields are evaluated. A message that
+/// is cut off at the end of the input is ignored.
+pub fn scan_time_anomalies(
+    input: &[u8],
+    max_jump: core::time::Duration,
+) -> Result<Vec<TimeAnomaly>, DltParseError> {
+    scan_time_anomalies_with_options(
+        input,
+        max_jump,
+        ParseOptions::new(StorageHeaderMode::Required),
+    )
+}
+
+/// like `scan_time_anomalies` with the storage magic and size limit of `options`
+///
+/// the storage header mode of `options` is not used
+pub fn scan_time_anomalies_with_options(
+    input: &[u8],
+    max_jump: core::time::Duration,
+    options: ParseOptions,
+) -> Result<Vec<TimeAnomaly>, DltParseError> {
+    let max_jump = max_jump.as_micros() as i64;
+    let mut anomalies = vec![];
+    let mut previous: Option<DltTimeStamp> = None;
+    let storage_magic = options.storage_magic;
+    let walker = FrameWalker::new(options.with_storage_header()).without_resync();
+    for (index, (_, frame)) in Frames::new(input, walker).enumerate() {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(DltParseError::IncompleteData { .. }) => break,
+            Err(e) => return Err(e),
+        };
+        let (_, storage_header) = dlt_storage_header_ref(frame, storage_magic)?;
+        let current = storage_header.timestamp;
+        if let Some(previous_ts) = previous {
+            let delta = current.as_us() as i64 - previous_ts.as_us() as i64;
+            if delta < 0 || delta > max_jump {
+                anomalies.push(TimeAnomaly {
+                    index,
+                    previous_ts,
+                    current_ts: current.clone(),
+                    delta,
+                });
+            }
+        }
+        previous = Some(current);
+    }
+    Ok(anomalies)
+}
+
+/// number of processed bytes between two calls of a progress callback
+pub const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+/// calls a progress callback at most once per `PROGRESS_INTERVAL` processed
+/// bytes and once more with `(total, total)` when `total` bytes are processed
+/// or the input is done
+pub(crate) struct Progress<'a> {
+    callback: Box<dyn FnMut(u64, u64) + 'a>,
+    total: u64,
+    next_report: u64,
+    finished: bool,
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new<F: FnMut(u64, u64) + 'a>(total: u64, callback: F) -> Self {
+        Progress {
+            callback: Box::new(callback),
+            total,
+            next_report: PROGRESS_INTERVAL,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn update(&mut self, processed: u64) {
+        if processed >= self.total {
+            self.finish();
+        } else if processed >= self.next_report {
+            (self.callback)(processed, self.total);
+            self.next_report = processed + PROGRESS_INTERVAL;
+        }
+    }
+
+    pub(crate) fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            (self.callback)(self.total, self.total);
+        }
+    }
+}
+
+/// where a message was found in the input
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SourceSpan {
+    /// position of the storage header, from the start of the input
+    pub offset: u64,
+    /// length of the message including its storage header
+    pub len: u32,
+}
+
+/// iterators over messages that know where each message was found
+pub(crate) trait SpannedMessages {
+    fn next_with_span(&mut self) -> Option<Result<(Message, SourceSpan), DltParseError>>;
+}
+
+/// adapter that yields every message together with its `SourceSpan`
+///
+/// created with `with_spans` of the message iterators
+pub struct WithSpans<I> {
+    inner: I,
+}
+
+impl<I: SpannedMessages> Iterator for WithSpans<I> {
+    type Item = Result<(Message, SourceSpan), DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_with_span()
+    }
+}
+
+/// a message produced by `ResyncingMessages`
+#[derive(Debug, PartialEq)]
+pub struct ResyncedMessage {
+    /// number of bytes that were discarded right before this message
+    /// to get back to a message boundary
+    pub discarded: usize,
+    pub message: ParsedMessage,
+}
+
+/// iterator over all messages of a dlt file (with storage headers) that does
+/// not give up on corrupt data
+///
+/// when a message cannot be parsed, the iterator skips ahead to the next
+/// plausible storage header (see `find_next_storage_header`) and continues there.
+pub struct ResyncingMessages<'a> {
+    frames: Frames<'a>,
+    index: usize,
+    filter_config: Option<&'a ProcessedDltFilterConfig>,
+    fibex_metadata: Option<Rc<FibexMetadata>>,
+    progress: Option<Progress<'a>>,
+}
+
+/// iterate over the messages in `input`, resyncing after corrupt regions
+pub fn messages_with_resync<'a>(
+    input: &'a [u8],
+    filter_config: Option<&'a ProcessedDltFilterConfig>,
+    fibex_metadata: Option<Rc<FibexMetadata>>,
+) -> ResyncingMessages<'a> {
+    ResyncingMessages {
+        frames: Frames::new(
+            input,
+            FrameWalker::new(ParseOptions::new(StorageHeaderMode::Required)),
+        ),
+        index: 0,
+        filter_config,
+        fibex_metadata,
+        progress: None,
+    }
+}
+
+impl<'a> ResyncingMessages<'a> {
+    /// report messages that claim to be bigger than `max_message_size` bytes
+    /// as `ImplausibleLength`, see `ParseOptions::max_message_size`
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.frames.options_mut().max_message_size = max_message_size;
+        self
+    }
+
+    /// expect storage headers that start with `storage_magic` instead of `DLT_STORAGE_MAGIC`
+    pub fn with_storage_magic(mut self, storage_magic: [u8; 4]) -> Self {
+        self.frames.options_mut().storage_magic = storage_magic;
+        self
+    }
+
+    /// decode the payload only as far as `payload_mode` says
+    pub fn with_payload_mode(mut self, payload_mode: PayloadMode) -> Self {
+        self.frames.options_mut().payload_mode = payload_mode;
+        self
+    }
+
+    /// keep arguments that cannot be decoded as raw data, see `ParseOptions::lenient`
+    pub fn with_lenient_arguments(mut self, lenient: bool) -> Self {
+        self.frames.options_mut().lenient = lenient;
+        self
+    }
+
+    /// call `callback` with the number of processed and the total number of
+    /// bytes while iterating, see `Messages::with_progress`
+    pub fn with_progress<F: FnMut(u64, u64) + 'a>(mut self, callback: F) -> Self {
+        self.progress = Some(Progress::new(self.frames.input.len() as u64, callback));
+        self
+    }
+
+    /// total number of bytes discarded so far (including a truncated tail)
+    pub fn discarded(&self) -> usize {
+        self.frames.discarded()
+    }
+}
+
+impl<'a> Iterator for ResyncingMessages<'a> {
+    type Item = ResyncedMessage;
+    fn next(&mut self) -> Option<ResyncedMessage> {
+        let next = self.next_message();
+        if let Some(progress) = &mut self.progress {
+            match next {
+                Some(_) => progress.update(self.frames.offset() as u64),
+                None => progress.finish(),
+            }
+        }
+        next
+    }
+}
+
+impl<'a> ResyncingMessages<'a> {
+    fn next_message(&mut self) -> Option<ResyncedMessage> {
+        loop {
+            let (offset, frame) = self.frames.next()?;
+            // corrupt length fields and a truncated tail are discarded
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(_) => continue,
+            };
+            match dlt_message_with_options(
+                frame,
+                self.filter_config,
+                self.index,
+                None,
+                self.fibex_metadata.clone(),
+                self.frames.options(),
+            ) {
+                Ok((_, message)) => {
+                    self.index += 1;
+                    let discarded = self.frames.take_discarded();
+                    return Some(ResyncedMessage { discarded, message });
+                }
+                Err(e) => {
+                    warn!("could not parse message at offset {}: {}", offset, e);
+                    self.frames.reject(offset, frame);
+                }
+            }
+        }
+    }
+}
+
+/// iterator over the messages of a dlt file that is completely in memory
+///
+/// created with `messages`
+pub struct Messages<'a> {
+    frames: Frames<'a>,
+    index: usize,
+    progress: Option<Progress<'a>>,
+}
+
+/// iterate over all messages (with storage headers) in `input`
+///
+/// every item is one decoded message or the error of a message that could not
+/// be decoded. After an error iteration continues at the next plausible
+/// storage header, so corrupt parts of a file don't hide the messages after them.
+pub fn messages(input: &[u8]) -> Messages<'_> {
+    Messages {
+        frames: Frames::new(
+            input,
+            FrameWalker::new(ParseOptions::new(StorageHeaderMode::Required)),
+        ),
+        index: 0,
+        progress: None,
+    }
+}
+
+impl<'a> Messages<'a> {
+    /// parse with all settings of `options`, messages are still expected to have
+    /// storage headers
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        *self.frames.options_mut() = options.with_storage_header();
+        self
+    }
+
+    /// report messages that claim to be bigger than `max_message_size` bytes
+    /// as `ImplausibleLength`, see `ParseOptions::max_message_size`
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.frames.options_mut().max_message_size = max_message_size;
+        self
+    }
+
+    /// expect storage headers that start with `storage_magic` instead of `DLT_STORAGE_MAGIC`
+    pub fn with_storage_magic(mut self, storage_magic: [u8; 4]) -> Self {
+        self.frames.options_mut().storage_magic = storage_magic;
+        self
+    }
+
+    /// decode the payload only as far as `payload_mode` says
+    pub fn with_payload_mode(mut self, payload_mode: PayloadMode) -> Self {
+        self.frames.options_mut().payload_mode = payload_mode;
+        self
+    }
+
+    /// keep arguments that cannot be decoded as raw data, see `ParseOptions::lenient`
+    pub fn with_lenient_arguments(mut self, lenient: bool) -> Self {
+        self.frames.options_mut().lenient = lenient;
+        self
+    }
+
+    /// call `callback` with the number of processed and the total number of
+    /// bytes while iterating
+    ///
+    /// the callback is called at most once per `PROGRESS_INTERVAL` bytes and
+    /// once with `(total, total)` when the end of the input is reached
+    pub fn with_progress<F: FnMut(u64, u64) + 'a>(mut self, callback: F) -> Self {
+        self.progress = Some(Progress::new(self.frames.input.len() as u64, callback));
+        self
+    }
+
+    /// yield the position of each message in the input as well
+    pub fn with_spans(self) -> WithSpans<Self> {
+        WithSpans { inner: self }
+    }
+
+    fn next_message(&mut self) -> Option<Result<(Message, SourceSpan), DltParseError>> {
+        loop {
+            let (offset, frame) = self.frames.next()?;
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(e) => return Some(Err(e)),
+            };
+            let res = dlt_message_with_options(
+                frame,
+                None,
+                self.index,
+                None,
+                None,
+                self.frames.options(),
+            );
+            self.index += 1;
+            match res {
+                Ok((_, ParsedMessage::Item(msg))) => {
+                    let span = SourceSpan {
+                        offset: offset as u64,
+                        len: frame.len() as u32,
+                    };
+                    return Some(Ok((msg, span)));
+                }
+                Ok(_) => (),
+                Err(e) => {
+                    self.frames.reject(offset, frame);
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = Result<Message, DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_span().map(|res| res.map(|(msg, _)| msg))
+    }
+}
+
+impl<'a> SpannedMessages for Messages<'a> {
+    fn next_with_span(&mut self) -> Option<Result<(Message, SourceSpan), DltParseError>> {
+        let next = self.next_message();
+        if let Some(progress) = &mut self.progress {
+            match next {
+                Some(_) => progress.update(self.frames.offset() as u64),
+                None => progress.finish(),
+            }
+        }
+        next
+    }
+}
+
+/// the parts of a `Message` without FIBEX metadata, which can be sent between threads
+#[cfg(feature = "std")]
+struct MessageParts {
+    storage_header: Option<StorageHeader>,
+    header: StandardHeader,
+    extended_header: Option<ExtendedHeader>,
+    payload: Payload2,
+}
 
-    // trace!("parsing 2...let's validate the payload length");
-    let payload_length = match validated_payload_length(&header, Some(index), update_channel) {
-        Some(length) => length,
-        None => {
-            return Ok((after_storage_and_normal_header, ParsedMessage::Invalid));
+#[cfg(feature = "std")]
+impl MessageParts {
+    fn into_message(self) -> Message {
+        Message {
+            storage_header: self.storage_header,
+            header: self.header,
+            extended_header: self.extended_header,
+            payload: self.payload,
+            fibex_metadata: None,
         }
-    };
+    }
+}
 
-    // trace!("dlt_msg 4, payload_length: {}", payload_length);
-    let mut verbose: bool = false;
-    let mut is_controll_msg = false;
-    let mut arg_count = 0;
-    let (after_headers, extended_header) = if header.has_extended_header {
-        // trace!("try to parse extended header");
-        let (rest, ext_header) =
-            dlt_extended_header(after_storage_and_normal_header, Some(index), update_channel)?;
-        verbose = ext_header.verbose;
-        arg_count = ext_header.argument_count;
-        // trace!(
-        //     "did parse extended header (type: {})",
-        //     ext_header.message_type
-        // );
-        is_controll_msg = match ext_header.message_type {
-            MessageType::Control(_) => true,
-            _ => false,
+/// parse all messages that start in `input[start..end]`
+///
+/// `start` does not need to be a message start, the first storage header after it is used.
+/// Returns the parsed messages and the offset of the first message that starts at
+/// or after `end` (or the input length). Messages that cannot be parsed are skipped.
+#[cfg(feature = "std")]
+fn parse_region(
+    input: &[u8],
+    start: usize,
+    end: usize,
+    options: ParseOptions,
+) -> (Vec<MessageParts>, usize) {
+    let mut parsed = vec![];
+    let mut frames = Frames::new(input, FrameWalker::new(options)).starting_at(start);
+    while let Some((offset, frame)) = frames.next() {
+        if offset >= end {
+            return (parsed, offset);
+        }
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(_) => continue,
         };
-        // trace!(
-        //     "did parse extended header, verbose: {}, arg_count: {}, is_controll: {}",
-        //     verbose,
-        //     arg_count,
-        //     is_controll_msg
-        // );
-        dbg_parsed(
-            "extended header",
-            &after_storage_and_normal_header,
-            &rest,
-            &ext_header,
-        );
-        (rest, Some(ext_header))
-    } else {
-        (after_storage_and_normal_header, None)
-    };
-    // trace!(
-    //     "extended header: {:?}",
-    //     serde_json::to_string(&extended_header)
-    // );
-    // trace!("dlt_msg 5");
-    if let Some(filter_config) = filter_config_opt {
-        // trace!("dlt_msg 6");
-        if let Some(h) = &extended_header {
-            // trace!("dlt_msg 7");
-            if let Some(min_filter_level) = filter_config.min_log_level {
-                if h.skip_with_level(min_filter_level) {
-                    // trace!("no need to parse further, skip payload (skipped level)");
-                    let (after_message, _) = take(payload_length)(after_headers)?;
-                    return Ok((after_message, ParsedMessage::FilteredOut));
+        match dlt_message_with_options(frame, None, 0, None, None, options) {
+            Ok((_, ParsedMessage::Item(msg))) => parsed.push(MessageParts {
+                storage_header: msg.storage_header,
+                header: msg.header,
+                extended_header: msg.extended_header,
+                payload: msg.payload,
+            }),
+            Ok(_) => (),
+            Err(_) => frames.reject(offset, frame),
+        }
+    }
+    (parsed, input.len())
+}
+
+/// parse all messages (with storage headers) of `input` using `threads` threads
+///
+/// the input is split at storage headers found by `find_next_storage_header` and
+/// every region is parsed on its own thread. Such a split point might be a storage
+/// pattern inside of a payload: every region reports where its last message ended
+/// and if that is not where the next region started, the next region is parsed
+/// again from there. So the result is the same as collecting the successfully
+/// parsed messages of `messages(input)` in original order.
+/// A panic of a worker thread is passed on to the caller.
+#[cfg(feature = "std")]
+pub fn parse_parallel(input: &[u8], threads: usize) -> Vec<Message> {
+    parse_parallel_with_options(
+        input,
+        threads,
+        ParseOptions::new(StorageHeaderMode::Required),
+    )
+}
+
+/// like `parse_parallel` but the messages are parsed as `options` say, the
+/// storage header mode of `options` is not used
+#[cfg(feature = "std")]
+pub fn parse_parallel_with_options(
+    input: &[u8],
+    threads: usize,
+    options: ParseOptions,
+) -> Vec<Message> {
+    let options = options.with_storage_header();
+    let threads = std::cmp::max(threads, 1);
+    let mut starts = vec![0usize];
+    for i in 1..threads {
+        let nominal = i * input.len() / threads;
+        let last = *starts.last().unwrap_or(&0);
+        if nominal <= last {
+            continue;
+        }
+        match find_next_storage_header_with_magic(&input[nominal..], options.storage_magic) {
+            Some(skip) => {
+                let candidate = nominal + skip;
+                if candidate > last {
+                    starts.push(candidate);
                 }
             }
-            if let Some(only_these_components) = &filter_config.app_ids {
-                if !only_these_components.contains(&h.application_id) {
-                    // trace!("no need to parse further, skip payload (skipped app id)");
-                    let (after_message, _) = take(payload_length)(after_headers)?;
-                    return Ok((after_message, ParsedMessage::FilteredOut));
-                }
+            None => break,
+        }
+    }
+    let regions: Vec<(usize, usize)> = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| (start, *starts.get(i + 1).unwrap_or(&input.len())))
+        .collect();
+    let results: Vec<(Vec<MessageParts>, usize)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = regions
+            .iter()
+            .map(|&(start, end)| scope.spawn(move || parse_region(input, start, end, options)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    let mut messages = vec![];
+    let mut parsed_till = 0usize;
+    for ((parts, stopped_at), &(start, end)) in results.into_iter().zip(&regions) {
+        let (parts, stopped_at) = if start == parsed_till {
+            (parts, stopped_at)
+        } else if parsed_till >= end {
+            // the previous region already covered all of this one
+            continue;
+        } else {
+            // this region did not start at a real message
+            parse_region(input, parsed_till, end, options)
+        };
+        messages.extend(parts.into_iter().map(MessageParts::into_message));
+        parsed_till = stopped_at;
+    }
+    messages
+}
+
+/// iterator over borrowed messages of a dlt file that is completely in memory
+///
+/// created with `message_refs`
+pub struct MessageRefs<'a> {
+    frames: Frames<'a>,
+    progress: Option<Progress<'a>>,
+}
+
+/// like `messages` but the messages borrow their strings and payload from `input`
+pub fn message_refs(input: &[u8]) -> MessageRefs<'_> {
+    MessageRefs {
+        frames: Frames::new(
+            input,
+            FrameWalker::new(ParseOptions::new(StorageHeaderMode::Required)),
+        ),
+        progress: None,
+    }
+}
+
+impl<'a> MessageRefs<'a> {
+    /// parse with the storage magic and size limit of `options`, messages are still expected to have
+    /// storage headers
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        *self.frames.options_mut() = options.with_storage_header();
+        self
+    }
+
+    /// expect storage headers that start with `storage_magic` instead of `DLT_STORAGE_MAGIC`
+    pub fn with_storage_magic(mut self, storage_magic: [u8; 4]) -> Self {
+        self.frames.options_mut().storage_magic = storage_magic;
+        self
+    }
+
+    /// report messages that claim to be bigger than `max_message_size` bytes
+    /// as `ImplausibleLength`, see `ParseOptions::max_message_size`
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.frames.options_mut().max_message_size = max_message_size;
+        self
+    }
+
+    /// call `callback` with the number of processed bytes and the size of the
+    /// input while iterating, see `Messages::with_progress`
+    ///
+    /// the messages borrow from the input as long as the callback is borrowed
+    pub fn with_progress<F: FnMut(u64, u64) + 'a>(mut self, callback: F) -> Self {
+        self.progress = Some(Progress::new(self.frames.input.len() as u64, callback));
+        self
+    }
+
+    fn next_message(&mut self) -> Option<Result<MessageRef<'a>, DltParseError>> {
+        let (offset, frame) = self.frames.next()?;
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => return Some(Err(e)),
+        };
+        match dlt_message_ref_with_options(frame, self.frames.options()) {
+            Ok((_, msg)) => Some(Ok(msg)),
+            Err(e) => {
+                self.frames.reject(offset, frame);
+                Some(Err(e))
             }
-            if let Some(only_these_context_ids) = &filter_config.context_ids {
-                if !only_these_context_ids.contains(&h.context_id) {
-                    // trace!("no need to parse further, skip payload (skipped context id)");
-                    let (after_message, _) = take(payload_length)(after_headers)?;
-                    return Ok((after_message, ParsedMessage::FilteredOut));
-                }
+        }
+    }
+}
+
+impl<'a> Iterator for MessageRefs<'a> {
+    type Item = Result<MessageRef<'a>, DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next_message();
+        if let Some(progress) = &mut self.progress {
+            match next {
+                Some(_) => progress.update(self.frames.offset() as u64),
+                None => progress.finish(),
             }
-            if let Some(only_these_ecu_ids) = &filter_config.ecu_ids {
-                if let Some(ecu_id) = &header.ecu_id {
-                    if !only_these_ecu_ids.contains(ecu_id) {
-                        // trace!("no need to parse further, skip payload (skipped ecu id)");
-                        let (after_message, _) = take(payload_length)(after_headers)?;
-                        return Ok((after_message, ParsedMessage::FilteredOut));
-                    }
+        }
+        next
+    }
+}
+
+/// iterator over the messages of a dlt file that is read from a `BufRead`
+///
+/// created with `messages_from_reader`
+#[cfg(feature = "std")]
+pub struct ReaderMessages<'a, R: BufRead> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// start of the data in the buffer that was not used yet, the used data is
+    /// only removed before more is read
+    start: usize,
+    /// position of `start` in the data read so far
+    offset: u64,
+    index: usize,
+    eof: bool,
+    walker: FrameWalker,
+    progress: Option<Progress<'a>>,
+}
+
+/// iterate over all messages (with storage headers) that can be read from `reader`
+///
+/// like `messages` but the data is read only as far as needed for the next message
+#[cfg(feature = "std")]
+pub fn messages_from_reader<'a, R: BufRead>(reader: R) -> ReaderMessages<'a, R> {
+    ReaderMessages {
+        reader,
+        buffer: Vec::new(),
+        start: 0,
+        offset: 0,
+        index: 0,
+        eof: false,
+        walker: FrameWalker::new(ParseOptions::new(StorageHeaderMode::Required)),
+        progress: None,
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: BufRead> ReaderMessages<'a, R> {
+    /// report messages that claim to be bigger than `max_message_size` bytes
+    /// as `ImplausibleLength` instead of reading their data
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.walker.options.max_message_size = max_message_size;
+        self
+    }
+
+    /// expect storage headers that start with `storage_magic` instead of `DLT_STORAGE_MAGIC`
+    pub fn with_storage_magic(mut self, storage_magic: [u8; 4]) -> Self {
+        self.walker.options.storage_magic = storage_magic;
+        self
+    }
+
+    /// decode the payload only as far as `payload_mode` says
+    pub fn with_payload_mode(mut self, payload_mode: PayloadMode) -> Self {
+        self.walker.options.payload_mode = payload_mode;
+        self
+    }
+
+    /// keep arguments that cannot be decoded as raw data, see `ParseOptions::lenient`
+    pub fn with_lenient_arguments(mut self, lenient: bool) -> Self {
+        self.walker.options.lenient = lenient;
+        self
+    }
+
+    /// call `callback` with the number of processed bytes and `total` while
+    /// iterating, see `Messages::with_progress`
+    ///
+    /// the reader does not know how much data there is, `total` (e.g. the size
+    /// of the file) is only used for reporting
+    pub fn with_progress<F: FnMut(u64, u64) + 'a>(mut self, total: u64, callback: F) -> Self {
+        self.progress = Some(Progress::new(total, callback));
+        self
+    }
+
+    /// yield the position of each message in the read data as well
+    pub fn with_spans(self) -> WithSpans<Self> {
+        WithSpans { inner: self }
+    }
+
+    fn buffered(&self) -> &[u8] {
+        &self.buffer[self.start..]
+    }
+
+    fn discard(&mut self, bytes: usize) {
+        self.start += bytes;
+        self.offset += bytes as u64;
+    }
+
+    /// try to decode the next message from what is buffered
+    ///
+    /// returns `None` if more data is needed
+    fn next_buffered(&mut self) -> Option<Result<(Message, SourceSpan), DltParseError>> {
+        loop {
+            if self.buffered().is_empty() {
+                return None;
+            }
+            let error = match self.walker.step(self.buffered(), self.eof) {
+                FrameStep::Frame(length) => match self.parse_buffered(length) {
+                    Some(res) => return Some(res),
+                    None => continue,
+                },
+                FrameStep::Skip(skip) => {
+                    self.discard(skip);
+                    continue;
                 }
+                FrameStep::Incomplete { needed } if self.eof => {
+                    DltParseError::IncompleteData { needed }
+                }
+                FrameStep::Incomplete { .. } => return None,
+                FrameStep::Invalid(e) => e,
+            };
+            let skip = self.walker.skip_unusable(self.buffered(), None);
+            self.discard(skip);
+            return Some(Err(error));
+        }
+    }
+
+    /// decode the buffered frame, `None` if it is no message (e.g. filtered out)
+    fn parse_buffered(
+        &mut self,
+        frame_length: usize,
+    ) -> Option<Result<(Message, SourceSpan), DltParseError>> {
+        let res = dlt_message_with_options(
+            &self.buffer[self.start..self.start + frame_length],
+            None,
+            self.index,
+            None,
+            None,
+            self.walker.options,
+        );
+        self.index += 1;
+        match res {
+            Ok((_, ParsedMessage::Item(msg))) => {
+                let span = SourceSpan {
+                    offset: self.offset,
+                    len: frame_length as u32,
+                };
+                self.discard(frame_length);
+                Some(Ok((msg, span)))
+            }
+            Ok(_) => {
+                self.discard(frame_length);
+                None
+            }
+            Err(e) => {
+                let skip = self
+                    .walker
+                    .skip_unusable(self.buffered(), Some(frame_length));
+                self.discard(skip);
+                Some(Err(e))
             }
         }
     }
-    // trace!("about to parse payload, left: {}", after_headers.len());
-    // trace!("after_headers: {} bytes left", after_headers.len());
-    // trace!(
-    //     "parsing payload (header is {})",
-    //     if header.endianness == Endianness::Big {
-    //         "big endian"
-    //     } else {
-    //         "little endian"
-    //     }
-    // );
-    let (i, payload) = if header.endianness == Endianness::Big {
-        // trace!("parsing payload big endian");
-        dlt_payload::<BigEndian>(
-            after_headers,
-            verbose,
-            payload_length,
-            arg_count,
-            is_controll_msg,
-        )?
-    } else {
-        // trace!("parsing payload little endian");
-        dlt_payload::<LittleEndian>(
-            after_headers,
-            verbose,
-            payload_length,
-            arg_count,
-            is_controll_msg,
-        )?
-    };
-    dbg_parsed("payload", &after_headers, &i, &payload);
-    // trace!("after payload: {} bytes left", i.len());
-    Ok((
-        i,
-        ParsedMessage::Item(Message {
-            storage_header,
-            header,
-            extended_header,
-            payload,
-            fibex_metadata,
-        }),
-    ))
 }
 
-fn validated_payload_length<T>(
-    header: &StandardHeader,
-    index: Option<usize>,
-    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
-) -> Option<u16> {
-    let message_length = header.overall_length();
-    let headers_length = calculate_all_headers_length(header.header_type_byte());
-    if message_length < headers_length {
-        if let Some(tx) = update_channel {
-            let _ = tx.send(Err(Notification {
-                severity: Severity::ERROR,
-                content: format!(
-                    "Invalid header length {} (message only has {} bytes)",
-                    headers_length, message_length
-                ),
-                line: index,
-            }));
+#[cfg(feature = "std")]
+impl<'a, R: BufRead> Iterator for ReaderMessages<'a, R> {
+    type Item = Result<Message, DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_span().map(|res| res.map(|(msg, _)| msg))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, R: BufRead> SpannedMessages for ReaderMessages<'a, R> {
+    fn next_with_span(&mut self) -> Option<Result<(Message, SourceSpan), DltParseError>> {
+        let next = self.next_message();
+        if let Some(progress) = &mut self.progress {
+            match next {
+                Some(_) => progress.update(self.offset),
+                None => progress.finish(),
+            }
         }
-        return None;
+        next
     }
-    Some(message_length - headers_length)
 }
 
-fn skip_till_after_next_storage_header(input: &[u8]) -> Result<(&[u8], u64), DltParseError> {
-    match forward_to_next_storage_header(input) {
-        Some((consumed, rest)) => {
-            let (after_storage_header, skipped_bytes) = skip_storage_header(rest)?;
-            Ok((after_storage_header, consumed + skipped_bytes))
+#[cfg(feature = "std")]
+impl<'a, R: BufRead> ReaderMessages<'a, R> {
+    fn next_message(&mut self) -> Option<Result<(Message, SourceSpan), DltParseError>> {
+        loop {
+            if let Some(res) = self.next_buffered() {
+                return Some(res);
+            }
+            if self.eof {
+                return None;
+            }
+            match self.reader.fill_buf() {
+                Ok([]) => self.eof = true,
+                Ok(content) => {
+                    let len = content.len();
+                    self.buffer.drain(..self.start);
+                    self.start = 0;
+                    self.buffer.extend_from_slice(content);
+                    self.reader.consume(len);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(e) => {
+                    self.eof = true;
+                    return Some(Err(e.into()));
+                }
+            }
         }
-        None => Err(DltParseError::ParsingHickup {
-            reason: "did not find another storage header".into(),
-        }),
     }
 }
 
-/// check if the DLT_PATTERN next and just skip the storage header if so
+/// check if the storage magic is next and just skip the storage header if so
 /// returns a slice where the storage header was removed
-pub(crate) fn skip_storage_header(input: &[u8]) -> Result<(&[u8], u64), DltParseError> {
-    let (i, (_, _, _)): (&[u8], _) = tuple((tag("DLT"), tag(&[0x01]), take(12usize)))(input)?;
+pub(crate) fn skip_storage_header(
+    input: &[u8],
+    storage_magic: [u8; 4],
+) -> Result<(&[u8], u64), DltParseError> {
+    let (i, (_, _)): (&[u8], _) = tuple((tag(&storage_magic[..]), take(12usize)))(input)?;
     if input.len() - i.len() == STORAGE_HEADER_LENGTH as usize {
         Ok((i, STORAGE_HEADER_LENGTH))
     } else {
@@ -994,17 +3004,56 @@ pub fn dlt_statistic_row_info<'a, T>(
     index: Option<usize>,
     with_storage_header: bool,
     update_channel: Option<&cc::Sender<IndexingResults<T>>>,
+) -> Result<(&'a [u8], StatisticRowInfo), DltParseError> {
+    let storage_header_mode = if with_storage_header {
+        StorageHeaderMode::Required
+    } else {
+        StorageHeaderMode::Absent
+    };
+    dlt_statistic_row_info_with_options(
+        input,
+        index,
+        ParseOptions::new(storage_header_mode),
+        update_channel,
+    )
+}
+
+/// like `dlt_statistic_row_info` with the storage header mode, magic and size
+/// limit of `options`
+pub fn dlt_statistic_row_info_with_options<'a, T>(
+    input: &'a [u8],
+    index: Option<usize>,
+    options: ParseOptions,
+    update_channel: Option<&cc::Sender<IndexingResults<T>>>,
 ) -> Result<(&'a [u8], StatisticRowInfo), DltParseError> {
     let update_channel_ref = update_channel;
+    let with_storage_header = options
+        .storage_header_mode
+        .applies_to(input, options.storage_magic);
     // let (after_storage_header, _) = skip_till_after_next_storage_header(input)?;
     let (after_storage_header, _) = if with_storage_header {
-        skip_till_after_next_storage_header(input)?
+        skip_till_after_next_storage_header(input, options.storage_magic)?
     } else {
         (input, 0)
     };
     let (after_storage_and_normal_header, header) = dlt_standard_header(after_storage_header)?;
+    check_version(header.header_type_byte())?;
+    check_message_size(
+        if with_storage_header {
+            STORAGE_HEADER_LENGTH as usize
+        } else {
+            0
+        },
+        header.overall_length(),
+        options.max_message_size,
+    )?;
 
-    let payload_length = match validated_payload_length(&header, index, update_channel_ref) {
+    let payload_length = match validated_payload_length(
+        header.overall_length(),
+        header.header_type_byte(),
+        index,
+        update_channel_ref,
+    ) {
         Some(length) => length,
         None => {
             return Ok((
@@ -1051,15 +3100,77 @@ pub fn dlt_statistic_row_info<'a, T>(
     ))
 }
 
-#[derive(Error, Debug, PartialEq)]
+/// `Display` is implemented by hand (and `std::error::Error` only with the `std`
+/// feature) so the error type does not depend on `std`
+#[derive(Debug, PartialEq)]
 pub enum DltParseError {
-    #[error("parsing stopped, cannot continue: {}", cause)]
-    Unrecoverable { cause: String },
-    #[error("parsing error, try to continue: {}", reason)]
-    ParsingHickup { reason: String },
-    #[error("parsing could not complete: {:?}", needed)]
-    IncompleteParse { needed: Option<usize> },
+    Unrecoverable {
+        cause: String,
+    },
+    ParsingHickup {
+        reason: String,
+    },
+    /// the input ends within a message, parsing can be retried with more data
+    ///
+    /// `needed` is the number of bytes the parser asked for, at least 1
+    IncompleteData {
+        needed: usize,
+    },
+    /// there is no storage header where one was expected
+    InvalidStorageHeader,
+    /// the standard header announces a protocol version we cannot parse
+    UnsupportedVersion(u8),
+    /// an argument of a verbose message has a type info we do not know
+    UnknownTypeInfo(u32),
+    /// the length of a message exceeds the configured maximum
+    ImplausibleLength {
+        length: usize,
+        max: usize,
+    },
+    /// the name or unit of a variable is not valid UTF-8
+    Utf8(str::Utf8Error),
 }
+impl fmt::Display for DltParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DltParseError::Unrecoverable { cause } => {
+                write!(f, "parsing stopped, cannot continue: {}", cause)
+            }
+            DltParseError::ParsingHickup { reason } => {
+                write!(f, "parsing error, try to continue: {}", reason)
+            }
+            DltParseError::IncompleteData { needed } => {
+                write!(f, "incomplete data, needed {} more bytes", needed)
+            }
+            DltParseError::InvalidStorageHeader => write!(f, "no valid storage header found"),
+            DltParseError::UnsupportedVersion(version) => {
+                write!(f, "unsupported dlt protocol version {}", version)
+            }
+            DltParseError::UnknownTypeInfo(info) => write!(f, "unknown type info 0x{:08X}", info),
+            DltParseError::ImplausibleLength { length, max } => write!(
+                f,
+                "implausible message length {} (at most {} bytes allowed)",
+                length, max
+            ),
+            DltParseError::Utf8(e) => write!(f, "invalid utf-8: {}", e),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for DltParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DltParseError::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<str::Utf8Error> for DltParseError {
+    fn from(err: str::Utf8Error) -> DltParseError {
+        DltParseError::Utf8(err)
+    }
+}
+#[cfg(feature = "std")]
 impl From<std::io::Error> for DltParseError {
     fn from(err: std::io::Error) -> DltParseError {
         DltParseError::Unrecoverable {
@@ -1067,6 +3178,7 @@ impl From<std::io::Error> for DltParseError {
         }
     }
 }
+#[cfg(feature = "std")]
 impl From<pcap_parser::PcapError> for DltParseError {
     fn from(err: pcap_parser::PcapError) -> DltParseError {
         DltParseError::Unrecoverable {
@@ -1086,10 +3198,10 @@ impl From<nom::Err<(&[u8], nom::error::ErrorKind)>> for DltParseError {
         match err {
             nom::Err::Incomplete(n) => {
                 let needed = match n {
-                    nom::Needed::Size(s) => Some(s),
-                    nom::Needed::Unknown => None,
+                    nom::Needed::Size(s) => s.max(1),
+                    nom::Needed::Unknown => 1,
                 };
-                DltParseError::IncompleteParse { needed }
+                DltParseError::IncompleteData { needed }
             }
             nom::Err::Error((input, kind)) => DltParseError::ParsingHickup {
                 reason: format!("{:?} ({} bytes left in input)", kind, input.len()),
@@ -1101,6 +3213,7 @@ impl From<nom::Err<(&[u8], nom::error::ErrorKind)>> for DltParseError {
     }
 }
 
+#[cfg(feature = "std")]
 #[derive(Serialize, Debug, Default)]
 struct LevelDistribution {
     non_log: usize,
@@ -1112,6 +3225,7 @@ struct LevelDistribution {
     log_verbose: usize,
     log_invalid: usize,
 }
+#[cfg(feature = "std")]
 impl LevelDistribution {
     pub fn new(level: Option<LogLevel>) -> LevelDistribution {
         let all_zero = Default::default();
@@ -1151,8 +3265,10 @@ impl LevelDistribution {
         }
     }
 }
+#[cfg(feature = "std")]
 type IdMap = FxHashMap<String, LevelDistribution>;
 
+#[cfg(feature = "std")]
 fn add_for_level(level: Option<LogLevel>, ids: &mut IdMap, id: String) {
     if let Some(n) = ids.get_mut(&id) {
         match level {
@@ -1209,6 +3325,7 @@ fn add_for_level(level: Option<LogLevel>, ids: &mut IdMap, id: String) {
         ids.insert(id, LevelDistribution::new(level));
     }
 }
+#[cfg(feature = "std")]
 #[derive(Serialize, Debug)]
 pub struct StatisticInfo {
     app_ids: Vec<(String, LevelDistribution)>,
@@ -1217,7 +3334,9 @@ pub struct StatisticInfo {
     contained_non_verbose: bool,
 }
 
+#[cfg(feature = "std")]
 pub type StatisticsResults = std::result::Result<IndexingProgress<StatisticInfo>, Notification>;
+#[cfg(feature = "std")]
 pub fn get_dlt_file_info(
     in_file: &std::path::PathBuf,
     update_channel: &cc::Sender<StatisticsResults>,
@@ -1287,12 +3406,17 @@ pub fn get_dlt_file_info(
                 // we couldn't parse the message. try to skip it and find the next.
                 debug!("stats...try to skip and continue parsing: {}", e);
                 match e {
-                    DltParseError::ParsingHickup { reason } => {
+                    DltParseError::ParsingHickup { .. }
+                    | DltParseError::InvalidStorageHeader
+                    | DltParseError::UnsupportedVersion(_)
+                    | DltParseError::UnknownTypeInfo(_)
+                    | DltParseError::ImplausibleLength { .. }
+                    | DltParseError::Utf8(_) => {
                         // we couldn't parse the message. try to skip it and find the next.
                         reader.consume(4); // at least skip the magic DLT pattern
                         debug!(
                             "error parsing 1 dlt message, try to continue parsing: {}",
-                            reason
+                            e
                         );
                     }
                     DltParseError::Unrecoverable { cause } => {
@@ -1304,14 +3428,11 @@ pub fn get_dlt_file_info(
                         }));
                         break;
                     }
-                    DltParseError::IncompleteParse { needed } => {
-                        warn!(
-                            "cannot continue parsing, parse was incomplete: {:?}",
-                            needed
-                        );
+                    DltParseError::IncompleteData { needed } => {
+                        warn!("cannot continue parsing, parse was incomplete: {}", needed);
                         let _ = update_channel.send(Err(Notification {
                             severity: Severity::ERROR,
-                            content: format!("parse was incomplete: {:?}", needed),
+                            content: format!("parse was incomplete: {}", needed),
                             line: None,
                         }));
                         break;
@@ -1356,6 +3477,7 @@ pub struct StatisticRowInfo {
     level: Option<LogLevel>,
     verbose: bool,
 }
+#[cfg(feature = "std")]
 fn read_one_dlt_message_info<T: Read>(
     reader: &mut ReduxReader<T, MinBuffered>,
     index: Option<usize>,
@@ -1377,3 +3499,290 @@ fn read_one_dlt_message_info<T: Read>(
         }),
     }
 }
+
+#[cfg(feature = "std")]
+/// message counts of a dlt trace as collected by a `StatisticsCollector`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DltStatistics {
+    /// number of messages
+    pub total: usize,
+    /// number of messages per ECU-ID, for messages without one in the standard
+    /// header the ECU-ID of the storage header (messages with neither are not counted)
+    pub per_ecu: HashMap<String, usize>,
+    /// number of messages per App-ID (messages without extended header are not counted)
+    pub per_app: HashMap<String, usize>,
+    /// number of messages per Context-ID for each App-ID, a Context-ID is only
+    /// unique within its application (messages without extended header are not counted)
+    pub per_context: HashMap<String, HashMap<String, usize>>,
+    /// number of log messages per log level
+    pub per_level: HashMap<LogLevel, usize>,
+    /// smallest and biggest timestamp found in the standard headers
+    pub time_range: Option<(u32, u32)>,
+}
+
+#[cfg(feature = "std")]
+/// collects `DltStatistics` from the headers of messages
+///
+/// the headers can either be fed one by one or be scanned from raw bytes.
+/// Scanning only looks at the standard and extended headers and skips the
+/// payload using the length field, so it is a lot faster than parsing the messages.
+#[derive(Debug, Default)]
+pub struct StatisticsCollector {
+    statistics: DltStatistics,
+}
+
+#[cfg(feature = "std")]
+impl StatisticsCollector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// count a message with the given headers
+    pub fn add_headers(
+        &mut self,
+        storage_header: Option<&StorageHeader>,
+        header: &StandardHeader,
+        extended_header: Option<&ExtendedHeader>,
+    ) {
+        let level = extended_header.and_then(|h| match h.message_type {
+            MessageType::Log(level) => Some(level),
+            _ => None,
+        });
+        self.add(
+            header
+                .ecu_id
+                .as_deref()
+                .or_else(|| storage_header.map(|h| h.ecu_id.as_str())),
+            extended_header.map(|h| (h.application_id.as_str(), h.context_id.as_str())),
+            level,
+            header.timestamp,
+        );
+    }
+
+    /// count all messages in `input` by looking only at their headers
+    ///
+    /// with `StorageHeaderMode::Required` anything that is not a plausible message
+    /// is skipped, otherwise an invalid header is returned as error.
+    /// Returns the number of bytes that were scanned, which is less than the input
+    /// length if the input ends with an incomplete message.
+    pub fn scan(
+        &mut self,
+        input: &[u8],
+        storage_header_mode: StorageHeaderMode,
+    ) -> Result<usize, DltParseError> {
+        self.scan_with_options(input, ParseOptions::new(storage_header_mode))
+    }
+
+    /// like `scan` with the storage header mode, magic and size limit of `options`
+    pub fn scan_with_options(
+        &mut self,
+        input: &[u8],
+        options: ParseOptions,
+    ) -> Result<usize, DltParseError> {
+        scan_frames(input, options, |storage_header, headers| {
+            self.add_raw_headers(storage_header, headers, options.storage_magic)
+        })
+    }
+
+    pub fn statistics(&self) -> &DltStatistics {
+        &self.statistics
+    }
+
+    pub fn into_statistics(self) -> DltStatistics {
+        self.statistics
+    }
+
+    fn add_raw_headers(
+        &mut self,
+        storage_header: Option<&[u8]>,
+        input: &[u8],
+        storage_magic: [u8; 4],
+    ) -> Result<(), DltParseError> {
+        let (after_standard_header, header) = dlt_standard_header_fields(input)?;
+        check_version(header.header_type_byte)?;
+        let extended_header = if header.header_type_byte & WITH_EXTENDED_HEADER_FLAG != 0 {
+            Some(dlt_extended_header_fields(after_standard_header)?.1)
+        } else {
+            None
+        };
+        let level =
+            extended_header
+                .as_ref()
+                .and_then(|h| match MessageType::try_from(h.message_info) {
+                    Ok(MessageType::Log(level)) => Some(level),
+                    _ => None,
+                });
+        let storage_ecu_id = || {
+            storage_header
+                .and_then(|h| dlt_storage_header_ref(h, storage_magic).ok())
+                .map(|(_, h)| h.ecu_id)
+        };
+        self.add(
+            header.ecu_id.or_else(storage_ecu_id),
+            extended_header.map(|h| (h.app_id, h.context_id)),
+            level,
+            header.timestamp,
+        );
+        Ok(())
+    }
+
+    fn add(
+        &mut self,
+        ecu_id: Option<&str>,
+        app_id_context_id: Option<(&str, &str)>,
+        level: Option<LogLevel>,
+        timestamp: Option<u32>,
+    ) {
+        let stats = &mut self.statistics;
+        stats.total += 1;
+        if let Some(ecu_id) = ecu_id {
+            count_id(&mut stats.per_ecu, ecu_id);
+        }
+        if let Some((app_id, context_id)) = app_id_context_id {
+            count_id(&mut stats.per_app, app_id);
+            match stats.per_context.get_mut(app_id) {
+                Some(app_contexts) => count_id(app_contexts, context_id),
+                None => {
+                    let mut app_contexts = HashMap::new();
+                    app_contexts.insert(context_id.to_string(), 1);
+                    stats.per_context.insert(app_id.to_string(), app_contexts);
+                }
+            }
+        }
+        if let Some(level) = level {
+            *stats.per_level.entry(level).or_insert(0) += 1;
+        }
+        if let Some(t) = timestamp {
+            stats.time_range = Some(match stats.time_range {
+                Some((min, max)) => (min.min(t), max.max(t)),
+                None => (t, t),
+            });
+        }
+    }
+}
+
+/// hand the storage header (if there is one) and the headers of every message in
+/// `input` to `add`, the payloads are skipped using the length field
+///
+/// see `StatisticsCollector::scan` for how invalid data and the end of the
+/// input are handled
+fn scan_frames<F>(input: &[u8], options: ParseOptions, mut add: F) -> Result<usize, DltParseError>
+where
+    F: FnMut(Option<&[u8]>, &[u8]) -> Result<(), DltParseError>,
+{
+    let storage_header_mode = options.storage_header_mode;
+    let resync = storage_header_mode == StorageHeaderMode::Required;
+    let mut frames = Frames::new(input, FrameWalker::new(options));
+    // start of a cut off message that might be the end of the input
+    let mut incomplete_at = None;
+    while let Some((offset, frame)) = frames.next() {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(DltParseError::IncompleteData { .. }) if resync => {
+                // a corrupt length field might point beyond the end, so keep looking
+                incomplete_at = incomplete_at.or(Some(offset));
+                continue;
+            }
+            Err(DltParseError::IncompleteData { .. }) => return Ok(offset),
+            Err(_) if resync => continue,
+            Err(e) => return Err(e),
+        };
+        incomplete_at = None;
+        let (storage_header, headers) =
+            if storage_header_mode.applies_to(frame, options.storage_magic) {
+                let (storage_header, headers) = frame.split_at(STORAGE_HEADER_LENGTH as usize);
+                (Some(storage_header), headers)
+            } else {
+                (None, frame)
+            };
+        match add(storage_header, headers) {
+            Ok(()) => (),
+            Err(_) if resync => frames.reject(offset, frame),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(incomplete_at.unwrap_or(input.len()))
+}
+
+/// the distinct ids that occur in a dlt trace, see `inventory`
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct DltInventory {
+    /// ECU-IDs of the standard headers, for messages without one the ECU-ID of
+    /// the storage header
+    pub ecus: BTreeSet<String>,
+    /// App-IDs of the messages with extended header
+    pub apps: BTreeSet<String>,
+    /// Context-IDs together with the App-ID they were used with
+    pub contexts: BTreeSet<(String, String)>,
+}
+
+/// collect the ECU-IDs, App-IDs and Context-IDs of all messages (with storage
+/// headers) in `input`
+///
+/// like `StatisticsCollector::scan` only the headers are looked at and anything
+/// that is not a plausible message is skipped, so this is fast enough for huge
+/// files. Messages without extended header add no App-ID and Context-ID.
+pub fn inventory(input: &[u8]) -> DltInventory {
+    inventory_with_options(input, ParseOptions::new(StorageHeaderMode::Required))
+}
+
+/// like `inventory` with the storage magic and size limit of `options`, the
+/// storage header mode of `options` is not used
+pub fn inventory_with_options(input: &[u8], options: ParseOptions) -> DltInventory {
+    let options = options.with_storage_header();
+    let mut ecus = BTreeSet::new();
+    // contexts per app, so ids that were seen before are not allocated again
+    let mut contexts: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let _ = scan_frames(input, options, |storage_header, headers| {
+        let (after_standard_header, header) = dlt_standard_header_fields(headers)?;
+        check_version(header.header_type_byte)?;
+        let storage_ecu_id = || {
+            storage_header
+                .and_then(|h| dlt_storage_header_ref(h, options.storage_magic).ok())
+                .map(|(_, h)| h.ecu_id)
+        };
+        if let Some(ecu_id) = header.ecu_id.or_else(storage_ecu_id) {
+            if !ecus.contains(ecu_id) {
+                ecus.insert(ecu_id.to_string());
+            }
+        }
+        if header.header_type_byte & WITH_EXTENDED_HEADER_FLAG != 0 {
+            let (_, ext) = dlt_extended_header_fields(after_standard_header)?;
+            match contexts.get_mut(ext.app_id) {
+                Some(app_contexts) => {
+                    if !app_contexts.contains(ext.context_id) {
+                        app_contexts.insert(ext.context_id.to_string());
+                    }
+                }
+                None => {
+                    let mut app_contexts = BTreeSet::new();
+                    app_contexts.insert(ext.context_id.to_string());
+                    contexts.insert(ext.app_id.to_string(), app_contexts);
+                }
+            }
+        }
+        Ok(())
+    });
+    DltInventory {
+        ecus,
+        apps: contexts.keys().cloned().collect(),
+        contexts: contexts
+            .into_iter()
+            .flat_map(|(app_id, app_contexts)| {
+                app_contexts
+                    .into_iter()
+                    .map(move |context_id| (app_id.clone(), context_id))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(feature = "std")]
+fn count_id(counts: &mut HashMap<String, usize>, id: &str) {
+    match counts.get_mut(id) {
+        Some(n) => *n += 1,
+        None => {
+            counts.insert(id.to_string(), 1);
+        }
+    }
+}
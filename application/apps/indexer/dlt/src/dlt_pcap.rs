@@ -23,8 +23,12 @@ pub fn convert_to_dlt_file(
     update_channel: cc::Sender<ChunkResults>,
     fibex: Option<Rc<FibexMetadata>>,
 ) -> Result<(), DltParseError> {
-    let filter_config: Option<filtering::ProcessedDltFilterConfig> =
-        dlt_filter.map(filtering::process_filter_config);
+    let filter_config: Option<filtering::ProcessedDltFilterConfig> = dlt_filter
+        .map(filtering::process_filter_config)
+        .transpose()
+        .map_err(|e| DltParseError::Unrecoverable {
+            cause: format!("invalid filter: {}", e),
+        })?;
     let ending = &pcap_path
         .extension()
         .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "could not get extension"))?;
@@ -142,7 +146,7 @@ impl futures::Stream for PcapMessageProducer {
                                 index,
                                 Some(&update_channel),
                                 fibex,
-                                false,
+                                StorageHeaderMode::Absent,
                             ) {
                                 Ok((_, ParsedMessage::Item(m))) => {
                                     let msg_with_storage_header = m.add_storage_header(Some(
@@ -158,17 +162,13 @@ impl futures::Stream for PcapMessageProducer {
                                 Ok((_, ParsedMessage::Invalid)) => {
                                     futures::task::Poll::Ready(Some(Ok(MessageStreamItem::Skipped)))
                                 }
-                                Err(DltParseError::IncompleteParse { needed }) => {
-                                    let needed_s = match needed {
-                                        Some(s) => format!("{}", s),
-                                        None => "unknown".to_string(),
-                                    };
+                                Err(DltParseError::IncompleteData { needed }) => {
                                     futures::task::Poll::Ready(Some(Err(
                                           DltParseError::Unrecoverable {
                                               cause: format!(
                                                   "read_one_dlt_message: imcomplete parsing error for dlt messages: (bytes left: {}, but needed: {})",
                                                   value.payload.len(),
-                                                  needed_s)
+                                                  needed)
                                           },
                                       )))
                                 }
@@ -281,19 +281,28 @@ pub fn index_from_pcap(
                         line: None,
                     }));
                 }
+                Event::Msg(Err(e @ DltParseError::InvalidStorageHeader))
+                | Event::Msg(Err(e @ DltParseError::UnsupportedVersion(_)))
+                | Event::Msg(Err(e @ DltParseError::UnknownTypeInfo(_)))
+                | Event::Msg(Err(e @ DltParseError::ImplausibleLength { .. }))
+                | Event::Msg(Err(e @ DltParseError::Utf8(_))) => {
+                    warn!("parsing error in stream: {}", e);
+                    let _ = update_channel.send(Err(Notification {
+                        severity: Severity::WARNING,
+                        content: format!("parsing faild for one message: {}", e),
+                        line: None,
+                    }));
+                }
                 Event::Msg(Err(DltParseError::Unrecoverable { cause })) => {
                     warn!("Unrecoverable error in stream: {}", cause);
                     let _ = update_channel.send(Ok(IndexingProgress::Finished));
                     break;
                 }
-                Event::Msg(Err(DltParseError::IncompleteParse { needed })) => {
-                    warn!(
-                        "parse error in stream, was incomplete: (needed {:?})",
-                        needed
-                    );
+                Event::Msg(Err(DltParseError::IncompleteData { needed })) => {
+                    warn!("parse error in stream, was incomplete: (needed {})", needed);
                     let _ = update_channel.send(Err(Notification {
                         severity: Severity::WARNING,
-                        content: format!("parsing incomplete for one message: needed {:?}", needed),
+                        content: format!("parsing incomplete for one message: needed {}", needed),
                         line: None,
                     }));
                 }
@@ -314,16 +323,20 @@ pub fn create_index_and_mapping_dlt_from_pcap(
     trace!("create_index_and_mapping_dlt_from_pcap");
     match utils::next_line_nr(&config.out_path) {
         Ok(initial_line_nr) => {
-            let filter_config: Option<filtering::ProcessedDltFilterConfig> =
-                dlt_filter.map(filtering::process_filter_config);
-            match index_from_pcap(
-                config,
-                filter_config,
-                initial_line_nr,
-                update_channel.clone(),
-                shutdown_receiver,
-                fibex_metadata,
-            ) {
+            let indexed = match dlt_filter.map(filtering::process_filter_config).transpose() {
+                Ok(filter_config) => index_from_pcap(
+                    config,
+                    filter_config,
+                    initial_line_nr,
+                    update_channel.clone(),
+                    shutdown_receiver,
+                    fibex_metadata,
+                ),
+                Err(e) => Err(DltParseError::Unrecoverable {
+                    cause: format!("invalid filter: {}", e),
+                }),
+            };
+            match indexed {
                 Ok(()) => Ok(()),
                 Err(e) => {
                     let content = format!("{}", e);
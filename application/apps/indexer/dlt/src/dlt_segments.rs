@@ -0,0 +1,417 @@
+use crate::{
+    dlt::*,
+    dlt_parse::{raw_argument, DltParseError},
+};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    string::String,
+    vec,
+    vec::Vec,
+};
+
+/// first argument of the message that announces a segmented network trace
+pub const SEGMENT_START: &str = "NWST";
+/// first argument of a message with one segment of the data
+pub const SEGMENT_CHUNK: &str = "NWCH";
+/// first argument of the message that ends a segmented network trace
+pub const SEGMENT_END: &str = "NWEN";
+
+/// Output of the `SegmentReassembler`
+#[derive(Debug, PartialEq)]
+pub enum Reassembled {
+    /// a message that is not part of a known segmented sequence
+    Message(Message),
+    /// all segments of a sequence combined into one network trace message
+    Complete(Message),
+    /// a sequence that was given up, either because it timed out, its end
+    /// arrived without all segments, it was flushed or it was dropped to stay
+    /// below the buffer limit
+    Incomplete(IncompleteSequence),
+    /// a sequence with more data than fits into one DLT message
+    Oversized(OversizedSequence),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IncompleteSequence {
+    /// the network trace message with the segments that did arrive
+    pub message: Message,
+    /// sequence numbers of the segments that were not received
+    pub missing_segments: Vec<u16>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OversizedSequence {
+    /// the message that started the sequence
+    pub start: Message,
+    /// the network header of the start message
+    pub header: Vec<u8>,
+    /// the data of the segments that were received, in order
+    pub data: Vec<u8>,
+    /// sequence numbers of the segments that were not received
+    pub missing_segments: Vec<u16>,
+}
+
+/// default limit for the segment data of all open sequences
+pub const DEFAULT_MAX_BUFFERED_BYTES: usize = 16 * 1024 * 1024;
+
+/// ids from the headers and the stream handle: handles are only unique per application
+type SequenceKey = (Option<String>, String, String, u32);
+
+struct PendingSequence {
+    start: Message,
+    header: Vec<u8>,
+    segment_count: u16,
+    segments: BTreeMap<u16, Vec<u8>>,
+    started: usize,
+    last_seen: usize,
+}
+
+impl PendingSequence {
+    /// bytes buffered for the header and the segments
+    fn buffered(&self) -> usize {
+        self.header.len() + self.segments.values().map(Vec::len).sum::<usize>()
+    }
+
+    /// the start message with the header and the received data as raw arguments,
+    /// the same layout as a network trace that is not segmented
+    fn into_message(self) -> Reassembled {
+        let missing_segments: Vec<u16> = (0..self.segment_count)
+            .filter(|n| !self.segments.contains_key(n))
+            .collect();
+        let data: Vec<u8> = self.segments.into_values().flatten().collect();
+        // type info and length of both raw arguments
+        let payload_length = 2 * (4 + 2) + self.header.len() + data.len();
+        let headers_length =
+            calculate_all_headers_length(self.start.header.header_type_byte()) as usize;
+        if data.len() > u16::MAX as usize || headers_length + payload_length > u16::MAX as usize {
+            return Reassembled::Oversized(OversizedSequence {
+                start: self.start,
+                header: self.header,
+                data,
+                missing_segments,
+            });
+        }
+        let mut message = self.start;
+        message.payload = Payload2 {
+            payload_content: PayloadContent::Verbose(vec![
+                raw_argument(self.header),
+                raw_argument(data),
+            ]),
+        };
+        if let Some(ext) = message.extended_header.as_mut() {
+            ext.argument_count = 2;
+        }
+        message.header.payload_length = match message.header.endianness {
+            Endianness::Big => message.payload.as_bytes::<byteorder::BigEndian>().len(),
+            Endianness::Little => message.payload.as_bytes::<byteorder::LittleEndian>().len(),
+        } as u16;
+        if missing_segments.is_empty() {
+            Reassembled::Complete(message)
+        } else {
+            Reassembled::Incomplete(IncompleteSequence {
+                message,
+                missing_segments,
+            })
+        }
+    }
+}
+
+enum Segment<'a> {
+    Start {
+        handle: u32,
+        header: &'a [u8],
+        segment_count: u16,
+    },
+    Chunk {
+        handle: u32,
+        sequence: u16,
+        data: &'a [u8],
+    },
+    End {
+        handle: u32,
+    },
+}
+
+/// recognize the messages of a segmented network trace
+///
+/// * start: `"NWST"`, handle (u32), header (raw), payload size (u32), segment count (u16), segment size (u16)
+/// * chunk: `"NWCH"`, handle (u32), sequence number (u16), data (raw)
+/// * end: `"NWEN"`, handle (u32)
+fn segment(msg: &Message) -> Option<Segment<'_>> {
+    if !matches!(msg.message_type(), Some(MessageType::NetworkTrace(_))) {
+        return None;
+    }
+    let args = match &msg.payload.payload_content {
+        PayloadContent::Verbose(args) => args,
+        _ => return None,
+    };
+    let values: Vec<&Value> = args.iter().map(|a| &a.value).collect();
+    match values.as_slice() {
+        [Value::StringVal(id), Value::U32(handle), Value::Raw(header), Value::U32(_), Value::U16(segment_count), Value::U16(_)]
+            if id == SEGMENT_START =>
+        {
+            Some(Segment::Start {
+                handle: *handle,
+                header,
+                segment_count: *segment_count,
+            })
+        }
+        [Value::StringVal(id), Value::U32(handle), Value::U16(sequence), Value::Raw(data)]
+            if id == SEGMENT_CHUNK =>
+        {
+            Some(Segment::Chunk {
+                handle: *handle,
+                sequence: *sequence,
+                data,
+            })
+        }
+        [Value::StringVal(id), Value::U32(handle)] if id == SEGMENT_END => {
+            Some(Segment::End { handle: *handle })
+        }
+        _ => None,
+    }
+}
+
+fn sequence_key(msg: &Message, handle: u32) -> SequenceKey {
+    let (app_id, context_id) = match &msg.extended_header {
+        Some(ext) => (ext.application_id.clone(), ext.context_id.clone()),
+        None => (String::new(), String::new()),
+    };
+    (msg.header.ecu_id.clone(), app_id, context_id, handle)
+}
+
+/// Combines the messages of segmented network traces into one message
+///
+/// An ECU splits a big network trace into a start message, numbered data
+/// segments and an end message. The segments are collected by the stream
+/// handle (per ECU, application and context) and when the end message
+/// arrives a single network trace message is emitted instead. It has the
+/// headers of the start message and the network header and the combined data
+/// as raw arguments.
+/// Segments of unknown sequences, segments with a sequence number beyond the
+/// announced segment count and all other messages are passed on as they are. Sequences that are still open at the end of the input have to be
+/// collected with `flush`. Sequences with more data than a DLT message can hold
+/// are returned as `Reassembled::Oversized`.
+/// If the buffered data of all open sequences exceeds the limit (see
+/// `with_max_buffered_bytes`) the oldest sequences are given up.
+pub struct SegmentReassembler {
+    pending: BTreeMap<SequenceKey, PendingSequence>,
+    timeout: Option<usize>,
+    max_buffered_bytes: usize,
+    buffered: usize,
+    index: usize,
+}
+
+impl Default for SegmentReassembler {
+    fn default() -> Self {
+        SegmentReassembler::new()
+    }
+}
+
+impl SegmentReassembler {
+    pub fn new() -> Self {
+        SegmentReassembler {
+            pending: BTreeMap::new(),
+            timeout: None,
+            max_buffered_bytes: DEFAULT_MAX_BUFFERED_BYTES,
+            buffered: 0,
+            index: 0,
+        }
+    }
+
+    /// give up a sequence if none of its messages was seen within the last
+    /// `messages` messages
+    pub fn with_timeout(mut self, messages: usize) -> Self {
+        self.timeout = Some(messages);
+        self
+    }
+
+    /// limit for the data of all open sequences (default: `DEFAULT_MAX_BUFFERED_BYTES`)
+    pub fn with_max_buffered_bytes(mut self, bytes: usize) -> Self {
+        self.max_buffered_bytes = bytes;
+        self
+    }
+
+    /// number of sequences that are waiting for more segments
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// bytes that are buffered for the open sequences
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered
+    }
+
+    /// process the next message
+    ///
+    /// returns what is ready after this message, which is nothing if the
+    /// message was buffered as part of a sequence
+    pub fn push(&mut self, msg: Message) -> Vec<Reassembled> {
+        self.index += 1;
+        let mut ready = self.expire();
+        let index = self.index;
+        match segment(&msg) {
+            Some(Segment::Start {
+                handle,
+                header,
+                segment_count,
+            }) => {
+                let sequence = PendingSequence {
+                    header: header.to_vec(),
+                    segment_count,
+                    segments: BTreeMap::new(),
+                    started: index,
+                    last_seen: index,
+                    start: msg.clone(),
+                };
+                self.buffered += sequence.buffered();
+                if let Some(previous) = self.pending.insert(sequence_key(&msg, handle), sequence) {
+                    // the handle was reused before the previous sequence ended
+                    self.buffered -= previous.buffered();
+                    ready.push(previous.into_message());
+                }
+            }
+            Some(Segment::Chunk {
+                handle,
+                sequence,
+                data,
+            }) => match self.pending.get_mut(&sequence_key(&msg, handle)) {
+                Some(pending) if sequence < pending.segment_count => {
+                    self.buffered += data.len();
+                    if let Some(replaced) = pending.segments.insert(sequence, data.to_vec()) {
+                        self.buffered -= replaced.len();
+                    }
+                    pending.last_seen = index;
+                }
+                // unknown sequence or a sequence number beyond the announced count
+                _ => ready.push(Reassembled::Message(msg)),
+            },
+            Some(Segment::End { handle }) => match self.pending.remove(&sequence_key(&msg, handle))
+            {
+                Some(pending) => {
+                    self.buffered -= pending.buffered();
+                    ready.push(pending.into_message());
+                }
+                None => ready.push(Reassembled::Message(msg)),
+            },
+            None => ready.push(Reassembled::Message(msg)),
+        }
+        ready.extend(self.limit_buffered());
+        ready
+    }
+
+    /// give up all open sequences, e.g. at the end of the input
+    ///
+    /// they are returned in the order in which they started
+    pub fn flush(&mut self) -> Vec<Reassembled> {
+        let mut open: Vec<PendingSequence> =
+            core::mem::take(&mut self.pending).into_values().collect();
+        open.sort_by_key(|p| p.started);
+        self.buffered = 0;
+        open.into_iter()
+            .map(PendingSequence::into_message)
+            .collect()
+    }
+
+    fn expire(&mut self) -> Vec<Reassembled> {
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return vec![],
+        };
+        let index = self.index;
+        let expired: Vec<SequenceKey> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| index - p.last_seen > timeout)
+            .map(|(k, _)| k.clone())
+            .collect();
+        self.give_up(expired)
+    }
+
+    /// give up the oldest sequences until the buffered data is within the limit
+    fn limit_buffered(&mut self) -> Vec<Reassembled> {
+        if self.buffered <= self.max_buffered_bytes {
+            return vec![];
+        }
+        let mut by_age: Vec<(usize, usize, &SequenceKey)> = self
+            .pending
+            .iter()
+            .map(|(k, p)| (p.started, p.buffered(), k))
+            .collect();
+        by_age.sort_by_key(|(started, _, _)| *started);
+        let mut remaining = self.buffered;
+        let mut dropped = vec![];
+        for (_, buffered, key) in by_age {
+            if remaining <= self.max_buffered_bytes {
+                break;
+            }
+            remaining -= buffered;
+            dropped.push(key.clone());
+        }
+        self.give_up(dropped)
+    }
+
+    fn give_up(&mut self, keys: Vec<SequenceKey>) -> Vec<Reassembled> {
+        let mut sequences: Vec<PendingSequence> =
+            keys.iter().filter_map(|k| self.pending.remove(k)).collect();
+        sequences.sort_by_key(|p| p.started);
+        for sequence in &sequences {
+            self.buffered -= sequence.buffered();
+        }
+        sequences
+            .into_iter()
+            .map(PendingSequence::into_message)
+            .collect()
+    }
+}
+
+/// Iterator over parsed messages with segmented network traces combined
+///
+/// see `SegmentReassembler`, sequences that are still open when the messages
+/// run out are returned as `Reassembled::Incomplete` at the end
+pub struct ReassembledMessages<I> {
+    messages: I,
+    reassembler: SegmentReassembler,
+    ready: VecDeque<Reassembled>,
+    flushed: bool,
+}
+
+pub fn reassemble_segments<I>(
+    messages: I,
+    reassembler: SegmentReassembler,
+) -> ReassembledMessages<I>
+where
+    I: Iterator<Item = Result<Message, DltParseError>>,
+{
+    ReassembledMessages {
+        messages,
+        reassembler,
+        ready: VecDeque::new(),
+        flushed: false,
+    }
+}
+
+impl<I> Iterator for ReassembledMessages<I>
+where
+    I: Iterator<Item = Result<Message, DltParseError>>,
+{
+    type Item = Result<Reassembled, DltParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(reassembled) = self.ready.pop_front() {
+                return Some(Ok(reassembled));
+            }
+            if self.flushed {
+                return None;
+            }
+            match self.messages.next() {
+                Some(Ok(msg)) => self.ready.extend(self.reassembler.push(msg)),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.ready.extend(self.reassembler.flush());
+                    self.flushed = true;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{
+    dlt::Message,
+    dlt_parse::{
+        dlt_message_with_options, FrameStep, FrameWalker, ParseOptions, ParsedMessage, PayloadMode,
+        Progress, StorageHeaderMode,
+    },
+    fibex::FibexMetadata,
+    filtering,
+};
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+    rc::Rc,
+};
+
+/// A transport that delivers DLT data in chunks of any size
+///
+/// Implement this for a custom transport to decode its data with
+/// `DltStreamParser::messages`. An empty chunk means that the source is exhausted.
+pub trait DltSource {
+    /// the next bytes of the stream, valid until the next call
+    fn next_chunk(&mut self) -> io::Result<&[u8]>;
+}
+
+/// all bytes are delivered in one chunk
+impl<'a> DltSource for &'a [u8] {
+    fn next_chunk(&mut self) -> io::Result<&[u8]> {
+        let chunk: &'a [u8] = self;
+        *self = &[];
+        Ok(chunk)
+    }
+}
+
+/// A `DltSource` for anything that implements `BufRead` (files, sockets, ...)
+///
+/// the chunks are the buffer contents of the reader
+pub struct BufReadSource<R> {
+    reader: R,
+    handed_out: usize,
+}
+
+impl<R: BufRead> BufReadSource<R> {
+    pub fn new(reader: R) -> Self {
+        BufReadSource {
+            reader,
+            handed_out: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> DltSource for BufReadSource<R> {
+    fn next_chunk(&mut self) -> io::Result<&[u8]> {
+        self.reader.consume(self.handed_out);
+        self.handed_out = 0;
+        loop {
+            match self.reader.fill_buf() {
+                Ok(_) => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+        // returning the buffer from within the loop does not pass the borrow checker
+        let chunk = self.reader.fill_buf()?;
+        self.handed_out = chunk.len();
+        Ok(chunk)
+    }
+}
+
+/// Result of feeding a chunk of bytes into the `DltStreamParser`
+#[derive(Debug, PartialEq)]
+pub struct StreamParseResult {
+    /// all messages that could be completed with this chunk
+    pub messages: Vec<Message>,
+    /// number of stream bytes that were used up in this call, either by messages
+    /// (including filtered or invalid ones) or by skipped bytes that could not be parsed
+    pub consumed: usize,
+    /// part of `consumed` that was skipped because it could not be parsed
+    pub discarded: usize,
+    /// number of bytes that are kept back because they belong to an incomplete message
+    pub buffered: usize,
+}
+
+/// Parser for DLT data that arrives in arbitrary chunks (e.g. from a socket)
+///
+/// The incomplete tail of a chunk is kept until the next chunk arrives so
+/// messages that straddle chunk borders are decoded as soon as they are complete.
+/// How many bytes a message needs is determined from the length field of the
+/// standard header, only complete messages are handed to the parser.
+pub struct DltStreamParser<'a> {
+    buffer: Vec<u8>,
+    walker: FrameWalker,
+    filter_config: Option<filtering::ProcessedDltFilterConfig>,
+    fibex_metadata: Option<Rc<FibexMetadata>>,
+    index: usize,
+    /// stream bytes used up by messages or skipped so far
+    processed: u64,
+    progress: Option<Progress<'a>>,
+}
+
+impl<'a> DltStreamParser<'a> {
+    pub fn new(
+        storage_header_mode: StorageHeaderMode,
+        filter_config: Option<filtering::ProcessedDltFilterConfig>,
+        fibex_metadata: Option<Rc<FibexMetadata>>,
+    ) -> Self {
+        DltStreamParser {
+            buffer: Vec::new(),
+            walker: FrameWalker::new(ParseOptions::new(storage_header_mode)),
+            filter_config,
+            fibex_metadata,
+            index: 0,
+            processed: 0,
+            progress: None,
+        }
+    }
+
+    /// skip messages that claim to be bigger than `max_message_size` bytes
+    /// instead of waiting for their data
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.walker.options.max_message_size = max_message_size;
+        self
+    }
+
+    /// expect storage headers that start with `storage_magic` instead of `DLT_STORAGE_MAGIC`
+    pub fn with_storage_magic(mut self, storage_magic: [u8; 4]) -> Self {
+        self.walker.options.storage_magic = storage_magic;
+        self
+    }
+
+    /// decode the payload only as far as `payload_mode` says
+    pub fn with_payload_mode(mut self, payload_mode: PayloadMode) -> Self {
+        self.walker.options.payload_mode = payload_mode;
+        self
+    }
+
+    /// keep arguments that cannot be decoded as raw data, see `ParseOptions::lenient`
+    pub fn with_lenient_arguments(mut self, lenient: bool) -> Self {
+        self.walker.options.lenient = lenient;
+        self
+    }
+
+    /// call `callback` with the number of used up stream bytes and `total`
+    /// while parsing, see `Messages::with_progress`
+    ///
+    /// `total` (e.g. the size of a file that is streamed) is only used for
+    /// reporting, the end is reported once `total` bytes are used up or the
+    /// source of `messages` is exhausted
+    pub fn with_progress<F: FnMut(u64, u64) + 'a>(mut self, total: u64, callback: F) -> Self {
+        self.progress = Some(Progress::new(total, callback));
+        self
+    }
+
+    /// number of bytes held back for the next call to `feed`
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// decode all messages that `source` delivers
+    ///
+    /// invalid data is skipped like with `feed`. An incomplete message at the
+    /// end of the source is dropped, use `SourceMessages::parser` to check
+    /// for buffered bytes
+    pub fn messages<S: DltSource>(self, source: S) -> SourceMessages<'a, S> {
+        SourceMessages {
+            parser: self,
+            source,
+            ready: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    /// add the next chunk of the stream and decode all messages that are complete
+    pub fn feed(&mut self, bytes: &[u8]) -> StreamParseResult {
+        self.buffer.extend_from_slice(bytes);
+        let buffer = std::mem::take(&mut self.buffer);
+        let mut messages = vec![];
+        let mut consumed = 0usize;
+        let mut discarded = 0usize;
+        loop {
+            match self.next_frame(&buffer[consumed..]) {
+                Frame::Message(msg, length) => {
+                    messages.push(msg);
+                    consumed += length;
+                }
+                Frame::Skipped {
+                    consumed: skipped,
+                    discarded: dropped,
+                } => {
+                    consumed += skipped;
+                    discarded += dropped;
+                }
+                Frame::Incomplete => break,
+            }
+        }
+        self.buffer = buffer;
+        self.buffer.drain(..consumed);
+        StreamParseResult {
+            messages,
+            consumed,
+            discarded,
+            buffered: self.buffer.len(),
+        }
+    }
+
+    /// look at the start of `input` for the next message
+    ///
+    /// this is the framing shared by `feed` and the async `DltCodec`
+    pub(crate) fn next_frame(&mut self, input: &[u8]) -> Frame {
+        let frame = self.frame(input);
+        let used = match &frame {
+            Frame::Message(_, length) => *length,
+            Frame::Skipped { consumed, .. } => *consumed,
+            Frame::Incomplete => 0,
+        };
+        self.processed += used as u64;
+        if let Some(progress) = &mut self.progress {
+            progress.update(self.processed);
+        }
+        frame
+    }
+
+    /// report the end of the stream to the progress callback
+    pub(crate) fn finish_progress(&mut self) {
+        if let Some(progress) = &mut self.progress {
+            progress.finish();
+        }
+    }
+
+    fn frame(&mut self, input: &[u8]) -> Frame {
+        let frame_length = match self.walker.step(input, false) {
+            FrameStep::Frame(length) => length,
+            FrameStep::Skip(dropped) => {
+                warn!("dropped {} bytes to get to next message", dropped);
+                return Frame::Skipped {
+                    consumed: dropped,
+                    discarded: dropped,
+                };
+            }
+            FrameStep::Incomplete { .. } => return Frame::Incomplete,
+            FrameStep::Invalid(e) => {
+                warn!("skipping invalid message: {}", e);
+                let skip = self.walker.skip_unusable(input, None);
+                return Frame::Skipped {
+                    consumed: skip,
+                    discarded: skip,
+                };
+            }
+        };
+        let frame = match dlt_message_with_options(
+            &input[..frame_length],
+            self.filter_config.as_ref(),
+            self.index,
+            None,
+            self.fibex_metadata.clone(),
+            self.walker.options,
+        ) {
+            Ok((_, ParsedMessage::Item(msg))) => Frame::Message(msg, frame_length),
+            Ok((_, ParsedMessage::FilteredOut)) | Ok((_, ParsedMessage::Invalid)) => {
+                Frame::Skipped {
+                    consumed: frame_length,
+                    discarded: 0,
+                }
+            }
+            Err(e) => {
+                warn!("could not parse message {}: {}", self.index, e);
+                let skip = self.walker.skip_unusable(input, Some(frame_length));
+                Frame::Skipped {
+                    consumed: skip,
+                    discarded: skip,
+                }
+            }
+        };
+        self.index += 1;
+        frame
+    }
+}
+
+/// what `DltStreamParser::next_frame` found at the start of the input
+pub(crate) enum Frame {
+    /// a decoded message and its length in bytes
+    Message(Message, usize),
+    /// bytes that are used up without a message (filtered out, invalid or skipped)
+    Skipped { consumed: usize, discarded: usize },
+    /// the message is not complete yet
+    Incomplete,
+}
+
+/// Iterator over the messages of a `DltSource`, see `DltStreamParser::messages`
+///
+/// ends with the source or after the first error of the source
+pub struct SourceMessages<'a, S> {
+    parser: DltStreamParser<'a>,
+    source: S,
+    ready: VecDeque<Message>,
+    exhausted: bool,
+}
+
+impl<'a, S> SourceMessages<'a, S> {
+    pub fn parser(&self) -> &DltStreamParser<'a> {
+        &self.parser
+    }
+}
+
+impl<'a, S: DltSource> Iterator for SourceMessages<'a, S> {
+    type Item = io::Result<Message>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(msg) = self.ready.pop_front() {
+                return Some(Ok(msg));
+            }
+            if self.exhausted {
+                return None;
+            }
+            match self.source.next_chunk() {
+                Ok([]) => {
+                    self.exhausted = true;
+                    self.parser.finish_progress();
+                    if self.parser.buffered() > 0 {
+                        warn!(
+                            "source ended within a message, dropped {} bytes",
+                            self.parser.buffered()
+                        );
+                    }
+                }
+                Ok(chunk) => self.ready.extend(self.parser.feed(chunk).messages),
+                Err(e) => {
+                    self.exhausted = true;
+                    self.parser.finish_progress();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
@@ -20,6 +20,32 @@ pub struct FibexMetadata {
     pub(crate) frame_map_with_key: HashMap<(ContextId, ApplicationId, FrameId), Rc<FrameMetadata>>, // TODO: avoid cloning on .get
     pub(crate) frame_map: HashMap<FrameId, Rc<FrameMetadata>>,
 }
+impl FibexMetadata {
+    /// find the frame for a non-verbose message
+    ///
+    /// frames that are defined for the app-id and context-id of the message
+    /// take precedence over frames that are only known by their id.
+    /// If several FIBEX files define the same frame, the first one read is used.
+    pub fn frame_for(
+        &self,
+        app_id: Option<&str>,
+        context_id: Option<&str>,
+        message_id: u32,
+    ) -> Option<&FrameMetadata> {
+        let frame_id = FrameId(format!("ID_{}", message_id));
+        let with_key = match (context_id, app_id) {
+            (Some(context_id), Some(app_id)) => self.frame_map_with_key.get(&(
+                ContextId(context_id.to_string()),
+                ApplicationId(app_id.to_string()),
+                frame_id.clone(),
+            )),
+            _ => None,
+        };
+        with_key
+            .or_else(|| self.frame_map.get(&frame_id))
+            .map(|frame| &**frame)
+    }
+}
 #[derive(Debug, PartialEq, Clone)]
 pub struct FrameMetadata {
     pub short_name: String,
@@ -55,6 +81,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -64,6 +91,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -73,6 +101,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -82,6 +111,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -91,6 +121,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -100,6 +131,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -109,6 +141,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -118,6 +151,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -127,6 +161,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -136,6 +171,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -145,6 +181,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -154,6 +191,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         })
     }
 
@@ -163,6 +201,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         }),
         "S_SINT8" => sint8(),
         "S_UINT8" => uint8(),
@@ -185,6 +224,7 @@ fn type_info_for_signal_ref(
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         }),
         s => match signals.get(s).and_then(|s| codings.get(s)) {
             Some(base_type) => match base_type.as_ref() {
@@ -10,8 +10,18 @@
 // is strictly forbidden unless prior written permission is obtained
 // from E.S.R.Labs.
 use crate::dlt;
+use anyhow::anyhow;
+use quick_xml::{events::Event as XmlEvent, Reader as XmlReader};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashSet, fs, io::Read, iter::FromIterator};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{BufRead, BufReader, Read},
+    iter::FromIterator,
+    path::Path,
+    time::SystemTime,
+};
 
 /// only select log entries with level MIN_LEVEL and more severe
 ///  1 => FATAL
@@ -20,28 +30,510 @@ use std::{collections::HashSet, fs, io::Read, iter::FromIterator};
 ///  4 => INFO
 ///  5 => DEBUG
 ///  6 => VERBOSE
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// the payload criteria are checked against the decoded payload text
+/// which is a lot more expensive than checking the ids of the header
+///
+/// the default config has no criteria and matches every message
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct DltFilterConfig {
     pub min_log_level: Option<u8>,
+    /// keep control, trace and network messages when a `min_log_level` is
+    /// set, they don't carry a log level (default: `true`)
+    pub keep_messages_without_log_level: Option<bool>,
+    /// only messages with a storage header time at or after `time_from`
+    pub time_from: Option<SystemTime>,
+    /// only messages with a storage header time at or before `time_to`
+    pub time_to: Option<SystemTime>,
+    /// keep messages without storage header (e.g. received from the network)
+    /// when a time range is set (default: `true`)
+    pub keep_messages_without_storage_header: Option<bool>,
+    /// only messages with one of these application ids, a message without
+    /// an extended header carries no application id and is filtered out
     pub app_ids: Option<Vec<String>>,
+    /// only messages with one of these ECU ids, the ECU id of the storage
+    /// header is used when the standard header has none. a message with
+    /// neither is filtered out
     pub ecu_ids: Option<Vec<String>>,
+    /// only messages with one of these context ids, a message without
+    /// an extended header carries no context id and is filtered out
     pub context_ids: Option<Vec<String>>,
+    /// texts of which at least one has to be part of the payload
+    pub payload_contains: Option<Vec<String>>,
+    /// regular expressions of which at least one has to match the payload
+    pub payload_regex: Option<Vec<String>>,
+    /// compare the payload without regard to case (default: `false`)
+    pub payload_ignore_case: Option<bool>,
+    /// messages that match one of these are filtered out
+    pub excludes: Option<Vec<DltExcludeFilter>>,
 }
+
+/// filters out the messages that match all of its criteria
+///
+/// a criteria that is `None` matches every message,
+/// the ECU id of the storage header is used when the standard header has none
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct DltExcludeFilter {
+    pub ecu_id: Option<String>,
+    pub app_id: Option<String>,
+    pub context_id: Option<String>,
+    /// regular expression that has to match the payload
+    pub payload_regex: Option<String>,
+}
+
+/// filter types used in DLT-Viewer filter files
+const DLF_POSITIVE_FILTER: &str = "0";
+const DLF_NEGATIVE_FILTER: &str = "1";
+
+impl DltFilterConfig {
+    /// import the filters of a DLT-Viewer filter file (`.dlf`)
+    ///
+    /// DLT-Viewer shows a message if it matches any of the enabled positive
+    /// filters. Since this config only holds one criteria per kind of id,
+    /// the positive filters are merged: a kind that is not restricted by
+    /// every one of them is not restricted at all. So the resulting config
+    /// selects all messages DLT-Viewer would show, but possibly a few more.
+    /// Criteria without an equivalent (header text, minimum log level,
+    /// regular expressions for ids) are skipped, markers only highlight
+    /// messages and are skipped as well. If some payload filters use a regular
+    /// expression and others plain text, the texts become regular expressions.
+    ///
+    /// Every negative filter becomes one of the `excludes`. Leaving out one of
+    /// its criteria would hide more messages than DLT-Viewer does, so a negative
+    /// filter with criteria that cannot be expressed is an error.
+    pub fn from_dlf<R: BufRead>(reader: R) -> Result<DltFilterConfig, anyhow::Error> {
+        let filters = read_dlf_filters(reader)?;
+        let mut positive: Vec<DlfFilter> = vec![];
+        let mut excludes: Vec<DltExcludeFilter> = vec![];
+        for filter in filters {
+            if !filter.flag("enablefilter") {
+                continue;
+            }
+            match filter.value("type") {
+                Some(DLF_POSITIVE_FILTER) => positive.push(filter),
+                Some(DLF_NEGATIVE_FILTER) => excludes.push(filter.to_exclude()?),
+                _ => (), // markers only highlight messages
+            }
+        }
+        let min_log_level = merge(&positive, |f| {
+            if f.flag("enableLogLevelMax") {
+                f.value("logLevelMax").and_then(|v| v.parse::<u8>().ok())
+            } else {
+                None
+            }
+        })
+        .and_then(|levels| levels.into_iter().max());
+        let (payload_contains, payload_regex) = match merge(&positive, DlfFilter::payload) {
+            Some(payloads) => {
+                let texts: Option<Vec<String>> = payloads
+                    .iter()
+                    .map(|p| match p {
+                        DlfPayload::Contains(text) => Some(text.clone()),
+                        DlfPayload::Regex(_) => None,
+                    })
+                    .collect();
+                if texts.is_some() {
+                    (texts, None)
+                } else {
+                    let regexes = payloads.into_iter().map(DlfPayload::into_regex).collect();
+                    (None, Some(regexes))
+                }
+            }
+            None => (None, None),
+        };
+        Ok(DltFilterConfig {
+            min_log_level,
+            app_ids: merge(&positive, |f| {
+                f.id("applicationid", Some("enableRegexp_Appid"))
+            }),
+            ecu_ids: merge(&positive, |f| f.id("ecuid", None)),
+            context_ids: merge(&positive, |f| {
+                f.id("contextid", Some("enableRegexp_Context"))
+            }),
+            payload_contains,
+            payload_regex,
+            excludes: if excludes.is_empty() {
+                None
+            } else {
+                Some(excludes)
+            },
+            // the case handling of each filter is part of its regex
+            ..Default::default()
+        })
+    }
+
+    /// import the filters of the DLT-Viewer filter file at `path`
+    ///
+    /// see `from_dlf`
+    pub fn from_dlf_file<P: AsRef<Path>>(path: P) -> Result<DltFilterConfig, anyhow::Error> {
+        DltFilterConfig::from_dlf(BufReader::new(fs::File::open(path)?))
+    }
+}
+
+/// the elements of one `<filter>` of a dlf file
+struct DlfFilter {
+    values: HashMap<String, String>,
+}
+
+#[derive(PartialEq)]
+enum DlfPayload {
+    Contains(String),
+    Regex(String),
+}
+
+impl DlfPayload {
+    fn into_regex(self) -> String {
+        match self {
+            DlfPayload::Contains(text) => regex::escape(&text),
+            DlfPayload::Regex(regex) => regex,
+        }
+    }
+}
+
+impl DlfFilter {
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.value(name) == Some("1")
+    }
+
+    /// the id if the filter is restricted to it
+    ///
+    /// ids given as regular expression are treated as unrestricted
+    fn id(&self, name: &str, regex_flag: Option<&str>) -> Option<String> {
+        let is_regex = regex_flag.map(|flag| self.flag(flag)).unwrap_or(false);
+        if is_regex || !self.flag(&format!("enable{}", name)) {
+            return None;
+        }
+        self.value(name)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+    }
+
+    /// the exclude filter for a negative filter
+    fn to_exclude(&self) -> Result<DltExcludeFilter, anyhow::Error> {
+        let unsupported = [
+            (
+                self.flag("enableapplicationid") && self.flag("enableRegexp_Appid"),
+                "a regular expression for the application id",
+            ),
+            (
+                self.flag("enablecontextid") && self.flag("enableRegexp_Context"),
+                "a regular expression for the context id",
+            ),
+            (self.flag("enableheadertext"), "the header text"),
+            (
+                self.flag("enableLogLevelMax") || self.flag("enableLogLevelMin"),
+                "log levels",
+            ),
+            (self.flag("enablectrlmsgs"), "control messages"),
+        ];
+        if let Some((_, criteria)) = unsupported.iter().find(|(used, _)| *used) {
+            return Err(anyhow!(
+                "negative filter {:?} uses {}, which cannot be imported",
+                self.value("name").unwrap_or_default(),
+                criteria
+            ));
+        }
+        Ok(DltExcludeFilter {
+            ecu_id: self.id("ecuid", None),
+            app_id: self.id("applicationid", None),
+            context_id: self.id("contextid", None),
+            payload_regex: self.payload().map(DlfPayload::into_regex),
+        })
+    }
+
+    fn payload(&self) -> Option<DlfPayload> {
+        if !self.flag("enablepayloadtext") {
+            return None;
+        }
+        let text = self.value("payloadtext").filter(|t| !t.is_empty())?;
+        let ignore_case = self.flag("ignoreCase_Payload");
+        Some(match (self.flag("enableRegexp_Payload"), ignore_case) {
+            (true, true) => DlfPayload::Regex(format!("(?i){}", text)),
+            (true, false) => DlfPayload::Regex(text.to_string()),
+            (false, true) => DlfPayload::Regex(format!("(?i){}", regex::escape(text))),
+            (false, false) => DlfPayload::Contains(text.to_string()),
+        })
+    }
+}
+
+/// collect the criteria of all filters
+///
+/// if one of the filters does not restrict the criteria, nothing is restricted
+fn merge<T, F>(filters: &[DlfFilter], criteria: F) -> Option<Vec<T>>
+where
+    T: PartialEq,
+    F: Fn(&DlfFilter) -> Option<T>,
+{
+    if filters.is_empty() {
+        return None;
+    }
+    let mut merged = vec![];
+    for filter in filters {
+        let value = criteria(filter)?;
+        if !merged.contains(&value) {
+            merged.push(value);
+        }
+    }
+    Some(merged)
+}
+
+fn read_dlf_filters<R: BufRead>(reader: R) -> Result<Vec<DlfFilter>, anyhow::Error> {
+    let mut xml_reader = XmlReader::from_reader(reader);
+    xml_reader.trim_text(true);
+    let mut buf = vec![];
+    let mut text_buf = vec![];
+    let mut filters = vec![];
+    let mut current: Option<DlfFilter> = None;
+    loop {
+        match xml_reader.read_event(&mut buf)? {
+            XmlEvent::Start(ref e) => match (e.name(), &mut current) {
+                (b"filter", None) => {
+                    current = Some(DlfFilter {
+                        values: HashMap::new(),
+                    })
+                }
+                (name, Some(filter)) => {
+                    let name = String::from_utf8_lossy(name).into_owned();
+                    let text = xml_reader.read_text(e.name(), &mut text_buf)?;
+                    text_buf.clear();
+                    filter.values.insert(name, text);
+                }
+                _ => (),
+            },
+            XmlEvent::End(ref e) if e.name() == b"filter" => {
+                if let Some(filter) = current.take() {
+                    filters.push(filter);
+                }
+            }
+            XmlEvent::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(filters)
+}
+
 #[derive(Clone)]
 pub struct ProcessedDltFilterConfig {
     pub min_log_level: Option<dlt::LogLevel>,
+    pub keep_messages_without_log_level: bool,
+    pub time_from: Option<SystemTime>,
+    pub time_to: Option<SystemTime>,
+    pub keep_messages_without_storage_header: bool,
     pub app_ids: Option<HashSet<String>>,
     pub ecu_ids: Option<HashSet<String>>,
     pub context_ids: Option<HashSet<String>>,
+    /// already lowercased if the case is ignored
+    pub payload_contains: Option<Vec<String>>,
+    /// all regular expressions of the config combined into one
+    pub payload_regex: Option<Regex>,
+    pub payload_ignore_case: bool,
+    pub excludes: Vec<ProcessedDltExcludeFilter>,
+}
+
+#[derive(Clone)]
+pub struct ProcessedDltExcludeFilter {
+    pub ecu_id: Option<String>,
+    pub app_id: Option<String>,
+    pub context_id: Option<String>,
+    pub payload_regex: Option<Regex>,
+}
+
+impl ProcessedDltExcludeFilter {
+    fn matches_ids(
+        &self,
+        ecu_id: Option<&str>,
+        app_id: Option<&str>,
+        context_id: Option<&str>,
+    ) -> bool {
+        let matches = |criteria: &Option<String>, id: Option<&str>| match criteria {
+            Some(criteria) => id == Some(criteria.as_str()),
+            None => true,
+        };
+        matches(&self.ecu_id, ecu_id)
+            && matches(&self.app_id, app_id)
+            && matches(&self.context_id, context_id)
+    }
+}
+
+impl ProcessedDltFilterConfig {
+    /// check the header information of a message against the filter
+    ///
+    /// this is done before the payload is parsed so the ids are passed
+    /// as borrowed strings straight from the input.
+    /// a filter criteria that is `None` matches everything, a message that does
+    /// not carry an id (e.g. no extended header) does not match an active criteria
+    pub fn matches(
+        &self,
+        ecu_id: Option<&str>,
+        app_id: Option<&str>,
+        context_id: Option<&str>,
+        message_type: Option<&dlt::MessageType>,
+    ) -> bool {
+        if let Some(min_filter_level) = self.min_log_level {
+            let keep = match message_type {
+                Some(t @ dlt::MessageType::Log(_)) => !t.skip_with_level(min_filter_level),
+                _ => self.keep_messages_without_log_level,
+            };
+            if !keep {
+                return false;
+            }
+        }
+        id_matches(&self.app_ids, app_id)
+            && id_matches(&self.context_ids, context_id)
+            && id_matches(&self.ecu_ids, ecu_id)
+            && !self.excludes.iter().any(|exclude| {
+                exclude.payload_regex.is_none() && exclude.matches_ids(ecu_id, app_id, context_id)
+            })
+    }
+
+    /// check the time of the storage header against the time range of the filter
+    ///
+    /// every message is checked on its own, so after the clock of a recording
+    /// device was reset the messages that are back in range are still found
+    pub fn matches_time(&self, timestamp: Option<&dlt::DltTimeStamp>) -> bool {
+        if self.time_from.is_none() && self.time_to.is_none() {
+            return true;
+        }
+        match timestamp {
+            Some(timestamp) => {
+                let time = timestamp.to_system_time();
+                !matches!(self.time_from, Some(from) if time < from)
+                    && !matches!(self.time_to, Some(to) if time > to)
+            }
+            None => self.keep_messages_without_storage_header,
+        }
+    }
+
+    /// true if the payload has to be decoded to apply the filter
+    pub fn has_payload_criteria(&self) -> bool {
+        self.payload_contains.is_some()
+            || self.payload_regex.is_some()
+            || self.excludes.iter().any(|e| e.payload_regex.is_some())
+    }
+
+    /// check the human readable payload of a message against the filter
+    ///
+    /// since this needs the decoded payload it should only be done for
+    /// messages that already passed `matches`
+    pub fn matches_payload(&self, payload: &str) -> bool {
+        if let Some(texts) = &self.payload_contains {
+            let found = if self.payload_ignore_case {
+                let payload = payload.to_lowercase();
+                texts.iter().any(|t| payload.contains(t.as_str()))
+            } else {
+                texts.iter().any(|t| payload.contains(t.as_str()))
+            };
+            if !found {
+                return false;
+            }
+        }
+        match &self.payload_regex {
+            Some(regex) => regex.is_match(payload),
+            None => true,
+        }
+    }
+
+    /// check the payload against the excludes with payload criteria
+    ///
+    /// the ids are those that were checked by `matches`
+    pub fn excludes_payload(
+        &self,
+        ecu_id: Option<&str>,
+        app_id: Option<&str>,
+        context_id: Option<&str>,
+        payload: &str,
+    ) -> bool {
+        self.excludes
+            .iter()
+            .any(|exclude| match &exclude.payload_regex {
+                Some(regex) => {
+                    exclude.matches_ids(ecu_id, app_id, context_id) && regex.is_match(payload)
+                }
+                None => false,
+            })
+    }
+}
+
+/// combine the patterns into one regular expression
+///
+/// every pattern is checked on its own first so the error points to the
+/// pattern that is invalid
+fn payload_regex(patterns: &[String], ignore_case: bool) -> Result<Regex, regex::Error> {
+    let grouped = patterns
+        .iter()
+        .map(|p| {
+            // a group keeps inline flags of a pattern from affecting the others
+            Regex::new(p).map(|_| format!("(?:{})", p))
+        })
+        .collect::<Result<Vec<String>, regex::Error>>()?;
+    RegexBuilder::new(&grouped.join("|"))
+        .case_insensitive(ignore_case)
+        .build()
 }
 
-pub fn process_filter_config(cfg: DltFilterConfig) -> ProcessedDltFilterConfig {
-    ProcessedDltFilterConfig {
+fn id_matches(ids: &Option<HashSet<String>>, id: Option<&str>) -> bool {
+    match (ids, id) {
+        (None, _) => true,
+        (Some(ids), Some(id)) => ids.contains(id),
+        (Some(_), None) => false,
+    }
+}
+
+/// prepare the config for filtering messages
+///
+/// fails if one of the payload regular expressions is invalid, skipping it
+/// would let messages pass that the filter was meant to remove
+pub fn process_filter_config(
+    cfg: DltFilterConfig,
+) -> Result<ProcessedDltFilterConfig, regex::Error> {
+    let payload_ignore_case = cfg.payload_ignore_case.unwrap_or(false);
+    let payload_regex = match cfg.payload_regex {
+        Some(patterns) => Some(payload_regex(&patterns, payload_ignore_case)?),
+        None => None,
+    };
+    let excludes = cfg
+        .excludes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|exclude| {
+            Ok(ProcessedDltExcludeFilter {
+                payload_regex: exclude
+                    .payload_regex
+                    .map(|pattern| Regex::new(&pattern))
+                    .transpose()?,
+                ecu_id: exclude.ecu_id,
+                app_id: exclude.app_id,
+                context_id: exclude.context_id,
+            })
+        })
+        .collect::<Result<_, regex::Error>>()?;
+    Ok(ProcessedDltFilterConfig {
         min_log_level: cfg.min_log_level.and_then(dlt::u8_to_log_level),
+        keep_messages_without_log_level: cfg.keep_messages_without_log_level.unwrap_or(true),
+        time_from: cfg.time_from,
+        time_to: cfg.time_to,
+        keep_messages_without_storage_header: cfg
+            .keep_messages_without_storage_header
+            .unwrap_or(true),
         app_ids: cfg.app_ids.map(HashSet::from_iter),
         ecu_ids: cfg.ecu_ids.map(HashSet::from_iter),
         context_ids: cfg.context_ids.map(HashSet::from_iter),
-    }
+        payload_contains: cfg.payload_contains.map(|texts| {
+            if payload_ignore_case {
+                texts.iter().map(|t| t.to_lowercase()).collect()
+            } else {
+                texts
+            }
+        }),
+        payload_regex,
+        payload_ignore_case,
+        excludes,
+    })
 }
 
 pub fn read_filter_options(f: &mut fs::File) -> Result<DltFilterConfig, anyhow::Error> {
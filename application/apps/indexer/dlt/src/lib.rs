@@ -10,23 +10,48 @@
 // is strictly forbidden unless prior written permission is obtained
 // from E.S.R.Labs.
 //#![allow(dead_code)]
+// tests always have std, the `std` feature only switches the modules that need it
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "std")]
 extern crate indexer_base;
 
 #[macro_use]
 extern crate log;
 
 pub mod dlt;
+pub mod dlt_builder;
+#[cfg(feature = "tokio")]
+pub mod dlt_codec;
+pub mod dlt_control;
+pub mod dlt_dedup;
+#[cfg(feature = "std")]
 pub mod dlt_file;
+#[cfg(feature = "std")]
 pub mod dlt_fmt;
+#[cfg(feature = "mmap")]
+pub mod dlt_mmap;
+#[cfg(feature = "std")]
 pub mod dlt_net;
 pub mod dlt_parse;
+#[cfg(feature = "std")]
 pub mod dlt_pcap;
+pub mod dlt_segments;
+#[cfg(feature = "std")]
+pub mod dlt_stream;
+#[cfg(feature = "std")]
 pub mod fibex;
+#[cfg(feature = "std")]
 pub mod filtering;
+#[cfg(any(feature = "std", test))]
 pub mod proptest_strategies;
+#[cfg(feature = "std")]
 pub mod service_id;
+#[cfg(not(feature = "std"))]
+mod without_std;
 
 #[cfg(test)]
 mod tests;
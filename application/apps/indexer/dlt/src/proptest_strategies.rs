@@ -71,7 +71,8 @@ prop_compose! {
         } else {
             payload.as_bytes::<LittleEndian>().len()
         } as u16;
-        let header = StandardHeader { payload_length, ..standard_header };
+        // other versions are rejected by the parser
+        let header = StandardHeader { payload_length, version: DLT_VERSION, ..standard_header };
         let real_arg_cnt = match &payload.payload_content {
             PayloadContent::Verbose(args) => args.len(),
             _ => 0,
@@ -80,7 +81,9 @@ prop_compose! {
         let real_msg_type = match &payload.payload_content {
             PayloadContent::ControlMsg(control_type, _) => MessageType::Control(control_type.clone()),
             PayloadContent::Verbose(_) => MessageType::Log(LogLevel::Warn),
-            PayloadContent::NonVerbose(_, _) => MessageType::Log(LogLevel::Debug),
+            PayloadContent::NonVerbose(_, _)
+            | PayloadContent::Raw(_)
+            | PayloadContent::Skipped(_) => MessageType::Log(LogLevel::Debug),
         };
         // println!("... to {}", real_msg_type);
         // correct extended header fiels according to payload
@@ -99,15 +102,44 @@ prop_compose! {
         }
     }
 }
-fn value_strategy(info: &TypeInfo) -> impl Strategy<Value = Value> {
+fn value_strategy(info: &TypeInfo) -> BoxedStrategy<Value> {
     // println!("value_strategy for {:?}", info);
     match &info.kind {
+        TypeInfoKind::Array(element) => {
+            let element_info = TypeInfo {
+                kind: (**element).clone(),
+                ..info.clone()
+            };
+            let element_type = (**element).clone();
+            prop::collection::vec(1..4u16, 1..3)
+                .prop_flat_map(move |dims| {
+                    let len: usize = dims.iter().map(|d| *d as usize).product();
+                    (
+                        Just(dims),
+                        prop::collection::vec(value_strategy(&element_info), len),
+                    )
+                })
+                .prop_map(move |(dims, values)| Value::Array {
+                    element_type: element_type.clone(),
+                    dims,
+                    values,
+                })
+                .boxed()
+        }
         TypeInfoKind::Bool => (0..10u8).prop_map(Value::Bool).boxed(),
+        // only values that survive the conversion to half-precision
+        TypeInfoKind::Float(FloatWidth::Width16) => any::<f32>()
+            .prop_map(|v| Value::F16(f16_to_f32(f32_to_f16(v))))
+            .boxed(),
         TypeInfoKind::Float(FloatWidth::Width32) => any::<f32>().prop_map(Value::F32).boxed(),
         TypeInfoKind::Float(FloatWidth::Width64) => any::<f64>().prop_map(Value::F64).boxed(),
         TypeInfoKind::Raw => prop::collection::vec(any::<u8>(), 0..5)
             .prop_map(Value::Raw)
             .boxed(),
+        // ASCII strings are stored as Latin-1
+        TypeInfoKind::StringType if info.coding == StringCoding::ASCII => {
+            "[ -~\u{a0}-\u{ff}]*".prop_map(Value::StringVal).boxed()
+        }
         TypeInfoKind::StringType => any::<String>()
             .prop_map(|v| {
                 // println!("create StringType value: {}", v);
@@ -122,7 +154,8 @@ fn value_strategy(info: &TypeInfo) -> impl Strategy<Value = Value> {
         TypeInfoKind::Signed(TypeLength::BitLength128) => {
             any::<i128>().prop_map(Value::I128).boxed()
         }
-        TypeInfoKind::SignedFixedPoint(FloatWidth::Width32) => {
+        TypeInfoKind::SignedFixedPoint(FloatWidth::Width16)
+        | TypeInfoKind::SignedFixedPoint(FloatWidth::Width32) => {
             any::<i32>().prop_map(Value::I32).boxed()
         }
         TypeInfoKind::SignedFixedPoint(FloatWidth::Width64) => {
@@ -142,7 +175,8 @@ fn value_strategy(info: &TypeInfo) -> impl Strategy<Value = Value> {
         TypeInfoKind::Unsigned(TypeLength::BitLength128) => {
             any::<u128>().prop_map(Value::U128).boxed()
         }
-        TypeInfoKind::UnsignedFixedPoint(FloatWidth::Width32) => {
+        TypeInfoKind::UnsignedFixedPoint(FloatWidth::Width16)
+        | TypeInfoKind::UnsignedFixedPoint(FloatWidth::Width32) => {
             any::<u32>().prop_map(Value::U32).boxed()
         }
         TypeInfoKind::UnsignedFixedPoint(FloatWidth::Width64) => {
@@ -179,11 +213,26 @@ fn fp_strategy(width: FloatWidth) -> impl Strategy<Value = FixedPoint> {
     } else {
         any::<i64>().prop_map(FixedPointValue::I64).boxed()
     };
-    (any::<f32>(), fp_value_strat).prop_map(|(quantization, offset)| FixedPoint {
+    // NaN would make the scaled value incomparable
+    let quantization_strat = prop::num::f32::NORMAL | prop::num::f32::ZERO;
+    (quantization_strat, fp_value_strat).prop_map(|(quantization, offset)| FixedPoint {
         quantization,
         offset,
     })
 }
+// any TypeInfo, including arrays of the types that can be array elements
+fn type_info_strategy() -> impl Strategy<Value = TypeInfo> {
+    prop_oneof![
+        4 => any::<TypeInfo>(),
+        1 => any::<TypeInfo>().prop_filter_map("no array element", |ti| match ti.kind {
+            TypeInfoKind::StringType | TypeInfoKind::Raw => None,
+            kind => Some(TypeInfo {
+                kind: TypeInfoKind::Array(Box::new(kind)),
+                ..ti
+            }),
+        }),
+    ]
+}
 // strategy that produces TypeInfo and matching optional FixedPoint for arguments
 fn type_info_and_fixed_point_strategy() -> impl Strategy<
     Value = (
@@ -193,15 +242,27 @@ fn type_info_and_fixed_point_strategy() -> impl Strategy<
         (Option<String>, Option<String>),
     ),
 > {
-    any::<TypeInfo>().prop_flat_map(move |ti| {
+    type_info_strategy().prop_flat_map(move |ti| {
         let fp_strat = match ti.kind {
             TypeInfoKind::SignedFixedPoint(width) => fp_strategy(width).prop_map(Some).boxed(),
             TypeInfoKind::UnsignedFixedPoint(width) => fp_strategy(width).prop_map(Some).boxed(),
+            TypeInfoKind::Array(ref element) => match **element {
+                TypeInfoKind::SignedFixedPoint(width) | TypeInfoKind::UnsignedFixedPoint(width) => {
+                    fp_strategy(width).prop_map(Some).boxed()
+                }
+                _ => fp_none_strategy().boxed(),
+            },
             _ => fp_none_strategy().boxed(),
         };
         let name_unit_strat = name_and_unit_strategy(ti.has_variable_info, ti.kind.clone());
         let val_strat = value_strategy(&ti);
-        (Just(ti), fp_strat, val_strat, name_unit_strat)
+        (Just(ti), fp_strat, val_strat, name_unit_strat).prop_map(|(ti, fp, val, name_unit)| {
+            let val = match &fp {
+                Some(fp) => fp.scale(val),
+                None => val,
+            };
+            (ti, fp, val, name_unit)
+        })
     })
 }
 
@@ -257,6 +318,13 @@ pub fn signed_strategy() -> impl Strategy<Value = TypeInfoKind> {
         any::<TypeLength>().prop_flat_map(|width| Just(TypeInfoKind::Signed(width)))
     ]
 }
+// strategy to produce float TypeInfoKinds, half-precision is only valid for floats
+pub fn float_strategy() -> impl Strategy<Value = TypeInfoKind> {
+    prop_oneof![
+        Just(TypeInfoKind::Float(FloatWidth::Width16)),
+        any::<FloatWidth>().prop_flat_map(|width| Just(TypeInfoKind::Float(width)))
+    ]
+}
 // strategy to produce unsigned TypeInfoKinds for only 32 and 64 bit width
 pub fn unsigned_strategy() -> impl Strategy<Value = TypeInfoKind> {
     prop_oneof![
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::{dlt::*, dlt_builder::*, dlt_parse::*};
+    use pretty_assertions::assert_eq;
+
+    fn parse(bytes: &[u8], mode: StorageHeaderMode) -> Message {
+        match dlt_message(bytes, None, 0, None, None, mode) {
+            Ok((rest, ParsedMessage::Item(msg))) => {
+                assert!(rest.is_empty());
+                msg
+            }
+            _ => panic!("could not parse message"),
+        }
+    }
+
+    #[test]
+    fn test_build_verbose_message() {
+        let msg = MessageBuilder::new("ECU1", "APP", "CTX1")
+            .log_level(LogLevel::Warn)
+            .counter(7)
+            .timestamp(1234)
+            .endianness(Endianness::Little)
+            .arg("temperature")
+            .arg(-12i16)
+            .arg(true)
+            .arg(0.5f64)
+            .arg(vec![1u8, 2, 3])
+            .build()
+            .expect("could not build message");
+        let ext = msg.extended_header.as_ref().expect("no extended header");
+        assert!(ext.verbose);
+        assert_eq!(5, ext.argument_count);
+        assert_eq!(MessageType::Log(LogLevel::Warn), ext.message_type);
+        assert_eq!(Some("ECU1".to_string()), msg.header.ecu_id);
+        assert_eq!(None, msg.header.session_id);
+
+        let bytes = msg.as_bytes();
+        let headers_length = calculate_all_headers_length(msg.header.header_type_byte());
+        assert_eq!(
+            bytes.len(),
+            (headers_length + msg.header.payload_length) as usize
+        );
+        assert_eq!(msg, parse(&bytes, StorageHeaderMode::Absent));
+    }
+
+    #[test]
+    fn test_build_message_with_storage_header() {
+        let msg = MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .session_id(3)
+            .storage_header(DltTimeStamp {
+                seconds: 100,
+                microseconds: 42,
+            })
+            .args(vec![1u32, 2, 3])
+            .build()
+            .expect("could not build message");
+        let storage_header = msg.storage_header.as_ref().expect("no storage header");
+        assert_eq!("ECU1", storage_header.ecu_id);
+        assert_eq!(msg, parse(&msg.as_bytes(), StorageHeaderMode::Required));
+    }
+
+    #[test]
+    fn test_build_non_verbose_message() {
+        let msg = MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .arg(1u8)
+            .non_verbose(0x42, vec![0xA, 0xB])
+            .build()
+            .expect("could not build message");
+        let ext = msg.extended_header.as_ref().expect("no extended header");
+        assert!(!ext.verbose);
+        assert_eq!(0, ext.argument_count);
+        assert_eq!(6, msg.header.payload_length);
+    }
+
+    #[test]
+    fn test_build_invalid_messages() {
+        assert!(MessageBuilder::new("ECU_1", "APP1", "CTX1")
+            .build()
+            .is_err());
+        assert!(MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .args(vec![0u8; 256])
+            .build()
+            .is_err());
+        assert!(MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .arg(vec![0u8; u16::MAX as usize])
+            .build()
+            .is_err());
+        assert!(MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .args(vec![0u8; 255])
+            .build()
+            .is_ok());
+    }
+}
@@ -0,0 +1,261 @@
+#[cfg(test)]
+mod tests {
+    use crate::{dlt::*, dlt_control::*, dlt_parse::*};
+    use pretty_assertions::assert_eq;
+
+    /// encode a control message with the given payload and parse it again
+    fn parsed_control_message(
+        control_type: ControlType,
+        endianness: Endianness,
+        payload: &[u8],
+    ) -> Message {
+        let msg = Message::new(
+            MessageConfig {
+                version: 1,
+                counter: 0,
+                endianness,
+                ecu_id: Some("ECU".to_string()),
+                session_id: None,
+                timestamp: None,
+                payload: Payload2 {
+                    payload_content: PayloadContent::ControlMsg(
+                        ControlType::from_value(payload[0]),
+                        payload[1..].to_vec(),
+                    ),
+                },
+                extended_header_info: Some(ExtendedHeaderConfig {
+                    message_type: MessageType::Control(control_type),
+                    app_id: "DA1".to_string(),
+                    context_id: "DC1".to_string(),
+                }),
+            },
+            None,
+            None,
+        );
+        match dlt_message(
+            &msg.as_bytes(),
+            None,
+            0,
+            None,
+            None,
+            StorageHeaderMode::Absent,
+        ) {
+            Ok((_, ParsedMessage::Item(parsed))) => parsed,
+            res => panic!("could not parse control message: {:?}", res),
+        }
+    }
+
+    fn decode(control_type: ControlType, endianness: Endianness, payload: &[u8]) -> ControlMessage {
+        parsed_control_message(control_type, endianness, payload)
+            .control_message()
+            .expect("not a control message")
+            .expect("could not decode control message")
+    }
+
+    #[test]
+    fn test_set_log_level_request() {
+        #[rustfmt::skip]
+        let payload = [
+            0x01, 0x00, 0x00, 0x00, // service id
+            b'A', b'P', b'P', 0x00, // app id
+            b'C', b'T', b'X', b'1', // context id
+            0x05, // debug
+            b'r', b'e', b'm', b'o', // com interface
+        ];
+        assert_eq!(
+            ControlMessage::SetLogLevelRequest {
+                app_id: "APP".to_string(),
+                context_id: "CTX1".to_string(),
+                log_level: ControlLogLevel::Level(LogLevel::Debug),
+                com_interface: "remo".to_string(),
+            },
+            decode(ControlType::Request, Endianness::Little, &payload)
+        );
+    }
+
+    #[test]
+    fn test_status_responses() {
+        assert_eq!(
+            ControlMessage::SetLogLevelResponse(ControlStatus::Ok),
+            decode(
+                ControlType::Response,
+                Endianness::Little,
+                &[0x01, 0, 0, 0, 0]
+            )
+        );
+        assert_eq!(
+            ControlMessage::StoreConfigResponse(ControlStatus::Error),
+            decode(
+                ControlType::Response,
+                Endianness::Little,
+                &[0x05, 0, 0, 0, 2]
+            )
+        );
+        assert_eq!(
+            ControlMessage::GetDefaultLogLevelResponse {
+                status: ControlStatus::Ok,
+                log_level: ControlLogLevel::Off,
+            },
+            decode(
+                ControlType::Response,
+                Endianness::Big,
+                &[0, 0, 0, 0x04, 0, 0]
+            )
+        );
+    }
+
+    #[test]
+    fn test_set_default_log_level_request() {
+        assert_eq!(
+            ControlMessage::SetDefaultLogLevelRequest {
+                log_level: ControlLogLevel::Default,
+                com_interface: "remo".to_string(),
+            },
+            decode(
+                ControlType::Request,
+                Endianness::Big,
+                &[0, 0, 0, 0x11, 0xFF, b'r', b'e', b'm', b'o']
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_software_version_response() {
+        let mut payload = vec![0x13, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00];
+        payload.extend(b"1.2.3\0");
+        assert_eq!(
+            ControlMessage::GetSoftwareVersionResponse {
+                status: ControlStatus::Ok,
+                version: "1.2.3".to_string(),
+            },
+            decode(ControlType::Response, Endianness::Little, &payload)
+        );
+    }
+
+    #[test]
+    fn test_get_log_info_response() {
+        #[rustfmt::skip]
+        let payload = [
+            0x03, 0x00, 0x00, 0x00, // service id
+            0x07, // status: with log level, trace status and descriptions
+            0x02, 0x00, // 2 apps
+            b'A', b'P', b'P', b'1', // app id
+            0x02, 0x00, // 2 contexts
+            b'C', b'T', b'X', b'1', 0x04, 0x01, 0x02, 0x00, b'c', b'1',
+            b'C', b'T', b'X', b'2', 0xFF, 0x00, 0x00, 0x00,
+            0x04, 0x00, b'a', b'p', b'p', b'1', // app description
+            b'A', b'P', b'P', b'2', // app id
+            0x00, 0x00, // no contexts
+            0x00, 0x00, // empty app description
+            b'r', b'e', b'm', b'o', // com interface
+        ];
+        assert_eq!(
+            ControlMessage::GetLogInfoResponse {
+                status: ControlStatus::Other(7),
+                applications: vec![
+                    ApplicationLogInfo {
+                        app_id: "APP1".to_string(),
+                        contexts: vec![
+                            ContextLogInfo {
+                                context_id: "CTX1".to_string(),
+                                log_level: Some(ControlLogLevel::Level(LogLevel::Info)),
+                                trace_status: Some(1),
+                                description: Some("c1".to_string()),
+                            },
+                            ContextLogInfo {
+                                context_id: "CTX2".to_string(),
+                                log_level: Some(ControlLogLevel::Default),
+                                trace_status: Some(0),
+                                description: Some("".to_string()),
+                            },
+                        ],
+                        description: Some("app1".to_string()),
+                    },
+                    ApplicationLogInfo {
+                        app_id: "APP2".to_string(),
+                        contexts: vec![],
+                        description: Some("".to_string()),
+                    },
+                ],
+                com_interface: Some("remo".to_string()),
+            },
+            decode(ControlType::Response, Endianness::Little, &payload)
+        );
+    }
+
+    #[test]
+    fn test_get_log_info_response_only_log_levels() {
+        #[rustfmt::skip]
+        let payload = [
+            0x00, 0x00, 0x00, 0x03, // service id
+            0x04, // status: with log level
+            0x00, 0x01, // 1 app
+            b'A', b'P', b'P', b'1',
+            0x00, 0x01, // 1 context
+            b'C', b'T', b'X', b'1', 0x02,
+            b'r', b'e', b'm', b'o',
+        ];
+        assert_eq!(
+            ControlMessage::GetLogInfoResponse {
+                status: ControlStatus::Other(4),
+                applications: vec![ApplicationLogInfo {
+                    app_id: "APP1".to_string(),
+                    contexts: vec![ContextLogInfo {
+                        context_id: "CTX1".to_string(),
+                        log_level: Some(ControlLogLevel::Level(LogLevel::Error)),
+                        trace_status: None,
+                        description: None,
+                    }],
+                    description: None,
+                }],
+                com_interface: Some("remo".to_string()),
+            },
+            decode(ControlType::Response, Endianness::Big, &payload)
+        );
+    }
+
+    #[test]
+    fn test_unknown_service() {
+        assert_eq!(
+            ControlMessage::Unknown {
+                service_id: 0xF01,
+                payload: vec![1, 2],
+            },
+            decode(
+                ControlType::Request,
+                Endianness::Little,
+                &[0x01, 0x0F, 0, 0, 1, 2]
+            )
+        );
+    }
+
+    #[test]
+    fn test_truncated_control_message() {
+        let msg = parsed_control_message(ControlType::Request, Endianness::Little, &[0x01, 0, 0]);
+        assert!(msg
+            .control_message()
+            .expect("not a control message")
+            .is_err());
+    }
+
+    #[test]
+    fn test_no_control_message() {
+        let msg = Message::new(
+            MessageConfig {
+                version: 1,
+                counter: 0,
+                endianness: Endianness::Little,
+                ecu_id: None,
+                session_id: None,
+                timestamp: None,
+                payload: Payload2 {
+                    payload_content: PayloadContent::NonVerbose(1, vec![]),
+                },
+                extended_header_info: None,
+            },
+            None,
+            None,
+        );
+        assert_eq!(None, msg.control_message());
+    }
+}
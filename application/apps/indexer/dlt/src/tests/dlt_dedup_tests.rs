@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::{dlt::*, dlt_builder::MessageBuilder, dlt_dedup::*, dlt_parse::*};
+    use pretty_assertions::assert_eq;
+
+    fn message(text: &str, counter: u8, seconds: u32) -> Message {
+        MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .counter(counter)
+            .timestamp(seconds * 10_000)
+            .storage_header(DltTimeStamp {
+                seconds,
+                microseconds: 0,
+            })
+            .arg(text)
+            .build()
+            .unwrap()
+    }
+
+    fn collapse(msgs: &[Message], key: RepeatKey) -> Vec<(String, usize)> {
+        let bytes: Vec<u8> = msgs.iter().flat_map(|msg| msg.as_bytes()).collect();
+        collapse_repeats(message_refs(&bytes), key)
+            .map(|res| {
+                let (msg, count) = res.expect("could not parse message");
+                (msg.payload_text(), count)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collapse_repeats() {
+        let msgs = [
+            message("spam", 0, 1),
+            message("spam", 1, 1),
+            message("spam", 2, 2),
+            message("other", 3, 2),
+            message("spam", 4, 2),
+        ];
+        // the counter always differs and does not matter
+        assert_eq!(
+            vec![
+                ("spam".to_string(), 3),
+                ("other".to_string(), 1),
+                ("spam".to_string(), 1)
+            ],
+            collapse(&msgs, RepeatKey::Content)
+        );
+        assert_eq!(
+            vec![
+                ("spam".to_string(), 2),
+                ("spam".to_string(), 1),
+                ("other".to_string(), 1),
+                ("spam".to_string(), 1)
+            ],
+            collapse(&msgs, RepeatKey::ContentAndTime)
+        );
+
+        let other_app = MessageBuilder::new("ECU1", "APP2", "CTX1")
+            .storage_header(DltTimeStamp {
+                seconds: 1,
+                microseconds: 0,
+            })
+            .arg("spam")
+            .build()
+            .unwrap();
+        assert_eq!(
+            vec![("spam".to_string(), 1), ("spam".to_string(), 1)],
+            collapse(&[message("spam", 0, 1), other_app], RepeatKey::Content)
+        );
+        assert!(collapse(&[], RepeatKey::Content).is_empty());
+    }
+
+    #[test]
+    fn test_error_ends_a_run() {
+        let spam = message("spam", 0, 1).as_bytes();
+        let mut broken = spam.clone();
+        // a protocol version that is not supported
+        broken[STORAGE_HEADER_LENGTH as usize] |= 0b1110_0000;
+        let bytes = [&spam[..], &spam, &broken, &spam].concat();
+        let results: Vec<Result<usize, DltParseError>> =
+            collapse_repeats(message_refs(&bytes), RepeatKey::Content)
+                .map(|res| res.map(|(_, count)| count))
+                .collect();
+        assert_eq!(
+            vec![Ok(2), Err(DltParseError::UnsupportedVersion(7)), Ok(1)],
+            results
+        );
+    }
+}
@@ -1,10 +1,20 @@
 #[cfg(test)]
 mod tests {
 
-    use crate::dlt_file::*;
+    use crate::{
+        dlt::Message,
+        dlt_file::*,
+        dlt_parse::{
+            messages, messages_from_reader, ParseOptions, ParsedMessage, StorageHeaderMode,
+        },
+    };
     use crossbeam_channel as cc;
-    use indexer_base::{chunks::ChunkResults, config::IndexingConfig};
-    use std::path::PathBuf;
+    use flate2::{write::GzEncoder, Compression};
+    use indexer_base::{
+        chunks::ChunkResults,
+        config::{IndexSection, IndexingConfig, SectionConfig},
+    };
+    use std::{io::Write, path::PathBuf};
     #[test]
     fn test_storage_header_illegeal() {
         let in_path = PathBuf::from("..")
@@ -32,6 +42,143 @@ mod tests {
             &tx,
             None,
             None,
+            ParseOptions::new(StorageHeaderMode::Required),
         );
     }
+
+    #[test]
+    fn test_read_gzip_compressed_file() {
+        let plain_path = PathBuf::from("test_samples/testfile.dlt");
+        let bytes = std::fs::read(&plain_path).expect("could not read sample");
+        let gz_path = std::env::temp_dir().join(format!("testfile_{}.dlt.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).expect("could not compress");
+        std::fs::write(&gz_path, encoder.finish().expect("could not compress"))
+            .expect("could not write compressed file");
+
+        let read_all = |path: &PathBuf| -> Vec<_> {
+            let reader = open_dlt_reader(path).expect("could not open file");
+            messages_from_reader(reader)
+                .collect::<Result<_, _>>()
+                .expect("could not parse messages")
+        };
+        let plain = read_all(&plain_path);
+        assert_eq!(105, plain.len());
+        assert_eq!(plain, read_all(&gz_path));
+        std::fs::remove_file(gz_path).expect("could not remove temp file");
+    }
+
+    #[test]
+    fn test_parse_file_with_vendor_magic() {
+        const MAGIC: [u8; 4] = *b"XDL\x02";
+        let bytes = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        let expected: Vec<Message> = messages(&bytes)
+            .collect::<Result<_, _>>()
+            .expect("could not parse messages");
+        let mut vendor_bytes = vec![];
+        for msg in &expected {
+            let mut msg_bytes = msg.as_bytes();
+            msg_bytes[..4].copy_from_slice(&MAGIC);
+            vendor_bytes.extend(msg_bytes);
+        }
+        let path = std::env::temp_dir().join(format!("vendor_{}.dlt", std::process::id()));
+        std::fs::write(&path, &vendor_bytes).expect("could not write file");
+        let options = ParseOptions {
+            storage_magic: MAGIC,
+            ..ParseOptions::new(StorageHeaderMode::Required)
+        };
+        let parsed = futures::executor::block_on(parse_dlt_file_with_options(
+            path.clone(),
+            None,
+            None,
+            options,
+        ))
+        .expect("could not parse file");
+        assert_eq!(expected, parsed);
+        std::fs::remove_file(path).expect("could not remove temp file");
+    }
+
+    #[test]
+    fn test_index_and_export_file_with_vendor_magic() {
+        const MAGIC: [u8; 4] = *b"XDL\x02";
+        let bytes = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        let mut vendor_messages = vec![];
+        for msg in messages(&bytes) {
+            let mut msg_bytes = msg.expect("could not parse message").as_bytes();
+            msg_bytes[..4].copy_from_slice(&MAGIC);
+            vendor_messages.push(msg_bytes);
+        }
+        let dir = std::env::temp_dir();
+        let in_path = dir.join(format!("vendor_export_{}.dlt", std::process::id()));
+        let index_path = dir.join(format!("vendor_export_{}.out", std::process::id()));
+        let out_path = dir.join(format!("vendor_export_{}_part.dlt", std::process::id()));
+        std::fs::write(&in_path, vendor_messages.concat()).expect("could not write file");
+        let options = ParseOptions {
+            storage_magic: MAGIC,
+            ..ParseOptions::new(StorageHeaderMode::Required)
+        };
+
+        let (tx, _rx): (cc::Sender<ChunkResults>, cc::Receiver<ChunkResults>) = cc::unbounded();
+        create_index_and_mapping_dlt(
+            IndexingConfig {
+                tag: "TAG".to_string(),
+                chunk_size: 500,
+                in_file: in_path.clone(),
+                out_path: index_path.clone(),
+                append: false,
+                watch: false,
+            },
+            std::fs::metadata(&in_path).unwrap().len(),
+            None,
+            &tx,
+            None,
+            None,
+            options,
+        )
+        .expect("could not index file");
+        let indexed = std::fs::read_to_string(&index_path).expect("could not read index");
+        assert_eq!(vendor_messages.len(), indexed.lines().count());
+
+        export_as_dlt_file(
+            in_path.clone(),
+            out_path.clone(),
+            SectionConfig {
+                sections: vec![IndexSection {
+                    first_line: 1,
+                    last_line: 2,
+                }],
+            },
+            options,
+            tx,
+        )
+        .expect("could not export file");
+        let exported = std::fs::read(&out_path).expect("could not read exported file");
+        assert_eq!(vendor_messages[1..=2].concat(), exported);
+        for path in &[in_path, index_path, out_path] {
+            std::fs::remove_file(path).expect("could not remove temp file");
+        }
+    }
+
+    #[test]
+    fn test_file_message_producer_progress() {
+        let path = PathBuf::from("test_samples/testfile.dlt");
+        let bytes = std::fs::read(&path).expect("could not read sample");
+        let total = bytes.len() as u64;
+        let (tx, _rx): (cc::Sender<ChunkResults>, cc::Receiver<ChunkResults>) = cc::unbounded();
+        let mut calls = Vec::new();
+        let produced = FileMessageProducer::new(
+            &path,
+            None,
+            tx,
+            ParseOptions::new(StorageHeaderMode::Required),
+            None,
+        )
+        .expect("could not open file")
+        .with_progress(total, |processed, total| calls.push((processed, total)))
+        .take_while(|msg| !matches!(msg, ParsedMessage::Invalid))
+        .count();
+        assert_eq!(messages(&bytes).count(), produced);
+        // the sample is smaller than the progress interval
+        assert_eq!(vec![(total, total)], calls);
+    }
 }
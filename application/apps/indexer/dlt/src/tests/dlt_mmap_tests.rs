@@ -0,0 +1,100 @@
+#[cfg(all(test, feature = "mmap"))]
+mod tests {
+    use crate::{dlt::*, dlt_mmap::*, dlt_parse::*};
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}.dlt", name, std::process::id()));
+        std::fs::write(&path, content).expect("could not write temp file");
+        path
+    }
+
+    #[test]
+    fn test_mapped_file() {
+        let bytes = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        let file = DltFile::open("test_samples/testfile.dlt").expect("could not map file");
+        assert_eq!(bytes.len(), file.len());
+        let total = bytes.len() as u64;
+        let mut calls = Vec::new();
+        file.message_refs()
+            .with_progress(|processed, total| calls.push((processed, total)))
+            .for_each(drop);
+        assert_eq!(vec![(total, total)], calls);
+        let refs: Vec<MessageRef> = file
+            .message_refs()
+            .collect::<Result<_, _>>()
+            .expect("could not parse messages");
+        let owned: Vec<Message> = file
+            .messages()
+            .collect::<Result<_, _>>()
+            .expect("could not parse messages");
+        assert_eq!(105, refs.len());
+        assert_eq!(105, file.count_messages().unwrap());
+        let mut expected = DltInventory::default();
+        for msg in &owned {
+            expected.ecus.extend(msg.header.ecu_id.clone());
+            if let Some(ext) = &msg.extended_header {
+                expected.apps.insert(ext.application_id.clone());
+                expected
+                    .contexts
+                    .insert((ext.application_id.clone(), ext.context_id.clone()));
+            }
+        }
+        assert_eq!(expected, file.inventory());
+        let converted: Vec<Message> = refs
+            .iter()
+            .map(|m| m.to_owned().expect("could not convert message"))
+            .collect();
+        assert_eq!(owned, converted);
+    }
+
+    #[test]
+    fn test_mapped_file_truncated_in_message() {
+        let bytes = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        let path = temp_file("truncated", &bytes[..bytes.len() - 5]);
+        let file = DltFile::open(&path).expect("could not map file");
+        let results: Vec<Result<MessageRef, DltParseError>> = file.message_refs().collect();
+        assert_eq!(105, results.len());
+        assert!(results[..104].iter().all(Result::is_ok));
+        assert!(results[104].is_err());
+        drop(results);
+        drop(file);
+        std::fs::remove_file(path).expect("could not remove temp file");
+    }
+
+    #[test]
+    fn test_mapped_file_with_vendor_magic() {
+        const MAGIC: [u8; 4] = *b"XDL\x02";
+        let bytes = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        let mut vendor_bytes = vec![];
+        for msg in messages(&bytes) {
+            let mut msg_bytes = msg.expect("could not parse message").as_bytes();
+            msg_bytes[..4].copy_from_slice(&MAGIC);
+            vendor_bytes.extend(msg_bytes);
+        }
+        let path = temp_file("vendor_magic", &vendor_bytes);
+        let file = DltFile::open(&path).expect("could not map file");
+        assert_eq!(0, file.message_refs().filter(Result::is_ok).count());
+        let file = file.with_options(ParseOptions {
+            storage_magic: MAGIC,
+            ..ParseOptions::new(StorageHeaderMode::Required)
+        });
+        assert_eq!(105, file.count_messages().unwrap());
+        assert_eq!(105, file.message_refs().filter(Result::is_ok).count());
+        assert_eq!(105, file.messages().filter(Result::is_ok).count());
+        assert_eq!(inventory(&bytes), file.inventory());
+        drop(file);
+        std::fs::remove_file(path).expect("could not remove temp file");
+    }
+
+    #[test]
+    fn test_mapped_empty_file() {
+        let path = temp_file("empty", &[]);
+        let file = DltFile::open(&path).expect("could not map file");
+        assert!(file.is_empty());
+        assert_eq!(0, file.message_refs().count());
+        drop(file);
+        std::fs::remove_file(path).expect("could not remove temp file");
+    }
+}
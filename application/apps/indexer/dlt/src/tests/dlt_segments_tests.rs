@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use crate::{dlt::*, dlt_builder::MessageBuilder, dlt_parse::*, dlt_segments::*};
+    use pretty_assertions::assert_eq;
+
+    fn trace(app_id: &str) -> MessageBuilder {
+        MessageBuilder::new("ECU1", app_id, "CTX1")
+            .message_type(MessageType::NetworkTrace(NetworkTraceType::Can))
+    }
+
+    fn start(handle: u32, segment_count: u16) -> Message {
+        trace("APP1")
+            .arg(SEGMENT_START)
+            .arg(handle)
+            .arg(vec![0xCA, 0xFE])
+            .arg(u32::from(segment_count) * 2)
+            .arg(segment_count)
+            .arg(2u16)
+            .build()
+            .unwrap()
+    }
+
+    fn chunk(handle: u32, sequence: u16, data: Vec<u8>) -> Message {
+        trace("APP1")
+            .arg(SEGMENT_CHUNK)
+            .arg(handle)
+            .arg(sequence)
+            .arg(data)
+            .build()
+            .unwrap()
+    }
+
+    fn end(handle: u32) -> Message {
+        trace("APP1").arg(SEGMENT_END).arg(handle).build().unwrap()
+    }
+
+    fn log(text: &str) -> Message {
+        MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .arg(text)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reassemble_segments() {
+        let mut reassembler = SegmentReassembler::new();
+        assert!(reassembler.push(start(1, 3)).is_empty());
+        // segments of another handle and other messages are interleaved
+        assert!(reassembler.push(start(2, 1)).is_empty());
+        assert_eq!(
+            vec![Reassembled::Message(log("between"))],
+            reassembler.push(log("between"))
+        );
+        assert!(reassembler.push(chunk(1, 1, vec![3, 4])).is_empty());
+        assert!(reassembler.push(chunk(1, 0, vec![1, 2])).is_empty());
+        assert!(reassembler.push(chunk(1, 2, vec![5])).is_empty());
+        assert_eq!(2, reassembler.pending());
+
+        let complete = match reassembler.push(end(1)).as_slice() {
+            [Reassembled::Complete(msg)] => msg.clone(),
+            res => panic!("expected complete message, got {:?}", res),
+        };
+        assert_eq!(1, reassembler.pending());
+        let frame = complete
+            .network_trace()
+            .expect("not a network trace")
+            .expect("invalid network trace");
+        assert_eq!(NetworkTraceType::Can, frame.trace_type);
+        assert_eq!(vec![0xCA, 0xFE], frame.header);
+        assert_eq!(vec![1, 2, 3, 4, 5], frame.payload);
+
+        // the reassembled message is a valid dlt message
+        let bytes = complete.as_bytes();
+        match dlt_message(&bytes, None, 0, None, None, StorageHeaderMode::Absent) {
+            Ok((rest, ParsedMessage::Item(parsed))) => {
+                assert!(rest.is_empty());
+                assert_eq!(complete, parsed);
+            }
+            res => panic!("could not parse reassembled message: {:?}", res),
+        }
+
+        // segments without start are passed on
+        let unknown = chunk(7, 0, vec![1]);
+        assert_eq!(
+            vec![Reassembled::Message(unknown.clone())],
+            reassembler.push(unknown)
+        );
+    }
+
+    #[test]
+    fn test_incomplete_sequences() {
+        let mut reassembler = SegmentReassembler::new();
+        reassembler.push(start(1, 2));
+        reassembler.push(chunk(1, 1, vec![3, 4]));
+        match reassembler.push(end(1)).as_slice() {
+            [Reassembled::Incomplete(sequence)] => assert_eq!(vec![0], sequence.missing_segments),
+            res => panic!("expected incomplete sequence, got {:?}", res),
+        }
+
+        let mut reassembler = SegmentReassembler::new().with_timeout(2);
+        reassembler.push(start(1, 2));
+        reassembler.push(log("one"));
+        reassembler.push(log("two"));
+        match reassembler.push(log("three")).as_slice() {
+            [Reassembled::Incomplete(sequence), Reassembled::Message(_)] => {
+                assert_eq!(vec![0, 1], sequence.missing_segments)
+            }
+            res => panic!("expected timed out sequence, got {:?}", res),
+        }
+        assert_eq!(0, reassembler.pending());
+    }
+
+    #[test]
+    fn test_segment_beyond_segment_count_is_passed_on() {
+        let mut reassembler = SegmentReassembler::new();
+        reassembler.push(start(1, 2));
+        reassembler.push(chunk(1, 0, vec![1, 2]));
+        let beyond = chunk(1, 2, vec![9, 9]);
+        assert_eq!(
+            vec![Reassembled::Message(beyond.clone())],
+            reassembler.push(beyond)
+        );
+        assert_eq!(4, reassembler.buffered_bytes());
+        match reassembler.push(end(1)).as_slice() {
+            [Reassembled::Incomplete(sequence)] => {
+                assert_eq!(vec![1], sequence.missing_segments);
+                let frame = sequence
+                    .message
+                    .network_trace()
+                    .expect("not a network trace")
+                    .expect("invalid network trace");
+                assert_eq!(vec![1, 2], frame.payload);
+            }
+            res => panic!("expected incomplete sequence, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_oversized_sequence() {
+        let mut reassembler = SegmentReassembler::new();
+        reassembler.push(start(1, 3));
+        for sequence in 0..3u16 {
+            reassembler.push(chunk(1, sequence, vec![sequence as u8; 30_000]));
+        }
+        assert_eq!(2 + 90_000, reassembler.buffered_bytes());
+        match reassembler.push(end(1)).as_slice() {
+            [Reassembled::Oversized(sequence)] => {
+                assert_eq!(start(1, 3), sequence.start);
+                assert_eq!(vec![0xCA, 0xFE], sequence.header);
+                assert_eq!(90_000, sequence.data.len());
+                assert_eq!(vec![2u8; 30_000], sequence.data[60_000..].to_vec());
+                assert!(sequence.missing_segments.is_empty());
+            }
+            res => panic!("expected oversized sequence, got {:?}", res),
+        }
+        assert_eq!(0, reassembler.buffered_bytes());
+    }
+
+    #[test]
+    fn test_buffer_limit_gives_up_oldest_sequences() {
+        let mut reassembler = SegmentReassembler::new().with_max_buffered_bytes(10);
+        reassembler.push(start(1, 2));
+        reassembler.push(chunk(1, 0, vec![1; 6]));
+        reassembler.push(start(2, 1));
+        assert_eq!(10, reassembler.buffered_bytes());
+        match reassembler.push(chunk(2, 0, vec![2; 4])).as_slice() {
+            [Reassembled::Incomplete(sequence)] => {
+                assert_eq!(vec![1], sequence.missing_segments);
+                let frame = sequence.message.network_trace().unwrap().unwrap();
+                assert_eq!(vec![1; 6], frame.payload);
+            }
+            res => panic!("expected the first sequence to be given up, got {:?}", res),
+        }
+        assert_eq!(1, reassembler.pending());
+        assert_eq!(6, reassembler.buffered_bytes());
+        assert!(matches!(
+            reassembler.push(end(2)).as_slice(),
+            [Reassembled::Complete(_)]
+        ));
+        assert_eq!(0, reassembler.buffered_bytes());
+    }
+
+    #[test]
+    fn test_partial_sequence_at_end_of_input() {
+        let mut bytes = vec![];
+        for msg in &[start(1, 2), chunk(1, 0, vec![1, 2]), log("last")] {
+            bytes.extend(msg.clone().add_storage_header(None).as_bytes());
+        }
+        let results: Vec<Reassembled> =
+            reassemble_segments(messages(&bytes), SegmentReassembler::new())
+                .collect::<Result<_, _>>()
+                .expect("could not parse messages");
+        assert_eq!(2, results.len());
+        assert!(matches!(&results[0], Reassembled::Message(_)));
+        match &results[1] {
+            Reassembled::Incomplete(sequence) => {
+                assert_eq!(vec![1], sequence.missing_segments);
+                let frame = sequence.message.network_trace().unwrap().unwrap();
+                assert_eq!(vec![1, 2], frame.payload);
+            }
+            res => panic!("expected incomplete sequence, got {:?}", res),
+        }
+    }
+}
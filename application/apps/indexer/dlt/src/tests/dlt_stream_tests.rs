@@ -0,0 +1,252 @@
+#[cfg(test)]
+mod tests {
+    use crate::{dlt::*, dlt_parse::StorageHeaderMode, dlt_stream::*};
+    use pretty_assertions::assert_eq;
+
+    fn message(counter: u8, text: &str, mode: StorageHeaderMode) -> Message {
+        let payload = Payload2 {
+            payload_content: PayloadContent::Verbose(vec![Argument {
+                type_info: TypeInfo {
+                    kind: TypeInfoKind::StringType,
+                    coding: StringCoding::UTF8,
+                    has_variable_info: false,
+                    has_trace_info: false,
+                    unmodelled_bits: 0,
+                },
+                name: None,
+                unit: None,
+                fixed_point: None,
+                value: Value::StringVal(text.to_string()),
+            }]),
+        };
+        let storage_header = if mode == StorageHeaderMode::Required {
+            Some(StorageHeader {
+                timestamp: DltTimeStamp {
+                    seconds: 1_500_000_000 + u32::from(counter),
+                    microseconds: 0,
+                },
+                ecu_id: "ECU".to_string(),
+            })
+        } else {
+            None
+        };
+        Message::new(
+            MessageConfig {
+                version: 1,
+                endianness: Endianness::Big,
+                counter,
+                ecu_id: Some("ECU".to_string()),
+                session_id: None,
+                timestamp: Some(u32::from(counter) * 1000),
+                payload,
+                extended_header_info: Some(ExtendedHeaderConfig {
+                    message_type: MessageType::Log(LogLevel::Info),
+                    app_id: "APP".to_string(),
+                    context_id: "CTX".to_string(),
+                }),
+            },
+            None,
+            storage_header,
+        )
+    }
+
+    fn messages(mode: StorageHeaderMode) -> Vec<Message> {
+        (0..20u8)
+            .map(|i| message(i, &"x".repeat(i as usize * 7), mode))
+            .collect()
+    }
+
+    fn feed_in_chunks(
+        bytes: &[u8],
+        chunk_size: usize,
+        mode: StorageHeaderMode,
+    ) -> (Vec<Message>, usize, usize) {
+        let mut parser = DltStreamParser::new(mode, None, None);
+        let mut parsed = vec![];
+        let mut consumed = 0;
+        let mut buffered = 0;
+        for chunk in bytes.chunks(chunk_size) {
+            let res = parser.feed(chunk);
+            parsed.extend(res.messages);
+            consumed += res.consumed;
+            buffered = res.buffered;
+            assert_eq!(buffered, parser.buffered());
+        }
+        (parsed, consumed, buffered)
+    }
+
+    #[test]
+    fn test_messages_split_over_chunks() {
+        for &mode in &[StorageHeaderMode::Required, StorageHeaderMode::Absent] {
+            let expected = messages(mode);
+            let bytes: Vec<u8> = expected.iter().flat_map(|m| m.as_bytes()).collect();
+            for &chunk_size in &[1usize, 3, 17, 64, 65536] {
+                let (parsed, consumed, buffered) = feed_in_chunks(&bytes, chunk_size, mode);
+                assert_eq!(expected, parsed);
+                assert_eq!(bytes.len(), consumed);
+                assert_eq!(0, buffered);
+            }
+        }
+    }
+
+    #[test]
+    fn test_optional_storage_headers_in_stream() {
+        let expected: Vec<Message> = (0..10u8)
+            .map(|i| {
+                let mode = if i % 3 == 0 {
+                    StorageHeaderMode::Required
+                } else {
+                    StorageHeaderMode::Absent
+                };
+                message(i, "mixed", mode)
+            })
+            .collect();
+        let bytes: Vec<u8> = expected.iter().flat_map(|m| m.as_bytes()).collect();
+        for &chunk_size in &[1usize, 5, 1024] {
+            let (parsed, consumed, buffered) =
+                feed_in_chunks(&bytes, chunk_size, StorageHeaderMode::Optional);
+            assert_eq!(expected, parsed);
+            assert_eq!(bytes.len(), consumed);
+            assert_eq!(0, buffered);
+        }
+    }
+
+    #[test]
+    fn test_incomplete_message_is_buffered() {
+        let msg = message(1, "incomplete", StorageHeaderMode::Absent);
+        let bytes = msg.as_bytes();
+        let mut parser = DltStreamParser::new(StorageHeaderMode::Absent, None, None);
+        let res = parser.feed(&bytes[..bytes.len() - 1]);
+        assert!(res.messages.is_empty());
+        assert_eq!(0, res.consumed);
+        assert_eq!(bytes.len() - 1, res.buffered);
+        let res = parser.feed(&bytes[bytes.len() - 1..]);
+        assert_eq!(vec![msg], res.messages);
+        assert_eq!(bytes.len(), res.consumed);
+        assert_eq!(0, res.buffered);
+    }
+
+    #[test]
+    fn test_garbage_between_messages() {
+        let expected = messages(StorageHeaderMode::Required);
+        let mut bytes = vec![];
+        for msg in &expected {
+            bytes.extend(b"garbage");
+            bytes.extend(msg.as_bytes());
+        }
+        let (parsed, consumed, buffered) = feed_in_chunks(&bytes, 100, StorageHeaderMode::Required);
+        assert_eq!(expected, parsed);
+        assert_eq!(bytes.len(), consumed);
+        assert_eq!(0, buffered);
+    }
+
+    #[test]
+    fn test_skip_messages_above_max_message_size() {
+        let small = message(1, "small", StorageHeaderMode::Required);
+        let big = message(2, &"x".repeat(500), StorageHeaderMode::Required);
+        let mut bytes = small.as_bytes();
+        bytes.extend(big.as_bytes());
+        bytes.extend(small.as_bytes());
+        let mut parser = DltStreamParser::new(StorageHeaderMode::Required, None, None)
+            .with_max_message_size(100);
+        // the big message is dropped as soon as its header is seen
+        let res = parser.feed(&bytes[..small.as_bytes().len() + 30]);
+        assert_eq!(vec![small.clone()], res.messages);
+        // only a possible start of the next storage header is kept
+        assert!(res.buffered < 4);
+        let res = parser.feed(&bytes[small.as_bytes().len() + 30..]);
+        assert_eq!(vec![small], res.messages);
+        assert_eq!(0, res.buffered);
+    }
+
+    /// chunks as they might come from a ring buffer, followed by an error
+    struct ChunkedSource {
+        chunks: Vec<Vec<u8>>,
+        current: Vec<u8>,
+    }
+
+    impl DltSource for ChunkedSource {
+        fn next_chunk(&mut self) -> std::io::Result<&[u8]> {
+            if self.chunks.is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "device removed",
+                ));
+            }
+            self.current = self.chunks.remove(0);
+            Ok(&self.current)
+        }
+    }
+
+    #[test]
+    fn test_messages_from_sources() {
+        let mode = StorageHeaderMode::Required;
+        let expected = messages(mode);
+        let bytes: Vec<u8> = expected.iter().flat_map(|m| m.as_bytes()).collect();
+        let parser = || DltStreamParser::new(mode, None, None);
+
+        let parsed: Vec<Message> = parser()
+            .messages(&bytes[..])
+            .collect::<Result<_, _>>()
+            .expect("could not read slice");
+        assert_eq!(expected, parsed);
+
+        let reader = std::io::BufReader::with_capacity(10, &bytes[..]);
+        let parsed: Vec<Message> = parser()
+            .messages(BufReadSource::new(reader))
+            .collect::<Result<_, _>>()
+            .expect("could not read reader");
+        assert_eq!(expected, parsed);
+
+        let source = ChunkedSource {
+            chunks: bytes[..bytes.len() - 3]
+                .chunks(33)
+                .map(|c| c.to_vec())
+                .collect(),
+            current: vec![],
+        };
+        let mut source_messages = parser().messages(source);
+        let parsed: Vec<Message> = source_messages
+            .by_ref()
+            .take_while(Result::is_ok)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(&expected[..expected.len() - 1], &parsed[..]);
+        assert_eq!(
+            expected.last().unwrap().as_bytes().len() - 3,
+            source_messages.parser().buffered()
+        );
+        assert!(source_messages.next().is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_codec_retains_partial_frames() {
+        use crate::dlt_codec::DltCodec;
+        use tokio_util::codec::Decoder;
+
+        let mode = StorageHeaderMode::Required;
+        let expected = messages(mode);
+        let mut bytes = b"noise".to_vec();
+        bytes.extend(expected.iter().flat_map(|m| m.as_bytes()));
+        let truncated = expected[0].as_bytes();
+        bytes.extend(&truncated[..truncated.len() - 1]);
+
+        let mut codec = DltCodec::new(mode, None, None);
+        let mut buffer = bytes1::BytesMut::new();
+        let mut decoded = vec![];
+        for chunk in bytes.chunks(13) {
+            buffer.extend_from_slice(chunk);
+            while let Some(msg) = codec.decode(&mut buffer).expect("decoding failed") {
+                decoded.push(msg);
+            }
+        }
+        assert_eq!(expected, decoded);
+        assert_eq!(truncated.len() - 1, buffer.len());
+        assert_eq!(
+            None,
+            codec.decode_eof(&mut buffer).expect("decoding failed")
+        );
+        assert!(buffer.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use crate::{dlt::*, proptest_strategies::argument_strategy};
+    use crate::{
+        dlt::*,
+        dlt_parse::{dlt_argument, dlt_argument_ref, dlt_standard_header},
+        proptest_strategies::argument_strategy,
+    };
     use byteorder::ByteOrder;
     use proptest::prelude::*;
 
@@ -26,6 +30,13 @@ mod tests {
                 calculated
             );
         }
+        #[test]
+        fn type_info_raw_is_the_written_type_info(arg in argument_strategy()) {
+            let raw = arg.type_info_raw();
+            assert_eq!(raw, BigEndian::read_u32(&arg.as_bytes::<BigEndian>()[..4]));
+            assert_eq!(raw, LittleEndian::read_u32(&arg.as_bytes::<LittleEndian>()[..4]));
+            assert_eq!(Some(arg.type_info.clone()), TypeInfo::try_from(raw).ok());
+        }
     }
 
     // string coding .......................^^.^|||      ||||
@@ -79,6 +90,95 @@ mod tests {
         assert!(extended_header.skip_with_level(LogLevel::Invalid(2)));
     }
     #[test]
+    fn test_log_level_ordering() {
+        let levels = vec![
+            LogLevel::Invalid(7),
+            LogLevel::Verbose,
+            LogLevel::Debug,
+            LogLevel::Invalid(0),
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Error,
+            LogLevel::Fatal,
+        ];
+        let mut sorted = levels.clone();
+        sorted.sort();
+        assert_eq!(
+            vec![
+                LogLevel::Invalid(0),
+                LogLevel::Fatal,
+                LogLevel::Error,
+                LogLevel::Warn,
+                LogLevel::Info,
+                LogLevel::Debug,
+                LogLevel::Verbose,
+                LogLevel::Invalid(7),
+            ],
+            sorted
+        );
+        assert!(LogLevel::Fatal.meets_threshold(LogLevel::Warn));
+        assert!(LogLevel::Warn.meets_threshold(LogLevel::Warn));
+        assert!(!LogLevel::Info.meets_threshold(LogLevel::Warn));
+        assert_eq!(6, LogLevel::Verbose.value());
+    }
+    #[test]
+    fn test_message_type_from_message_info() {
+        // MSIN: type info in bits 4-7, message type in bits 1-3, verbose in bit 0
+        let cases = vec![
+            (0x31, MessageType::Log(LogLevel::Warn)),
+            (
+                0x12,
+                MessageType::ApplicationTrace(ApplicationTraceType::Variable),
+            ),
+            (
+                0x22,
+                MessageType::ApplicationTrace(ApplicationTraceType::FunctionIn),
+            ),
+            (
+                0x33,
+                MessageType::ApplicationTrace(ApplicationTraceType::FunctionOut),
+            ),
+            (
+                0x42,
+                MessageType::ApplicationTrace(ApplicationTraceType::State),
+            ),
+            (
+                0x52,
+                MessageType::ApplicationTrace(ApplicationTraceType::Vfb),
+            ),
+            (0x24, MessageType::NetworkTrace(NetworkTraceType::Can)),
+            (0x26, MessageType::Control(ControlType::Response)),
+        ];
+        for (message_info, expected) in cases {
+            let message_type = MessageType::try_from(message_info).expect("invalid message info");
+            assert_eq!(expected, message_type, "message info {:#x}", message_info);
+            assert_eq!(message_info & !1, u8::from(&message_type));
+
+            let msg = crate::dlt_builder::MessageBuilder::new("ECU1", "APP1", "CTX1")
+                .message_type(message_type)
+                .arg(1u8)
+                .build()
+                .unwrap();
+            let bytes = msg.as_bytes();
+            let (_, parsed) = crate::dlt_parse::dlt_message(
+                &bytes,
+                None,
+                0,
+                None,
+                None,
+                crate::dlt_parse::StorageHeaderMode::Absent,
+            )
+            .expect("could not parse message");
+            match parsed {
+                crate::dlt_parse::ParsedMessage::Item(parsed) => {
+                    assert_eq!(Some(expected), parsed.message_type());
+                    assert!(parsed.is_verbose());
+                }
+                p => panic!("unexpected {:?}", p),
+            }
+        }
+    }
+    #[test]
     fn test_filter_out_non_relevant_ext_headers() {
         let extended_header = ExtendedHeader {
             argument_count: 1,
@@ -155,18 +255,21 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let type_info2 = TypeInfo {
             kind: TypeInfoKind::UnsignedFixedPoint(FloatWidth::Width32),
             coding: StringCoding::ASCII,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let type_info3 = TypeInfo {
             kind: TypeInfoKind::StringType,
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         //                                                        vvvv..type lenght
         // type array .....................................v      ||||
@@ -216,12 +319,101 @@ mod tests {
         );
     }
     #[test]
+    fn test_argument_type_info_accessors() {
+        let arg = Argument {
+            type_info: TypeInfo {
+                kind: TypeInfoKind::StringType,
+                coding: StringCoding::UTF8,
+                has_variable_info: true,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            name: Some("name".to_string()),
+            unit: None,
+            fixed_point: None,
+            value: Value::StringVal("value".to_string()),
+        };
+        //                    SCOD.vv  VARI.v STRG.v
+        assert_eq!(
+            0b0000_0000_0000_0000_1000_1010_0000_0000,
+            arg.type_info_raw()
+        );
+        assert!(arg.has_variable_info());
+        assert!(!arg.has_trace_info());
+        assert!(!arg.is_fixed_point());
+        assert_eq!(&StringCoding::UTF8, arg.string_coding());
+    }
+    #[test]
+    fn test_type_info_raw_keeps_unmodelled_bits() {
+        //        reserved bit 20 | UTF8 | STRU | STRG
+        let raw: u32 = 0x0010_0000 | 0x0000_8000 | 0x0000_4000 | 0x0000_0200;
+        let mut bytes = raw.to_be_bytes().to_vec();
+        bytes.extend(&6u16.to_be_bytes());
+        bytes.extend(b"hello\0");
+        let (rest, arg) = dlt_argument::<BigEndian>(&bytes).expect("could not parse argument");
+        assert!(rest.is_empty());
+        assert_eq!(TypeInfoKind::StringType, arg.type_info.kind);
+        assert_eq!(Value::StringVal("hello".to_string()), arg.value);
+        assert_eq!(raw, arg.type_info_raw());
+        // the argument is written again as it was read
+        assert_eq!(bytes, arg.as_bytes::<BigEndian>());
+        let (_, arg_ref) = dlt_argument_ref::<BigEndian>(&bytes).expect("could not parse argument");
+        assert_eq!(raw, arg_ref.type_info_raw());
+        // a type info without such bits is the same as one that was built
+        let plain = TypeInfo::try_from(0x0000_8200).expect("invalid type info");
+        assert_eq!(0, plain.unmodelled_bits);
+        assert_eq!(
+            TypeInfo {
+                kind: TypeInfoKind::StringType,
+                coding: StringCoding::UTF8,
+                has_variable_info: false,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            plain
+        );
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_display_argument_with_variable_info() {
+        let mut arg = argument(
+            TypeInfoKind::Unsigned(TypeLength::BitLength16),
+            Value::U16(42),
+        );
+        arg.type_info.has_variable_info = true;
+        arg.name = Some("speed".to_string());
+        arg.unit = Some("mph".to_string());
+        assert_eq!("speed: 42 mph", arg.to_string());
+        arg.unit = None;
+        assert_eq!("speed: 42", arg.to_string());
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_display_values() {
+        assert_eq!("true", Value::Bool(1).to_string());
+        assert_eq!("false", Value::Bool(0).to_string());
+        assert_eq!("-17", Value::I32(-17).to_string());
+        assert_eq!("0.1", Value::F32(0.1).to_string());
+        assert_eq!("3.14159", Value::F64(std::f64::consts::PI).to_string());
+        assert_eq!("1e+20", Value::F64(1e20).to_string());
+        assert_eq!("hello", Value::StringVal("hello".to_string()).to_string());
+
+        let raw = Value::Raw(vec![0x0A, 0xFF, 0x00]);
+        assert_eq!("0AFF00", raw.to_string());
+        assert_eq!("0A FF 00", format!("{:#}", raw));
+        assert_eq!("", Value::Raw(vec![]).to_string());
+        let mut arg = argument(TypeInfoKind::Raw, raw);
+        arg.name = Some("data".to_string());
+        assert_eq!("data: 0A FF 00", format!("{:#}", arg));
+    }
+    #[test]
     fn test_convert_bool_argument_to_bytes() {
         let type_info = TypeInfo {
             kind: TypeInfoKind::Bool,
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let argument = Argument {
             type_info: type_info.clone(),
@@ -240,8 +432,9 @@ mod tests {
         let type_info2 = TypeInfo {
             kind: TypeInfoKind::Bool,
             coding: StringCoding::UTF8,
-            has_variable_info: true,
+            has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected2 = type_info2.as_bytes::<BigEndian>();
         let argument2 = Argument {
@@ -261,6 +454,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -285,6 +479,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -307,6 +502,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -331,6 +527,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -353,6 +550,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -377,6 +575,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -392,12 +591,73 @@ mod tests {
         assert_eq!(expected, argument.as_bytes::<BigEndian>());
     }
     #[test]
+    fn test_half_precision_floats() {
+        let vectors: &[(u16, f32)] = &[
+            (0x0000, 0.0),
+            (0x3c00, 1.0),
+            (0xc000, -2.0),
+            (0x3555, 0.333_251_95),
+            (0x3bff, 0.999_511_7),
+            (0x7bff, 65504.0),        // biggest normal value
+            (0x0400, 6.103_515_6e-5), // smallest normal value
+            (0x03ff, 6.097_555e-5),   // biggest subnormal value
+            (0x0001, 5.960_464_5e-8), // smallest subnormal value
+            (0x8001, -5.960_464_5e-8),
+            (0x7c00, f32::INFINITY),
+            (0xfc00, f32::NEG_INFINITY),
+        ];
+        for &(bits, value) in vectors {
+            assert_eq!(value, f16_to_f32(bits), "decoding {:#06x}", bits);
+            assert_eq!(bits, f32_to_f16(value), "encoding {}", value);
+        }
+        let negative_zero = f16_to_f32(0x8000);
+        assert!(negative_zero == 0.0 && negative_zero.is_sign_negative());
+        assert_eq!(0x8000, f32_to_f16(-0.0));
+        assert!(f16_to_f32(0x7e00).is_nan());
+        assert!(f16_to_f32(0xfd01).is_nan());
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+
+        // values in between are rounded to the nearest, ties to even
+        assert_eq!(0x3c00, f32_to_f16(1.0 + 1.0 / 2048.0));
+        assert_eq!(0x3c02, f32_to_f16(1.0 + 3.0 / 2048.0));
+        assert_eq!(0x3c01, f32_to_f16(1.0 + 1.0 / 2048.0 + 1.0 / 65536.0));
+        assert_eq!(0x7bff, f32_to_f16(65519.0));
+        assert_eq!(0x7c00, f32_to_f16(65520.0));
+        assert_eq!(0x0000, f32_to_f16(2.980_232_2e-8)); // half of the smallest subnormal
+        assert_eq!(0x0001, f32_to_f16(4.470_348_4e-8));
+        assert_eq!(0x0400, f32_to_f16(6.102_e-5)); // rounds up to a normal value
+        assert_eq!(0x0000, f32_to_f16(1e-10));
+    }
+
+    #[test]
+    fn test_convert_half_float_argument_to_bytes() {
+        let type_info = TypeInfo {
+            kind: TypeInfoKind::Float(FloatWidth::Width16),
+            coding: StringCoding::UTF8,
+            has_variable_info: false,
+            has_trace_info: false,
+            unmodelled_bits: 0,
+        };
+        let mut expected = type_info.as_bytes::<LittleEndian>();
+        let argument = Argument {
+            type_info,
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value: Value::F16(-2.0),
+        };
+        expected.extend(vec![0x00, 0xc0]);
+        assert_eq!(expected, argument.as_bytes::<LittleEndian>());
+        assert_eq!(expected.len(), argument.len_new());
+    }
+    #[test]
     fn test_convert_string_argument_to_bytes() {
         let type_info = TypeInfo {
             kind: TypeInfoKind::StringType,
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -419,6 +679,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -441,6 +702,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -465,6 +727,16 @@ mod tests {
         BigEndian::write_i32(&mut buf, -44);
         expected.extend(&buf); // value
         assert_eq!(expected, argument.as_bytes::<BigEndian>());
+        // the scaled value is written as the transmitted integer
+        let scaled = Argument {
+            value: argument
+                .fixed_point
+                .as_ref()
+                .unwrap()
+                .scale(Value::I32(-44)),
+            ..argument.clone()
+        };
+        assert_eq!(expected, scaled.as_bytes::<BigEndian>());
 
         // now without variable info
         let type_info = TypeInfo {
@@ -472,6 +744,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -494,12 +767,48 @@ mod tests {
         assert_eq!(expected, argument.as_bytes::<BigEndian>());
     }
     #[test]
+    #[cfg(feature = "std")]
+    fn test_fixed_point_scaling() {
+        let fixed_point = FixedPoint {
+            quantization: 0.25,
+            offset: FixedPointValue::I64(-40),
+        };
+        assert_eq!(
+            Value::FixedPoint {
+                raw: 200,
+                quantization: 0.25,
+                offset: -40,
+                scaled: 10.0,
+            },
+            fixed_point.scale(Value::U32(200))
+        );
+        match fixed_point.scale(Value::U64(u64::MAX)) {
+            Value::FixedPoint { raw, scaled, .. } => {
+                assert_eq!(-1, raw);
+                assert_eq!(u64::MAX as f64 * 0.25 - 40.0, scaled);
+            }
+            v => panic!("no fixed point value: {:?}", v),
+        }
+        assert_eq!(Value::Bool(1), fixed_point.scale(Value::Bool(1)));
+
+        let mut arg = argument(
+            TypeInfoKind::UnsignedFixedPoint(FloatWidth::Width32),
+            fixed_point.scale(Value::U32(230)),
+        );
+        arg.fixed_point = Some(fixed_point);
+        arg.type_info.has_variable_info = true;
+        arg.name = Some("temperature".to_string());
+        arg.unit = Some("C".to_string());
+        assert_eq!("temperature: 17.5 C", arg.to_string());
+    }
+    #[test]
     fn test_convert_raw_argument_to_bytes() {
         let type_info = TypeInfo {
             kind: TypeInfoKind::Raw,
             coding: StringCoding::UTF8,
             has_variable_info: true,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -524,6 +833,7 @@ mod tests {
             coding: StringCoding::UTF8,
             has_variable_info: false,
             has_trace_info: false,
+            unmodelled_bits: 0,
         };
         let mut expected = type_info.as_bytes::<BigEndian>();
         let argument = Argument {
@@ -540,4 +850,430 @@ mod tests {
         expected.extend(vec![0xD, 0xE, 0xA, 0xD]);
         assert_eq!(expected, argument.as_bytes::<BigEndian>());
     }
+
+    fn network_trace_message(
+        message_type: MessageType,
+        endianness: Endianness,
+        payload_content: PayloadContent,
+    ) -> Message {
+        Message::new(
+            MessageConfig {
+                version: 1,
+                counter: 0,
+                endianness,
+                ecu_id: Some("ECU".to_string()),
+                session_id: None,
+                timestamp: None,
+                payload: Payload2 { payload_content },
+                extended_header_info: Some(ExtendedHeaderConfig {
+                    message_type,
+                    app_id: "APP".to_string(),
+                    context_id: "CTX".to_string(),
+                }),
+            },
+            None,
+            None,
+        )
+    }
+
+    fn raw_argument(bytes: &[u8]) -> Argument {
+        Argument {
+            type_info: TypeInfo {
+                kind: TypeInfoKind::Raw,
+                coding: StringCoding::ASCII,
+                has_variable_info: false,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value: Value::Raw(bytes.to_vec()),
+        }
+    }
+
+    #[test]
+    fn test_arg_count_saturates() {
+        let payload = |count: usize| Payload2 {
+            payload_content: PayloadContent::Verbose(vec![raw_argument(&[]); count]),
+        };
+        assert_eq!(3, payload(3).arg_count());
+        assert_eq!(255, payload(255).arg_count());
+        assert_eq!(255, payload(256).arg_count());
+    }
+
+    #[test]
+    fn test_network_trace_of_verbose_message() {
+        let can = MessageType::NetworkTrace(NetworkTraceType::Can);
+        let msg = network_trace_message(
+            can.clone(),
+            Endianness::Little,
+            PayloadContent::Verbose(vec![
+                raw_argument(&[0x00, 0x00, 0x01, 0x23]),
+                raw_argument(&[0xDE, 0xAD]),
+            ]),
+        );
+        assert_eq!(
+            Some(Ok(NetworkTrace {
+                trace_type: NetworkTraceType::Can,
+                header: vec![0x00, 0x00, 0x01, 0x23],
+                payload: vec![0xDE, 0xAD],
+            })),
+            msg.network_trace()
+        );
+
+        let msg = network_trace_message(
+            can.clone(),
+            Endianness::Little,
+            PayloadContent::Verbose(vec![raw_argument(&[0xDE, 0xAD])]),
+        );
+        assert_eq!(
+            Some(Ok(NetworkTrace {
+                trace_type: NetworkTraceType::Can,
+                header: vec![],
+                payload: vec![0xDE, 0xAD],
+            })),
+            msg.network_trace()
+        );
+
+        let mut string_arg = raw_argument(&[]);
+        string_arg.type_info.kind = TypeInfoKind::StringType;
+        string_arg.value = Value::StringVal("no frame".to_string());
+        let msg = network_trace_message(
+            can,
+            Endianness::Little,
+            PayloadContent::Verbose(vec![string_arg]),
+        );
+        assert!(msg.network_trace().expect("is a network trace").is_err());
+    }
+
+    #[test]
+    fn test_network_trace_of_non_verbose_message() {
+        // header length 2, header, payload length 3, payload
+        let bytes = [0x00, 0x02, 0x12, 0x34, 0x00, 0x03, 0x0A, 0x0B, 0x0C];
+        for &endianness in &[Endianness::Big, Endianness::Little] {
+            let mut bytes = bytes.to_vec();
+            if endianness == Endianness::Little {
+                bytes.swap(0, 1);
+                bytes.swap(4, 5);
+            }
+            let msg_id = if endianness == Endianness::Big {
+                BigEndian::read_u32(&bytes)
+            } else {
+                LittleEndian::read_u32(&bytes)
+            };
+            let msg = network_trace_message(
+                MessageType::NetworkTrace(NetworkTraceType::Ethernet),
+                endianness,
+                PayloadContent::NonVerbose(msg_id, bytes[4..].to_vec()),
+            );
+            assert_eq!(
+                Some(Ok(NetworkTrace {
+                    trace_type: NetworkTraceType::Ethernet,
+                    header: vec![0x12, 0x34],
+                    payload: vec![0x0A, 0x0B, 0x0C],
+                })),
+                msg.network_trace()
+            );
+        }
+        let truncated = network_trace_message(
+            MessageType::NetworkTrace(NetworkTraceType::Ethernet),
+            Endianness::Big,
+            PayloadContent::NonVerbose(0x0002_1234, vec![0x00, 0x05, 0x0A]),
+        );
+        assert!(truncated
+            .network_trace()
+            .expect("is a network trace")
+            .is_err());
+    }
+
+    #[test]
+    fn test_no_network_trace() {
+        let msg = network_trace_message(
+            MessageType::Log(LogLevel::Info),
+            Endianness::Big,
+            PayloadContent::Verbose(vec![raw_argument(&[0xDE, 0xAD])]),
+        );
+        assert_eq!(None, msg.network_trace());
+    }
+
+    #[test]
+    fn test_verbose_and_message_type() {
+        use crate::dlt_builder::MessageBuilder;
+        let verbose = MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .log_level(LogLevel::Debug)
+            .arg("value")
+            .build()
+            .unwrap();
+        assert!(verbose.is_verbose());
+        assert_eq!(
+            Some(MessageType::Log(LogLevel::Debug)),
+            verbose.message_type()
+        );
+
+        let non_verbose = MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .message_type(MessageType::ApplicationTrace(ApplicationTraceType::State))
+            .non_verbose(1, vec![])
+            .build()
+            .unwrap();
+        assert!(!non_verbose.is_verbose());
+        assert_eq!(
+            Some(MessageType::ApplicationTrace(ApplicationTraceType::State)),
+            non_verbose.message_type()
+        );
+
+        let mut without_extended_header = verbose;
+        without_extended_header.extended_header = None;
+        assert!(!without_extended_header.is_verbose());
+        assert_eq!(None, without_extended_header.message_type());
+    }
+
+    #[test]
+    fn test_to_record() {
+        use crate::dlt_builder::MessageBuilder;
+        let named = Argument {
+            name: Some("speed".to_string()),
+            unit: Some("km/h".to_string()),
+            ..Argument::from(42u16)
+        };
+        let msg = MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .log_level(LogLevel::Warn)
+            .counter(7)
+            .session_id(3)
+            .timestamp(1234)
+            .storage_header(DltTimeStamp {
+                seconds: 2,
+                microseconds: 5,
+            })
+            .arg("text")
+            .arg(named)
+            .arg(-1.5f64)
+            .build()
+            .unwrap();
+        assert_eq!(
+            DltRecord {
+                time_us: Some(2_000_005),
+                timestamp: Some(1234),
+                ecu_id: Some("ECU1".to_string()),
+                app_id: Some("APP1".to_string()),
+                context_id: Some("CTX1".to_string()),
+                message_type: Some(MessageType::Log(LogLevel::Warn)),
+                log_level: Some(LogLevel::Warn),
+                counter: 7,
+                session_id: Some(3),
+                message_id: None,
+                arguments: vec![
+                    (None, Value::StringVal("text".to_string())),
+                    (Some("speed".to_string()), Value::U16(42)),
+                    (None, Value::F64(-1.5)),
+                ],
+            },
+            msg.to_record()
+        );
+
+        // without FIBEX the data of a non-verbose message is kept raw
+        let mut non_verbose = MessageBuilder::new("ECU1", "APP1", "CTX1")
+            .message_type(MessageType::Control(ControlType::Request))
+            .non_verbose(0x10, vec![1, 2])
+            .build()
+            .unwrap();
+        let record = non_verbose.to_record();
+        assert_eq!(None, record.log_level);
+        assert_eq!(None, record.time_us);
+        assert_eq!(Some(0x10), record.message_id);
+        assert_eq!(vec![(None, Value::Raw(vec![1, 2]))], record.arguments);
+
+        // the storage header fills in a missing ECU-ID
+        non_verbose.extended_header = None;
+        non_verbose.header.ecu_id = None;
+        let record = non_verbose.add_storage_header(None).to_record();
+        assert_eq!(Some(DEFAULT_ECU_ID.to_string()), record.ecu_id);
+        assert_eq!(None, record.app_id);
+        assert_eq!(None, record.message_type);
+    }
+
+    #[test]
+    fn test_standard_header_accessors() {
+        // version 1, big endian, with ECU-ID and timestamp but no session id
+        #[rustfmt::skip]
+        let bytes = [
+            0x36, 0x07, 0x00, 0x0E,
+            b'E', b'C', b'U', b'1',
+            0x00, 0x00, 0x30, 0x39, // 12345 ticks
+            0x00, 0x00,
+        ];
+        let (_, header) = dlt_standard_header(&bytes).expect("could not parse header");
+        assert_eq!(Some("ECU1"), header.ecu_id());
+        assert_eq!(None, header.session_id());
+        assert_eq!(Some(12345), header.timestamp());
+        assert_eq!(
+            Some(std::time::Duration::from_micros(1_234_500)),
+            header.timestamp_duration()
+        );
+        assert_eq!(7, header.message_counter());
+
+        // only the session id
+        let bytes = [0x28, 0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x2A, 0x00, 0x00];
+        let (_, header) = dlt_standard_header(&bytes).expect("could not parse header");
+        assert_eq!(None, header.ecu_id());
+        assert_eq!(Some(42), header.session_id());
+        assert_eq!(None, header.timestamp());
+        assert_eq!(None, header.timestamp_duration());
+    }
+
+    #[cfg(feature = "std")]
+    fn argument(kind: TypeInfoKind, value: Value) -> Argument {
+        Argument {
+            type_info: TypeInfo {
+                kind,
+                coding: StringCoding::UTF8,
+                has_variable_info: false,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_format_as_dltviewer_line() {
+        let msg = Message::new(
+            MessageConfig {
+                version: 1,
+                counter: 5,
+                endianness: Endianness::Big,
+                ecu_id: Some("ECU1".to_string()),
+                session_id: None,
+                timestamp: Some(123_456),
+                payload: Payload2 {
+                    payload_content: PayloadContent::Verbose(vec![
+                        argument(TypeInfoKind::Bool, Value::Bool(1)),
+                        argument(TypeInfoKind::Float(FloatWidth::Width32), Value::F32(123.98)),
+                        argument(
+                            TypeInfoKind::Signed(TypeLength::BitLength16),
+                            Value::I16(-7),
+                        ),
+                        raw_argument(&[0x01, 0xAB]),
+                        argument(TypeInfoKind::StringType, Value::StringVal("hello".into())),
+                    ]),
+                },
+                extended_header_info: Some(ExtendedHeaderConfig {
+                    message_type: MessageType::Log(LogLevel::Warn),
+                    app_id: "APP".to_string(),
+                    context_id: "CTX".to_string(),
+                }),
+            },
+            None,
+            Some(StorageHeader {
+                timestamp: DltTimeStamp {
+                    seconds: 1_500_000_000,
+                    microseconds: 12,
+                },
+                ecu_id: "ECU1".to_string(),
+            }),
+        );
+        assert_eq!(
+            "7 2017/07/14 02:40:00.000012 12.3456 5 ECU1 APP CTX log warn verbose 5 \
+             true 123.98 -7 01 ab hello",
+            msg.format_as_dltviewer_line(7)
+        );
+
+        let without_extended_header = Message::new(
+            MessageConfig {
+                version: 1,
+                counter: 0,
+                endianness: Endianness::Little,
+                ecu_id: None,
+                session_id: None,
+                timestamp: None,
+                payload: Payload2 {
+                    payload_content: PayloadContent::NonVerbose(42, vec![0xFF, 0x00]),
+                },
+                extended_header_info: None,
+            },
+            None,
+            None,
+        );
+        assert_eq!(
+            "0 1970/01/01 00:00:00.000000 0.0000 0      non-verbose 0 [42] ff 00",
+            without_extended_header.format_as_dltviewer_line(0)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_format_multi_line_string_as_dltviewer_line() {
+        let msg = crate::dlt_builder::MessageBuilder::new("ECU1", "APP", "CTX")
+            .arg("first\nsecond\r\nthird")
+            .build()
+            .unwrap();
+        let line = msg.format_as_dltviewer_line(0);
+        assert!(!line.contains(&['\n', '\r'][..]), "{:?}", line);
+        assert!(line.ends_with(" first second  third"), "{:?}", line);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dltviewer_float() {
+        use crate::dlt_fmt::dltviewer_float;
+        let cases: &[(f64, &str)] = &[
+            (0.0, "0"),
+            (1.0, "1"),
+            (-2.5, "-2.5"),
+            (f64::from(123.98f32), "123.98"),
+            (1.0 / 3.0, "0.333333"),
+            (999_999.4, "999999"),
+            (999_999.5, "1e+06"),
+            (1_234_567.0, "1.23457e+06"),
+            (0.0001, "0.0001"),
+            (0.000_012_345, "1.2345e-05"),
+            (1e-100, "1e-100"),
+            (f64::INFINITY, "inf"),
+            (f64::NAN, "nan"),
+        ];
+        for &(value, expected) in cases {
+            assert_eq!(expected, dltviewer_float(value), "formatting {}", value);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_csv() {
+        use crate::{dlt_builder::MessageBuilder, dlt_fmt::*};
+        let messages = vec![
+            MessageBuilder::new("ECU1", "APP1", "CTX1")
+                .log_level(LogLevel::Warn)
+                .storage_header(DltTimeStamp {
+                    seconds: 0,
+                    microseconds: 0,
+                })
+                .arg("say \"hi\", then\nleave")
+                .build()
+                .unwrap(),
+            MessageBuilder::new("EC;U", "APP2", "CTX2")
+                .message_type(MessageType::Control(ControlType::Request))
+                .build()
+                .unwrap(),
+        ];
+        let mut out = vec![];
+        assert_eq!(2, write_csv(&messages, &mut out).expect("could not write"));
+        assert_eq!(
+            "index,timestamp,ecu,app,context,log_level,message_type,payload\r\n\
+             0,1970-01-01T00:00:00Z,ECU1,APP1,CTX1,warn,log,\"say \"\"hi\"\", then\nleave\"\r\n\
+             1,,EC;U,APP2,CTX2,,control,\"\"\r\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        let mut out = vec![];
+        write_csv_with_delimiter(&messages[1..], &mut out, ';').expect("could not write");
+        assert_eq!(
+            "index;timestamp;ecu;app;context;log_level;message_type;payload\r\n\
+             0;;\"EC;U\";APP2;CTX2;;control;\"\"\r\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
 }
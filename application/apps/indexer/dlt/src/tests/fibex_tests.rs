@@ -1,13 +1,220 @@
 #[cfg(test)]
 mod tests {
-    use crate::fibex::read_fibexes;
-    use std::path::PathBuf;
+    use crate::{
+        dlt::*,
+        dlt_parse::{dlt_message, ParsedMessage, StorageHeaderMode},
+        fibex::{read_fibexes, FibexMetadata},
+    };
+    use std::{path::PathBuf, rc::Rc};
+
+    fn fibex_path() -> PathBuf {
+        PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/dlt-messages.xml"
+        ))
+    }
+
+    /// redefines frame 64 of `fibex_path` and adds frame 66
+    fn overlapping_fibex_path() -> PathBuf {
+        PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test_samples/dlt-messages-overlapping.xml"
+        ))
+    }
+
+    fn string_argument(s: &str) -> Argument {
+        Argument {
+            type_info: TypeInfo {
+                kind: TypeInfoKind::StringType,
+                coding: StringCoding::UTF8,
+                has_variable_info: false,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value: Value::StringVal(s.to_string()),
+        }
+    }
+
+    fn i64_argument(v: i64) -> Argument {
+        Argument {
+            type_info: TypeInfo {
+                kind: TypeInfoKind::Signed(TypeLength::BitLength64),
+                coding: StringCoding::ASCII,
+                has_variable_info: false,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value: Value::I64(v),
+        }
+    }
+
+    fn raw_argument(data: &[u8]) -> Argument {
+        Argument {
+            type_info: TypeInfo {
+                kind: TypeInfoKind::Raw,
+                coding: StringCoding::ASCII,
+                has_variable_info: false,
+                has_trace_info: false,
+                unmodelled_bits: 0,
+            },
+            name: None,
+            unit: None,
+            fixed_point: None,
+            value: Value::Raw(data.to_vec()),
+        }
+    }
+
+    /// serialize a non-verbose message and parse it again with the FIBEX metadata
+    fn parse_non_verbose(fibex: FibexMetadata, message_id: u32, data: Vec<u8>) -> Message {
+        let msg = Message::new(
+            MessageConfig {
+                version: 1,
+                endianness: Endianness::Little,
+                counter: 0,
+                ecu_id: Some("ECU1".to_string()),
+                session_id: None,
+                timestamp: None,
+                payload: Payload2 {
+                    payload_content: PayloadContent::NonVerbose(message_id, data),
+                },
+                extended_header_info: Some(ExtendedHeaderConfig {
+                    message_type: MessageType::Log(LogLevel::Warn),
+                    app_id: "DR".to_string(),
+                    context_id: "CTX1".to_string(),
+                }),
+            },
+            None,
+            None,
+        );
+        match dlt_message(
+            &msg.as_bytes(),
+            None,
+            0,
+            None,
+            Some(Rc::new(fibex)),
+            StorageHeaderMode::Absent,
+        ) {
+            Ok((_, ParsedMessage::Item(parsed))) => parsed,
+            res => panic!("could not parse message: {:?}", res),
+        }
+    }
+
+    fn speed_and_heading(speed: i64, heading: i64) -> Vec<u8> {
+        let mut data = speed.to_le_bytes().to_vec();
+        data.extend(&heading.to_le_bytes());
+        data
+    }
+
     #[test]
     fn test_fibex_parsing() {
-        let fibex = read_fibexes(vec![
-            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/dlt-messages.xml")
-        ])
-        .expect("can't parse fibex");
+        let fibex = read_fibexes(vec![fibex_path()]).expect("can't parse fibex");
         println!("{:?}", fibex);
     }
+
+    #[test]
+    fn test_non_verbose_arguments_from_fibex() {
+        let fibex = read_fibexes(vec![fibex_path()]).expect("can't parse fibex");
+        let msg = parse_non_verbose(fibex, 64, speed_and_heading(-5, 90));
+        assert_eq!(
+            Some(vec![
+                string_argument("direction"),
+                string_argument("speed: "),
+                i64_argument(-5),
+                string_argument("heading: "),
+                i64_argument(90),
+            ]),
+            msg.non_verbose_arguments()
+        );
+        let record = msg.to_record();
+        assert_eq!(Some(64), record.message_id);
+        assert_eq!(
+            vec![
+                (None, Value::StringVal("direction".to_string())),
+                (None, Value::StringVal("speed: ".to_string())),
+                (None, Value::I64(-5)),
+                (None, Value::StringVal("heading: ".to_string())),
+                (None, Value::I64(90)),
+            ],
+            record.arguments
+        );
+    }
+
+    #[test]
+    fn test_non_verbose_unknown_message_id() {
+        let fibex = read_fibexes(vec![fibex_path()]).expect("can't parse fibex");
+        let data = vec![0x1, 0x2, 0x3];
+        let msg = parse_non_verbose(fibex, 4711, data.clone());
+        assert_eq!(Some(vec![raw_argument(&data)]), msg.non_verbose_arguments());
+    }
+
+    #[test]
+    fn test_non_verbose_data_too_short_for_frame() {
+        let fibex = read_fibexes(vec![fibex_path()]).expect("can't parse fibex");
+        let msg = parse_non_verbose(fibex, 64, vec![0x1, 0x2, 0x3, 0x4]);
+        assert_eq!(
+            Some(vec![
+                string_argument("direction"),
+                string_argument("speed: "),
+                raw_argument(&[0x1, 0x2, 0x3, 0x4]),
+            ]),
+            msg.non_verbose_arguments()
+        );
+    }
+
+    #[test]
+    fn test_overlapping_fibexes() {
+        let short_name = |fibex: &FibexMetadata, message_id| {
+            fibex
+                .frame_for(Some("DR"), Some("CTX1"), message_id)
+                .map(|frame| frame.short_name.clone())
+        };
+        let first_read = read_fibexes(vec![fibex_path(), overlapping_fibex_path()])
+            .expect("can't parse fibexes");
+        assert_eq!(Some("direction".to_string()), short_name(&first_read, 64));
+        assert_eq!(Some("timeing: ".to_string()), short_name(&first_read, 65));
+        assert_eq!(Some("odometer".to_string()), short_name(&first_read, 66));
+        let overlapping_read = read_fibexes(vec![overlapping_fibex_path(), fibex_path()])
+            .expect("can't parse fibexes");
+        assert_eq!(
+            Some("velocity".to_string()),
+            short_name(&overlapping_read, 64)
+        );
+        assert_eq!(
+            Some("timeing: ".to_string()),
+            short_name(&overlapping_read, 65)
+        );
+        // the frame of the file read first is used to decode the payload
+        let msg = parse_non_verbose(overlapping_read, 64, 42u32.to_le_bytes().to_vec());
+        assert_eq!(
+            Some(vec![
+                string_argument("velocity: "),
+                Argument {
+                    type_info: TypeInfo {
+                        kind: TypeInfoKind::Unsigned(TypeLength::BitLength32),
+                        coding: StringCoding::ASCII,
+                        has_variable_info: false,
+                        has_trace_info: false,
+                        unmodelled_bits: 0,
+                    },
+                    name: None,
+                    unit: None,
+                    fixed_point: None,
+                    value: Value::U32(42),
+                },
+            ]),
+            msg.non_verbose_arguments()
+        );
+        // frames can also be found without app-id and context-id
+        assert_eq!(
+            first_read.frame_for(Some("DR"), Some("CTX1"), 65),
+            first_read.frame_for(None, None, 65)
+        );
+        assert!(first_read.frame_for(None, None, 67).is_none());
+    }
 }
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use crate::filtering::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn dlf(filters: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><dltfilter>{}</dltfilter>"#,
+            filters
+        )
+    }
+
+    fn app_filter(filter_type: u8, app_id: &str, enabled: bool) -> String {
+        format!(
+            "<filter><type>{}</type><applicationid>{}</applicationid>\
+             <enableapplicationid>1</enableapplicationid>\
+             <enablefilter>{}</enablefilter></filter>",
+            filter_type, app_id, enabled as u8
+        )
+    }
+
+    #[test]
+    fn test_import_dlf_file() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/filters.dlf");
+        let cfg = DltFilterConfig::from_dlf_file(path).expect("could not read filters");
+        assert_eq!(Some(4), cfg.min_log_level);
+        assert_eq!(Some(vec!["ECU1".to_string()]), cfg.ecu_ids);
+        assert_eq!(Some(vec!["ENG".to_string()]), cfg.app_ids);
+        assert_eq!(
+            Some(vec!["CTX1".to_string(), "CTX2".to_string()]),
+            cfg.context_ids
+        );
+        // the plain text is escaped to be combined with the regex of the other filter
+        assert_eq!(
+            Some(vec!["error".to_string(), "time[o]ut & retry".to_string()]),
+            cfg.payload_regex
+        );
+        assert_eq!(None, cfg.payload_contains);
+        assert_eq!(
+            Some(vec![DltExcludeFilter {
+                app_id: Some("HB".to_string()),
+                ..Default::default()
+            }]),
+            cfg.excludes
+        );
+    }
+
+    #[test]
+    fn test_dlf_payload_filters() {
+        let payload_filter = |text: &str, regex: bool, ignore_case: bool| {
+            format!(
+                "<filter><type>0</type><payloadtext>{}</payloadtext>\
+                 <enablepayloadtext>1</enablepayloadtext>\
+                 <enableRegexp_Payload>{}</enableRegexp_Payload>\
+                 <ignoreCase_Payload>{}</ignoreCase_Payload>\
+                 <enablefilter>1</enablefilter></filter>",
+                text, regex as u8, ignore_case as u8
+            )
+        };
+        let contains = dlf(&format!(
+            "{}{}",
+            payload_filter("timeout", false, false),
+            payload_filter("ERROR", false, false)
+        ));
+        let cfg = DltFilterConfig::from_dlf(contains.as_bytes()).expect("could not read filters");
+        assert_eq!(
+            Some(vec!["timeout".to_string(), "ERROR".to_string()]),
+            cfg.payload_contains
+        );
+        assert_eq!(None, cfg.payload_regex);
+        assert_eq!(None, cfg.app_ids);
+
+        let regex = dlf(&format!(
+            "{}{}",
+            payload_filter("time[o]ut", true, false),
+            payload_filter("a.b", false, true)
+        ));
+        let cfg = DltFilterConfig::from_dlf(regex.as_bytes()).expect("could not read filters");
+        assert_eq!(
+            Some(vec!["time[o]ut".to_string(), r"(?i)a\.b".to_string()]),
+            cfg.payload_regex
+        );
+        assert_eq!(None, cfg.payload_contains);
+
+        let mixed = dlf(&format!(
+            "{}{}{}",
+            payload_filter("a.b", false, false),
+            payload_filter("time[o]ut", true, false),
+            payload_filter("x", false, true),
+        ));
+        let cfg = DltFilterConfig::from_dlf(mixed.as_bytes()).expect("could not read filters");
+        assert_eq!(
+            Some(vec![
+                r"a\.b".to_string(),
+                "time[o]ut".to_string(),
+                "(?i)x".to_string()
+            ]),
+            cfg.payload_regex
+        );
+        assert_eq!(None, cfg.payload_contains);
+    }
+
+    #[test]
+    fn test_dlf_filter_types() {
+        let exclude_app = |app_id: &str| DltExcludeFilter {
+            app_id: Some(app_id.to_string()),
+            ..Default::default()
+        };
+        let only_negative = dlf(&app_filter(1, "APP1", true));
+        let cfg =
+            DltFilterConfig::from_dlf(only_negative.as_bytes()).expect("could not read filters");
+        assert_eq!(None, cfg.app_ids);
+        assert_eq!(Some(vec![exclude_app("APP1")]), cfg.excludes);
+
+        let mixed = dlf(&format!(
+            "{}{}{}{}",
+            app_filter(0, "APP1", true),
+            app_filter(0, "APP2", false),
+            app_filter(1, "APP3", true),
+            app_filter(2, "APP4", true)
+        ));
+        let cfg = DltFilterConfig::from_dlf(mixed.as_bytes()).expect("could not read filters");
+        assert_eq!(Some(vec!["APP1".to_string()]), cfg.app_ids);
+        assert_eq!(None, cfg.ecu_ids);
+        assert_eq!(None, cfg.min_log_level);
+        assert_eq!(Some(vec![exclude_app("APP3")]), cfg.excludes);
+
+        // leaving out a criteria would make a negative filter hide more messages
+        let negative_with_header_text = dlf(
+            "<filter><type>1</type><name>header</name><applicationid>APP1</applicationid>\
+             <enableapplicationid>1</enableapplicationid><headertext>x</headertext>\
+             <enableheadertext>1</enableheadertext><enablefilter>1</enablefilter></filter>",
+        );
+        let err = DltFilterConfig::from_dlf(negative_with_header_text.as_bytes())
+            .expect_err("negative filter should not be imported");
+        assert!(err.to_string().contains("header text"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_dlf() {
+        assert!(DltFilterConfig::from_dlf(&b"<dltfilter><filter></dltfilter>"[..]).is_err());
+    }
+}
@@ -1,6 +1,20 @@
 #[macro_use]
 mod dlt_tests;
+mod dlt_builder_tests;
+mod dlt_control_tests;
+#[cfg(feature = "std")]
+mod dlt_dedup_tests;
+#[cfg(feature = "std")]
 mod dlt_file_tests;
+mod dlt_mmap_tests;
+#[cfg(feature = "std")]
 mod dlt_net_tests;
 mod dlt_parse_tests;
+mod dlt_segments_tests;
+#[cfg(feature = "std")]
+mod dlt_stream_tests;
+#[cfg(feature = "std")]
 mod fibex_tests;
+#[cfg(feature = "std")]
+mod filtering_tests;
+mod serde_tests;
@@ -0,0 +1,105 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use crate::{dlt::*, dlt_parse::*, proptest_strategies::*};
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    fn json_roundtrip<T>(v: &T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let json = serde_json::to_string(v).expect("could not serialize");
+        serde_json::from_str(&json).expect("could not deserialize")
+    }
+
+    // messages are only checked with fixed content since older serde_json
+    // versions do not guarantee that every f64 survives the text representation
+    proptest! {
+        #[test]
+        fn test_storage_header_json_roundtrip(header: StorageHeader) {
+            assert_eq!(header, json_roundtrip(&header));
+        }
+        #[test]
+        fn test_standard_header_json_roundtrip(header in header_strategy(4, Endianness::Little)) {
+            assert_eq!(header, json_roundtrip(&header));
+        }
+        #[test]
+        fn test_extended_header_json_roundtrip(header: ExtendedHeader) {
+            assert_eq!(header, json_roundtrip(&header));
+        }
+    }
+
+    #[test]
+    fn test_non_verbose_and_control_json_roundtrip() {
+        for payload_content in &[
+            PayloadContent::NonVerbose(0x1234, vec![1, 2, 3]),
+            PayloadContent::ControlMsg(ControlType::Response, vec![0x11, 0]),
+        ] {
+            let msg = Message::new(
+                MessageConfig {
+                    version: 1,
+                    counter: 7,
+                    endianness: Endianness::Big,
+                    ecu_id: Some("ECU1".to_string()),
+                    session_id: Some(3),
+                    timestamp: Some(1_000_000),
+                    payload: Payload2 {
+                        payload_content: payload_content.clone(),
+                    },
+                    extended_header_info: Some(ExtendedHeaderConfig {
+                        message_type: MessageType::Control(ControlType::Response),
+                        app_id: "APP".to_string(),
+                        context_id: "CTX".to_string(),
+                    }),
+                },
+                None,
+                None,
+            )
+            .add_storage_header(Some(DltTimeStamp {
+                seconds: 1_587_000_000,
+                microseconds: 999_999,
+            }));
+            assert_eq!(msg, json_roundtrip(&msg));
+        }
+    }
+
+    #[test]
+    fn test_messages_of_dlt_file_json_roundtrip() {
+        let bytes = std::fs::read("test_samples/testfile.dlt").expect("could not read sample");
+        let mut input = &bytes[..];
+        while !input.is_empty() {
+            match dlt_message(input, None, 0, None, None, StorageHeaderMode::Required) {
+                Ok((rest, ParsedMessage::Item(msg))) => {
+                    assert_eq!(msg, json_roundtrip(&msg));
+                    input = rest;
+                }
+                res => panic!("could not parse message: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_shape() {
+        assert_eq!(
+            r#"{"U32":42}"#,
+            serde_json::to_string(&Value::U32(42)).unwrap()
+        );
+        assert_eq!(
+            r#"{"Raw":[1,2]}"#,
+            serde_json::to_string(&Value::Raw(vec![1, 2])).unwrap()
+        );
+        assert_eq!(r#""Big""#, serde_json::to_string(&Endianness::Big).unwrap());
+        assert_eq!(
+            r#"{"Log":"Warn"}"#,
+            serde_json::to_string(&MessageType::Log(LogLevel::Warn)).unwrap()
+        );
+        assert_eq!(
+            r#"{"seconds":1,"microseconds":2}"#,
+            serde_json::to_string(&DltTimeStamp {
+                seconds: 1,
+                microseconds: 2
+            })
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Stand-ins for the types that need `std`
+//!
+//! Filtering, FIBEX metadata and update channels are only available with
+//! the `std` feature. Without it the parse functions keep their parameters
+//! so calls look the same in both builds, but these can only be `None`.
+use alloc::string::String;
+use core::{convert::Infallible, marker::PhantomData};
+
+/// a filter config cannot be created without `std`
+pub enum ProcessedDltFilterConfig {}
+
+impl ProcessedDltFilterConfig {
+    pub fn matches(
+        &self,
+        _ecu_id: Option<&str>,
+        _app_id: Option<&str>,
+        _context_id: Option<&str>,
+        _message_type: Option<&crate::dlt::MessageType>,
+    ) -> bool {
+        match *self {}
+    }
+
+    pub fn matches_time(&self, _timestamp: Option<&crate::dlt::DltTimeStamp>) -> bool {
+        match *self {}
+    }
+
+    pub fn has_payload_criteria(&self) -> bool {
+        match *self {}
+    }
+
+    pub fn matches_payload(&self, _payload: &str) -> bool {
+        match *self {}
+    }
+}
+
+/// FIBEX files cannot be read without `std`
+#[derive(Debug, PartialEq, Clone)]
+pub enum FibexMetadata {}
+
+impl FibexMetadata {
+    pub(crate) fn unavailable(&self) -> ! {
+        match *self {}
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq)]
+pub enum Severity {
+    WARNING,
+    ERROR,
+}
+
+pub struct Notification {
+    pub severity: Severity,
+    pub content: String,
+    pub line: Option<usize>,
+}
+
+pub type IndexingResults<T> = Result<T, Notification>;
+pub type ChunkResults = IndexingResults<()>;
+
+/// there are no channels without `std`
+pub mod cc {
+    use super::*;
+
+    pub struct Sender<T> {
+        never: Infallible,
+        marker: PhantomData<T>,
+    }
+
+    impl<T> Sender<T> {
+        pub fn send(&self, _msg: T) -> Result<(), T> {
+            match self.never {}
+        }
+    }
+}
+
+pub fn report_warning<S: AsRef<str>>(text: S) {
+    warn!("{}", text.as_ref());
+}
+
+pub fn report_error<S: AsRef<str>>(text: S) {
+    error!("{}", text.as_ref());
+}
@@ -24,7 +24,9 @@ use async_std::task;
 use crossbeam_channel as cc;
 use crossbeam_channel::unbounded;
 use dlt::{
-    dlt_file::export_as_dlt_file, dlt_parse::StatisticsResults, dlt_pcap::convert_to_dlt_file,
+    dlt_file::export_as_dlt_file,
+    dlt_parse::{ParseOptions, StatisticsResults, StorageHeaderMode},
+    dlt_pcap::convert_to_dlt_file,
     fibex::FibexMetadata,
 };
 use indexer_base::{
@@ -767,8 +769,14 @@ fn main() {
             let ending = &file_path.extension().expect("could not get extension");
             if ending.to_str() == Some("dlt") {
                 trace!("was dlt file");
-                export_as_dlt_file(file_path, out_path, SectionConfig { sections }, tx)
-                    .expect("export did not work");
+                export_as_dlt_file(
+                    file_path,
+                    out_path,
+                    SectionConfig { sections },
+                    ParseOptions::new(StorageHeaderMode::Required),
+                    tx,
+                )
+                .expect("export did not work");
             } else {
                 trace!("was regular file");
                 export_file_line_based(
@@ -851,6 +859,7 @@ fn main() {
                     //     components: None,
                     // },
                     load_test_fibex(),
+                    ParseOptions::new(StorageHeaderMode::Required),
                 ) {
                     report_error(format!("couldn't process: {}", why));
                     std::process::exit(2)